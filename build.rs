@@ -1,8 +1,39 @@
 // Build script to extract configuration from Trunk.toml
 // This runs at compile time to generate constants for the application
 
+use std::collections::HashMap;
+use std::env;
 use std::fs;
 use std::path::Path;
+use syntect::dumps::{dump_to_file, dump_to_uncompressed_file};
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::{SyntaxSet, SyntaxSetBuilder};
+
+/// Syntaxes selected by each `syntax-*` cargo feature
+///
+/// Syntaxes they embed (e.g. CSS and JavaScript inside HTML) are added
+/// automatically by `generate_syntax_dump`.
+const SYNTAX_FEATURES: &[(&str, &[&str])] = &[
+    ("RUST", &["Rust"]),
+    ("JAVASCRIPT", &["JavaScript"]),
+    ("PYTHON", &["Python"]),
+    ("SHELL", &["Bourne Again Shell (bash)"]),
+    ("HTML", &["HTML"]),
+    ("CSS", &["CSS"]),
+    ("JSON", &["JSON"]),
+    ("YAML", &["YAML"]),
+    ("MARKDOWN", &["Markdown", "MultiMarkdown"]),
+    ("XML", &["XML"]),
+    ("SQL", &["SQL"]),
+    ("C", &["C"]),
+    ("CPP", &["C++"]),
+    ("JAVA", &["Java"]),
+    ("GO", &["Go"]),
+    ("PHP", &["PHP"]),
+    ("RUBY", &["Ruby"]),
+    ("CSHARP", &["C#"]),
+    ("DIFF", &["Diff"]),
+];
 
 fn main() {
     // Tell Cargo to re-run this build script if Trunk.toml changes
@@ -34,4 +65,199 @@ fn main() {
 
     // Set environment variable for the config module
     println!("cargo:rustc-env=TRUNK_PUBLIC_URL={}", public_url);
-}
\ No newline at end of file
+
+    generate_bundled_themes();
+    generate_syntax_dump();
+    generate_theme_dump();
+}
+
+/// Dump the syntaxes selected by `syntax-*` features to OUT_DIR/syntaxes.packdump
+///
+/// `syntax-all` keeps every syntect default syntax. Plain Text is always kept
+/// as the fallback for unknown languages.
+fn generate_syntax_dump() {
+    let keep_all = env::var_os("CARGO_FEATURE_SYNTAX_ALL").is_some();
+    let mut selected = vec!["Plain Text"];
+    for (feature, names) in SYNTAX_FEATURES {
+        if env::var_os(format!("CARGO_FEATURE_SYNTAX_{}", feature)).is_some() {
+            selected.extend_from_slice(names);
+        }
+    }
+
+    // The default set is already linked: contexts refer to each other by
+    // syntax index, so the contexts are handled in serialized form to find
+    // embedded syntaxes and to renumber them for the filtered set. syntect
+    // has no public API for this, and the serialized form is not stable
+    // across its releases, hence the exact version pin in Cargo.toml (see
+    // `visit_context_ids`).
+    let syntaxes = SyntaxSet::load_defaults_newlines()
+        .into_builder()
+        .syntaxes()
+        .to_vec();
+    let mut contexts: Vec<Vec<(String, serde_json::Value)>> = syntaxes
+        .iter()
+        .map(|syntax| {
+            syntax
+                .contexts
+                .iter()
+                .map(|(name, context)| {
+                    let value = serde_json::to_value(context).expect("context serializes");
+                    (name.clone(), value)
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut kept: Vec<usize> = (0..syntaxes.len())
+        .filter(|&index| keep_all || selected.contains(&syntaxes[index].name.as_str()))
+        .collect();
+    let mut pending = kept.clone();
+    while let Some(index) = pending.pop() {
+        let mut referenced = Vec::new();
+        for (_, value) in &mut contexts[index] {
+            visit_context_ids(value, &mut |syntax_index| {
+                referenced.push(*syntax_index);
+            });
+        }
+        for syntax_index in referenced {
+            if !kept.contains(&syntax_index) {
+                kept.push(syntax_index);
+                pending.push(syntax_index);
+            }
+        }
+    }
+    kept.sort_unstable();
+
+    let new_index: HashMap<usize, usize> = kept
+        .iter()
+        .enumerate()
+        .map(|(new, &old)| (old, new))
+        .collect();
+
+    let mut builder = SyntaxSetBuilder::new();
+    for &old in &kept {
+        let mut syntax = syntaxes[old].clone();
+        for (name, mut value) in std::mem::take(&mut contexts[old]) {
+            visit_context_ids(&mut value, &mut |syntax_index| {
+                *syntax_index = new_index[syntax_index];
+            });
+            syntax.contexts.insert(
+                name,
+                serde_json::from_value(value).expect("context deserializes"),
+            );
+        }
+        builder.add(syntax);
+    }
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    dump_to_uncompressed_file(
+        &builder.build(),
+        Path::new(&out_dir).join("syntaxes.packdump"),
+    )
+    .expect("failed to dump syntaxes");
+}
+
+/// Call `visit` with the syntax index of every serialized `ContextId` in a context
+///
+/// This relies on syntect's serde layout, where a `ContextId` is an object
+/// with exactly `syntax_index` and `context_index`. That layout is internal
+/// to syntect, which is why Cargo.toml pins its exact version; check this
+/// walk still finds every reference before upgrading.
+fn visit_context_ids(value: &mut serde_json::Value, visit: &mut impl FnMut(&mut usize)) {
+    match value {
+        serde_json::Value::Object(map)
+            if map.len() == 2
+                && map.contains_key("syntax_index")
+                && map.contains_key("context_index") =>
+        {
+            let mut syntax_index = map["syntax_index"]
+                .as_u64()
+                .expect("syntax_index is an integer") as usize;
+            visit(&mut syntax_index);
+            map.insert("syntax_index".to_string(), syntax_index.into());
+        }
+        serde_json::Value::Object(map) => map
+            .values_mut()
+            .for_each(|child| visit_context_ids(child, visit)),
+        serde_json::Value::Array(items) => items
+            .iter_mut()
+            .for_each(|child| visit_context_ids(child, visit)),
+        _ => {}
+    }
+}
+
+/// Dump syntect's default themes plus data/themes/*.tmTheme to OUT_DIR/themes.themedump
+fn generate_theme_dump() {
+    let mut theme_set = ThemeSet::load_defaults();
+
+    if let Ok(entries) = fs::read_dir(Path::new("data/themes")) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("tmTheme") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            match ThemeSet::get_theme(&path) {
+                Ok(theme) => {
+                    theme_set.themes.insert(name.to_string(), theme);
+                }
+                Err(error) => println!(
+                    "cargo:warning=skipping invalid theme {}: {}",
+                    path.display(),
+                    error
+                ),
+            }
+        }
+    }
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    dump_to_file(&theme_set, Path::new(&out_dir).join("themes.themedump"))
+        .expect("failed to dump themes");
+}
+
+/// Embed every data/themes/*.json file so themes are available without fetching
+///
+/// Writes `themes.rs` to OUT_DIR containing a `BUNDLED_THEMES` table of
+/// (theme name, JSON source) pairs, sorted by name.
+fn generate_bundled_themes() {
+    println!("cargo:rerun-if-changed=data/themes");
+
+    let source = bundled_files_table("BUNDLED_THEMES", "json");
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    fs::write(Path::new(&out_dir).join("themes.rs"), source).expect("failed to write themes.rs");
+}
+
+/// Generate a `const` table embedding data/themes files with the given extension
+fn bundled_files_table(const_name: &str, extension: &str) -> String {
+    let mut files = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(Path::new("data/themes")) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(extension) {
+                continue;
+            }
+            if let (Some(name), Ok(absolute)) = (
+                path.file_stem().and_then(|stem| stem.to_str()),
+                fs::canonicalize(&path),
+            ) {
+                files.push((name.to_string(), absolute));
+            }
+        }
+    }
+    files.sort();
+
+    let mut source = format!("pub const {}: &[(&str, &str)] = &[\n", const_name);
+    for (name, path) in &files {
+        source.push_str(&format!(
+            "    ({:?}, include_str!({:?})),\n",
+            name,
+            path.display().to_string()
+        ));
+    }
+    source.push_str("];\n");
+    source
+}
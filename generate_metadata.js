@@ -4,6 +4,13 @@
 const fs = require('fs');
 const path = require('path');
 
+// Search index written into the data directory, fetched by the `search` command
+const SEARCH_INDEX_FILE = 'search_index.json';
+const SEARCH_INDEX_VERSION = 1;
+
+// Relevance of a term in each field, relative to the body
+const FIELD_WEIGHTS = { title: 3, tags: 3, description: 2, body: 1 };
+
 // Parse markdown frontmatter metadata
 function parseMarkdownMetadata(content) {
   const metadata = {};
@@ -73,6 +80,9 @@ function generateMetadata(dirPath, relativePath = '') {
     const items = fs.readdirSync(dirPath);
     
     for (const item of items) {
+      // The search index is generated output, not content
+      if (!relativePath && item === SEARCH_INDEX_FILE) continue;
+
       const itemPath = path.join(dirPath, item);
       const itemRelativePath = relativePath ? `${relativePath}/${item}` : item;
       const stats = fs.statSync(itemPath);
@@ -107,6 +117,147 @@ function generateMetadata(dirPath, relativePath = '') {
   return metadata;
 }
 
+// Chinese, Japanese and Korean characters; must match `is_cjk` in src/utils/search.rs
+function isCjk(char) {
+  const code = char.codePointAt(0);
+  return (code >= 0x3040 && code <= 0x30ff) ||
+    (code >= 0x3400 && code <= 0x4dbf) ||
+    (code >= 0x4e00 && code <= 0x9fff) ||
+    (code >= 0xac00 && code <= 0xd7af) ||
+    (code >= 0xf900 && code <= 0xfaff) ||
+    (code >= 0x20000 && code <= 0x2ffff);
+}
+
+// Split text into lowercase search terms; must match `tokenize` in src/utils/search.rs
+// Letter and digit runs are words, CJK runs become overlapping bigrams
+// (a lone CJK character is its own term).
+function tokenize(text) {
+  const tokens = [];
+  let word = '';
+  let cjkRun = [];
+
+  const flushWord = () => {
+    if (word) tokens.push(word);
+    word = '';
+  };
+  const flushCjk = () => {
+    if (cjkRun.length === 1) {
+      tokens.push(cjkRun[0]);
+    } else {
+      for (let i = 0; i + 1 < cjkRun.length; i++) {
+        tokens.push(cjkRun[i] + cjkRun[i + 1]);
+      }
+    }
+    cjkRun = [];
+  };
+
+  for (const char of text.toLowerCase()) {
+    if (isCjk(char)) {
+      flushWord();
+      cjkRun.push(char);
+    } else if (/[\p{L}\p{N}]/u.test(char)) {
+      flushCjk();
+      word += char;
+    } else {
+      flushWord();
+      flushCjk();
+    }
+  }
+  flushWord();
+  flushCjk();
+  return tokens;
+}
+
+// Markdown reduced to the text a reader sees, for indexing and snippets
+function markdownToText(content) {
+  return stripFrontmatter(content)
+    .replace(/^\s*(```|~~~).*$/gm, '')
+    .replace(/\{\{#[^}]*\}\}/g, ' ')
+    .replace(/<[^>]+>/g, ' ')
+    .replace(/!\[([^\]]*)\]\([^)]*\)/g, '$1')
+    .replace(/\[([^\]]*)\]\([^)]*\)/g, '$1')
+    .replace(/^\s{0,3}(#{1,6}|>|[-*+]|\d+\.)\s+/gm, '')
+    // Emphasis and code markers only; underscores inside words like file_name stay
+    .replace(/(?<![\p{L}\p{N}])[*_`~]+|[*_`~]+(?![\p{L}\p{N}])/gu, '')
+    .replace(/\s+/g, ' ')
+    .trim();
+}
+
+function stripFrontmatter(content) {
+  const delimiter = content.startsWith('---\n') ? '---' : content.startsWith('--\n') ? '--' : null;
+  if (!delimiter) return content;
+  const lines = content.split('\n');
+  const end = lines.findIndex((line, i) => i > 0 && line.trim() === delimiter);
+  return end === -1 ? content : lines.slice(end + 1).join('\n');
+}
+
+// Collect every markdown file under the data directory
+function collectMarkdownFiles(dirPath, relativePath = '', files = []) {
+  for (const item of fs.readdirSync(dirPath).sort()) {
+    const itemPath = path.join(dirPath, item);
+    const itemRelativePath = relativePath ? `${relativePath}/${item}` : item;
+    if (fs.statSync(itemPath).isDirectory()) {
+      collectMarkdownFiles(itemPath, itemRelativePath, files);
+    } else if (path.extname(item).toLowerCase() === '.md') {
+      files.push({ path: itemRelativePath, fullPath: itemPath });
+    }
+  }
+  return files;
+}
+
+// Inverted index of titles, descriptions, tags and body text
+//
+// `terms` maps each term to `[document, weighted term frequency]` postings;
+// `length` is a document's weighted term count, used by BM25 length
+// normalisation.
+function buildSearchIndex(dataDir) {
+  const docs = [];
+  const terms = {};
+
+  for (const file of collectMarkdownFiles(dataDir)) {
+    const content = fs.readFileSync(file.fullPath, 'utf-8');
+    const metadata = parseMarkdownMetadata(content);
+    const text = markdownToText(content);
+    const fields = {
+      title: metadata.title || '',
+      description: metadata.description || '',
+      tags: (metadata.tags || []).join(' '),
+      body: text,
+    };
+
+    const docIndex = docs.length;
+    const frequencies = {};
+    let length = 0;
+    for (const [field, value] of Object.entries(fields)) {
+      for (const token of tokenize(value)) {
+        frequencies[token] = (frequencies[token] || 0) + FIELD_WEIGHTS[field];
+        length += FIELD_WEIGHTS[field];
+      }
+    }
+    for (const [token, frequency] of Object.entries(frequencies)) {
+      (terms[token] = terms[token] || []).push([docIndex, frequency]);
+    }
+
+    docs.push({
+      path: file.path,
+      title: metadata.title || path.basename(file.path),
+      description: metadata.description || null,
+      tags: metadata.tags || [],
+      length,
+      text,
+    });
+  }
+
+  return { version: SEARCH_INDEX_VERSION, docs, terms };
+}
+
+function writeSearchIndex(dataDir = path.join(__dirname, 'data')) {
+  const index = buildSearchIndex(dataDir);
+  const outputFile = path.join(dataDir, SEARCH_INDEX_FILE);
+  fs.writeFileSync(outputFile, JSON.stringify(index));
+  console.log(`Search index generated: ${outputFile} (${index.docs.length} documents, ${Object.keys(index.terms).length} terms)`);
+}
+
 function main() {
   const dataDir = path.join(__dirname, 'data');
   const outputFile = path.join(__dirname, 'src', 'filesystem_metadata.json');
@@ -130,8 +281,12 @@ function main() {
   
   console.log(`Metadata generated successfully: ${outputFile}`);
   console.log(`Found ${Object.keys(metadata.children).length} items in root directory`);
+
+  writeSearchIndex(dataDir);
 }
 
 if (require.main === module) {
   main();
-}
\ No newline at end of file
+}
+
+module.exports = { buildSearchIndex, tokenize, writeSearchIndex };
\ No newline at end of file
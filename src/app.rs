@@ -4,6 +4,8 @@
 
 use crate::components::Terminal;
 use crate::hooks::use_app_config;
+use crate::utils::AppConfigService;
+use wasm_bindgen::prelude::*;
 use web_sys::window;
 use yew::prelude::*;
 
@@ -17,21 +19,38 @@ pub fn app() -> Html {
     let is_center_layout = app_config.config.layout.align == "center";
 
     // Apply theme to body element on component mount
-    let theme_class = app_config.get_theme_class();
-
     {
-        let theme_class = theme_class.to_string();
-        use_effect_with(theme_class.to_string().clone(), move |_| {
-            window()
-                .unwrap()
-                .document()
-                .unwrap()
-                .body()
-                .unwrap()
-                .set_class_name(&theme_class);
+        let app_config = (*app_config).clone();
+        use_effect_with(app_config.get_current_theme(), move |_| {
+            app_config.apply_theme_to_dom();
         });
     }
 
+    // Re-apply the theme when the system color scheme changes. The selection is
+    // re-read on every change, since `theme switch` runs in the terminal's own state.
+    use_effect_with((), |_| {
+        let query = window()
+            .and_then(|window| window.match_media("(prefers-color-scheme: dark)").ok())
+            .flatten();
+        let on_change = Closure::<dyn Fn()>::new(|| {
+            let app_config = AppConfigService::new();
+            if app_config.is_following_system() {
+                app_config.apply_theme_to_dom();
+            }
+        });
+
+        if let Some(query) = &query {
+            query.set_onchange(Some(on_change.as_ref().unchecked_ref()));
+        }
+
+        move || {
+            if let Some(query) = &query {
+                query.set_onchange(None);
+            }
+            drop(on_change);
+        }
+    });
+
     let terminal_class = if is_center_layout {
         "w-full max-w-[65ch]"
     } else {
@@ -39,7 +58,7 @@ pub fn app() -> Html {
     };
 
     html! {
-        <div class={vec![format!("w-full h-screen bg-terminal-bg overflow-y-scroll {} {}", theme_class, if is_center_layout {"flex justify-center"} else {""}), ]}>
+        <div class={vec![format!("w-full h-screen bg-terminal-bg overflow-y-scroll {}", if is_center_layout {"flex justify-center"} else {""}), ]}>
             <div class={terminal_class}>
                 <Terminal />
             </div>
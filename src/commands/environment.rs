@@ -0,0 +1,49 @@
+// Shell Environment
+// Session-scoped variables and exit status shared between commands and the prompt
+
+use std::collections::BTreeMap;
+
+/// Shell environment holding exported variables and the last exit status
+///
+/// A single instance is shared (via `Rc<RefCell<_>>`) between the command
+/// executor, the `export` command and the prompt renderer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Environment {
+    vars: BTreeMap<String, String>,
+    last_status: i32,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the value of a variable, if it has been exported
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(|value| value.as_str())
+    }
+
+    /// Set (or overwrite) a variable
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.vars.insert(name.to_string(), value.to_string());
+    }
+
+    /// Remove a variable
+    pub fn unset(&mut self, name: &str) {
+        self.vars.remove(name);
+    }
+
+    /// Iterate over all variables in name order
+    pub fn vars(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.vars.iter()
+    }
+
+    /// Exit status of the last command run from the prompt (0 = success)
+    pub fn last_status(&self) -> i32 {
+        self.last_status
+    }
+
+    pub fn set_last_status(&mut self, status: i32) {
+        self.last_status = status;
+    }
+}
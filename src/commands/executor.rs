@@ -1,11 +1,15 @@
 // Command Executor Implementation
 // Main command executor that manages and executes terminal commands
 
+use crate::filesystem::types::ContentSource;
 use crate::filesystem::FileSystem;
+use crate::utils::file_utils::run_block::discard_outputs;
+use crate::utils::AppConfigService;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+use super::environment::Environment;
 use super::filesystem::*;
 use super::system::*;
 use super::types::*;
@@ -15,15 +19,19 @@ use super::utility::*;
 ///
 /// This struct maintains a registry of available commands and handles
 /// parsing input strings and routing them to the appropriate command implementations.
+///
+/// Clones share the same command registry, filesystem and environment, so a
+/// clone handed to a component or an async future sees the same session.
+#[derive(Clone)]
 pub struct CommandExecutor {
     /// Map of command names to their implementations
-    pub commands: HashMap<String, Box<dyn Command>>,
-}
-
-impl Clone for CommandExecutor {
-    fn clone(&self) -> Self {
-        CommandExecutor::new()
-    }
+    pub commands: Rc<HashMap<String, Box<dyn Command>>>,
+    /// Filesystem shared by all filesystem commands
+    pub filesystem: Rc<RefCell<FileSystem>>,
+    /// Exported variables and last exit status
+    pub environment: Rc<RefCell<Environment>>,
+    /// Opens the full-screen editor, once the terminal has set it
+    pub editor: Rc<RefCell<Option<EditorOpener>>>,
 }
 
 impl PartialEq for CommandExecutor {
@@ -44,8 +52,10 @@ impl CommandExecutor {
     pub fn new() -> Self {
         let mut commands: HashMap<String, Box<dyn Command>> = HashMap::new();
 
-        // Create shared filesystem instance
+        // Create shared filesystem and environment instances
         let filesystem = Rc::new(RefCell::new(FileSystem::new()));
+        let environment = Rc::new(RefCell::new(Environment::new()));
+        let editor = Rc::new(RefCell::new(None));
 
         // Register system commands
         commands.insert("help".to_string(), Box::new(HelpCommand));
@@ -53,6 +63,9 @@ impl CommandExecutor {
         commands.insert("clear".to_string(), Box::new(ClearCommand));
         commands.insert("theme".to_string(), Box::new(ThemeCommand));
         commands.insert("history_push".to_string(), Box::new(HistoryPushCommand));
+        commands.insert("export".to_string(), Box::new(ExportCommand {
+            environment: environment.clone(),
+        }));
 
         // Register utility commands
         commands.insert("echo".to_string(), Box::new(EchoCommand));
@@ -78,8 +91,77 @@ impl CommandExecutor {
         commands.insert("view".to_string(), Box::new(ViewCommand {
             filesystem: filesystem.clone(),
         }));
+        commands.insert("headings".to_string(), Box::new(HeadingsCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("find".to_string(), Box::new(FindCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("grep".to_string(), Box::new(GrepCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("search".to_string(), Box::new(SearchCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("tree".to_string(), Box::new(TreeCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("stat".to_string(), Box::new(StatCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("file".to_string(), Box::new(FileCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("head".to_string(), Box::new(HeadCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("tail".to_string(), Box::new(TailCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("wc".to_string(), Box::new(WcCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("touch".to_string(), Box::new(TouchCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("mkdir".to_string(), Box::new(MkdirCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("rm".to_string(), Box::new(RmCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("mv".to_string(), Box::new(MvCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("cp".to_string(), Box::new(CpCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("reset-fs".to_string(), Box::new(ResetFsCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("fs".to_string(), Box::new(FsCommand {
+            filesystem: filesystem.clone(),
+        }));
+        commands.insert("edit".to_string(), Box::new(EditCommand {
+            filesystem: filesystem.clone(),
+            opener: editor.clone(),
+        }));
+        commands.insert("nano".to_string(), Box::new(EditCommand {
+            filesystem: filesystem.clone(),
+            opener: editor.clone(),
+        }));
+
+        Self {
+            commands: Rc::new(commands),
+            filesystem,
+            environment,
+            editor,
+        }
+    }
 
-        Self { commands }
+    /// Let `edit` show the editor through `opener`
+    pub fn set_editor_opener(&self, opener: EditorOpener) {
+        *self.editor.borrow_mut() = Some(opener);
     }
 
     /// Get all registered command names
@@ -110,8 +192,67 @@ impl CommandExecutor {
             return CommandResult::Success(String::new());
         }
 
+        // `< file`, `> file`, `>> file` and `2> file` apply to the whole line
+        let (input, redirects) = match Self::split_redirects(input) {
+            Ok(split) => split,
+            Err(error) => return CommandResult::Error(error),
+        };
+        let result = match &redirects.stdin {
+            Some(path) => self.execute_with_input(input, path, context),
+            None => self.execute_line(input, context),
+        };
+        // Only the last stage's output can reach a redirect
+        let command_name = Self::split_pipeline(input)
+            .ok()
+            .and_then(|stages| stages.last()?.first().cloned())
+            .unwrap_or_default();
+        self.redirect_output(command_name, result, redirects.stdout, redirects.stderr)
+    }
+
+    /// Execute a command line with a file's content as its stdin
+    fn execute_with_input(
+        &self,
+        input: &str,
+        path: &str,
+        context: &TerminalContext,
+    ) -> CommandResult {
+        let source = match self.filesystem.borrow().get_file_info(path) {
+            Ok(node) => node.content_source(),
+            Err(error) => return CommandResult::Error(error),
+        };
+        let detached = DetachedContext::new(context);
+        if let ContentSource::Memory(content) = source {
+            return self.execute_line(input, &detached.attach(self, Some(content)));
+        }
+
+        let executor = self.clone();
+        let input = input.to_string();
+        let path = path.to_string();
+        CommandResult::Async(Box::pin(async move {
+            let content = match source.read().await {
+                Ok(content) => content,
+                Err(error) => return CommandResult::Error(format!("{}: {}", path, error)),
+            };
+            match executor.execute_line(&input, &detached.attach(&executor, Some(content))) {
+                CommandResult::Async(future) => future.await,
+                result => result,
+            }
+        }))
+    }
+
+    /// Execute a command line without redirection, which may be a pipeline
+    fn execute_line(&self, input: &str, context: &TerminalContext) -> CommandResult {
+        // Split `a | b` into stages, each fed the previous one's output
+        let mut stages = match Self::split_pipeline(input) {
+            Ok(stages) => stages,
+            Err(error) => return CommandResult::Error(error),
+        };
+        if stages.len() > 1 {
+            return self.execute_pipeline(stages, context);
+        }
+
         // Parse the input into command and arguments with quote handling
-        let parts = Self::parse_command_line(input);
+        let parts = stages.pop().unwrap_or_default();
 
         // Double-check for empty parts
         if parts.is_empty() {
@@ -149,8 +290,10 @@ impl CommandExecutor {
                             ))
                         }
                     }
-                } else {
+                } else if command.trusts_html(args, context) {
                     command.execute(args, context)
+                } else {
+                    command.execute(args, context).sanitized()
                 }
             }
             None => CommandResult::Error(format!(
@@ -160,6 +303,222 @@ impl CommandExecutor {
         }
     }
 
+    /// Run pipeline stages in order, passing each one's text output as the
+    /// next one's stdin
+    ///
+    /// The first failing stage ends the pipeline with its error. Once a stage
+    /// goes async, the rest of the pipeline runs inside its future.
+    fn execute_pipeline(&self, stages: Vec<Vec<String>>, context: &TerminalContext) -> CommandResult {
+        let detached = DetachedContext::new(context);
+        let mut stdin = context.stdin.clone();
+
+        for (index, stage) in stages.iter().enumerate() {
+            let stage_context = detached.attach(self, stdin.take());
+            let result = self.execute_command(&stage[0], &stage[1..], &stage_context);
+            if index == stages.len() - 1 {
+                return result;
+            }
+            match result {
+                CommandResult::Async(future) => {
+                    let executor = self.clone();
+                    let command_name = stage[0].clone();
+                    let remaining = stages[index + 1..].to_vec();
+                    return CommandResult::Async(Box::pin(async move {
+                        executor
+                            .finish_pipeline(future.await, &command_name, remaining, detached)
+                            .await
+                    }));
+                }
+                result => match Self::pipe_output(&stage[0], result) {
+                    Ok(output) => stdin = Some(output),
+                    Err(error) => return error,
+                },
+            }
+        }
+
+        CommandResult::Success(String::new())
+    }
+
+    /// Continue a pipeline after an async stage resolved to `result`
+    async fn finish_pipeline(
+        &self,
+        mut result: CommandResult,
+        mut command_name: &str,
+        stages: Vec<Vec<String>>,
+        detached: DetachedContext,
+    ) -> CommandResult {
+        for stage in &stages {
+            let stdin = match Self::pipe_output(command_name, result) {
+                Ok(output) => output,
+                Err(error) => return error,
+            };
+            let stage_context = detached.attach(self, Some(stdin));
+            result = match self.execute_command(&stage[0], &stage[1..], &stage_context) {
+                CommandResult::Async(future) => future.await,
+                result => result,
+            };
+            command_name = &stage[0];
+        }
+        result
+    }
+
+    /// Text a finished stage passes down the pipeline, or the result that ends it
+    ///
+    /// Text gets a final newline, as with redirection, so filters like
+    /// `wc -l` count its last line.
+    fn pipe_output(command_name: &str, result: CommandResult) -> Result<String, CommandResult> {
+        match result {
+            CommandResult::Success(output) => Ok(with_newline(output)),
+            CommandResult::Html(html) => {
+                discard_outputs(&html);
+                Err(CommandResult::Error(format!(
+                    "{}: HTML output cannot be piped",
+                    command_name
+                )))
+            }
+            CommandResult::Async(_) => Err(CommandResult::Error(
+                "Nested async operations not supported".to_string(),
+            )),
+            error => Err(error),
+        }
+    }
+
+    /// Write a command's output to `stdout`, or its error to `stderr`, in
+    /// the overlay
+    ///
+    /// Text gets a final newline, as a shell would write it. HTML can't be
+    /// redirected, as it can't be piped. Like a shell, every target is created
+    /// or truncated even when the command writes nothing to it. Results
+    /// without a target are shown as usual.
+    fn redirect_output(
+        &self,
+        command_name: String,
+        result: CommandResult,
+        stdout: Option<RedirectTarget>,
+        stderr: Option<RedirectTarget>,
+    ) -> CommandResult {
+        if stdout.is_none() && stderr.is_none() {
+            return result;
+        }
+        let (output, error, result) = match result {
+            CommandResult::Async(future) => {
+                let executor = self.clone();
+                return CommandResult::Async(Box::pin(async move {
+                    let result = future.await;
+                    match executor.redirect_output(command_name, result, stdout, stderr) {
+                        CommandResult::Async(future) => future.await,
+                        result => result,
+                    }
+                }));
+            }
+            CommandResult::Success(output) if stdout.is_some() => (
+                with_newline(output),
+                String::new(),
+                CommandResult::Success(String::new()),
+            ),
+            CommandResult::Html(html) if stdout.is_some() => {
+                discard_outputs(&html);
+                (
+                    String::new(),
+                    String::new(),
+                    CommandResult::Error(format!(
+                        "{}: HTML output cannot be redirected",
+                        command_name
+                    )),
+                )
+            }
+            CommandResult::Error(error) if stderr.is_some() => (
+                String::new(),
+                with_newline(error),
+                CommandResult::Success(String::new()),
+            ),
+            result => (String::new(), String::new(), result),
+        };
+        let writes = [(stdout, output), (stderr, error)]
+            .into_iter()
+            .filter_map(|(target, content)| Some((target?, content)))
+            .collect();
+        self.write_redirects(writes, result)
+    }
+
+    /// Write each redirect target in turn, then give `result`, or the first
+    /// write error
+    fn write_redirects(
+        &self,
+        mut writes: Vec<(RedirectTarget, String)>,
+        result: CommandResult,
+    ) -> CommandResult {
+        while !writes.is_empty() {
+            let (target, content) = writes.remove(0);
+            match self.write_redirect(target, content) {
+                CommandResult::Async(future) => {
+                    let executor = self.clone();
+                    return CommandResult::Async(Box::pin(async move {
+                        match future.await {
+                            CommandResult::Error(error) => CommandResult::Error(error),
+                            _ => match executor.write_redirects(writes, result) {
+                                CommandResult::Async(future) => future.await,
+                                result => result,
+                            },
+                        }
+                    }));
+                }
+                CommandResult::Error(error) => return CommandResult::Error(error),
+                _ => {}
+            }
+        }
+        result
+    }
+
+    /// Write or append `content` to a redirect target
+    ///
+    /// Appending to a shipped file fetches its content first; appending
+    /// nothing only creates the file if it is missing.
+    fn write_redirect(&self, target: RedirectTarget, content: String) -> CommandResult {
+        if target.append
+            && content.is_empty()
+            && self.filesystem.borrow().get_file_info(&target.path).is_ok()
+        {
+            return CommandResult::Success(String::new());
+        }
+        let existing = if target.append {
+            self.filesystem
+                .borrow()
+                .get_file_info(&target.path)
+                .map(|node| node.content_source())
+                .ok()
+        } else {
+            None
+        };
+        let content = match existing {
+            None => content,
+            Some(ContentSource::Memory(existing)) => existing + &content,
+            Some(source) => {
+                let executor = self.clone();
+                return CommandResult::Async(Box::pin(async move {
+                    match source.read().await {
+                        Ok(existing) => executor.write_redirect(
+                            RedirectTarget {
+                                append: false,
+                                ..target
+                            },
+                            existing + &content,
+                        ),
+                        Err(error) => CommandResult::Error(format!("{}: {}", target.path, error)),
+                    }
+                }));
+            }
+        };
+        match self
+            .filesystem
+            .borrow_mut()
+            .write_file(&target.path, content)
+        {
+            Ok(()) => CommandResult::Success(String::new()),
+            Err(error) => CommandResult::Error(error),
+        }
+    }
+
     /// Get completion suggestions for tab completion
     ///
     /// This method delegates to the filesystem for file/directory completion
@@ -171,9 +530,8 @@ impl CommandExecutor {
     ) -> (Vec<String>, String) {
         // Try to get filesystem from any filesystem command
         if let Some(_pwd_cmd) = self.commands.get("pwd") {
-            // We need to access the filesystem through the command
-            // For now, create a new filesystem instance for completion
-            let fs = FileSystem::new();
+            // The shared filesystem, so files created in the overlay complete too
+            let fs = self.filesystem.borrow();
             fs.get_completion_suggestions(input, cursor_position)
         } else {
             // Fallback to command completion only
@@ -197,6 +555,137 @@ impl CommandExecutor {
         }
     }
 
+    /// Split a command line into pipeline stages at unquoted `|`
+    ///
+    /// Each stage is parsed with `parse_command_line`; a stage with no
+    /// command (`ls |`, `| grep x`) is a syntax error.
+    fn split_pipeline(input: &str) -> Result<Vec<Vec<String>>, String> {
+        let mut stages = Vec::new();
+        let mut stage_start = 0;
+        let mut quote: Option<char> = None;
+
+        for (index, ch) in input.char_indices() {
+            match (quote, ch) {
+                (None, '"' | '\'') => quote = Some(ch),
+                (Some(open), _) if ch == open => quote = None,
+                (None, '|') => {
+                    stages.push(Self::parse_command_line(&input[stage_start..index]));
+                    stage_start = index + 1;
+                }
+                _ => {}
+            }
+        }
+        stages.push(Self::parse_command_line(&input[stage_start..]));
+
+        if stages.len() > 1 && stages.iter().any(Vec::is_empty) {
+            return Err("syntax error near unexpected token `|'".to_string());
+        }
+        Ok(stages)
+    }
+
+    /// Split the redirections off the end of a command line
+    ///
+    /// Everything from the first unquoted `<`, `>`, `>>`, `2>` or `2>>` on
+    /// must be redirections, each followed by one file. The command may be
+    /// empty, so `> file` creates an empty file.
+    fn split_redirects(input: &str) -> Result<(&str, Redirects), String> {
+        let mut quote: Option<char> = None;
+        let position = input.char_indices().find_map(|(index, ch)| {
+            match (quote, ch) {
+                (None, '"' | '\'') => quote = Some(ch),
+                (Some(open), _) if ch == open => quote = None,
+                // A `2` only names stderr as a word of its own
+                (None, '>')
+                    if input[..index].ends_with('2')
+                        && input[..index - 1]
+                            .chars()
+                            .next_back()
+                            .is_none_or(char::is_whitespace) =>
+                {
+                    return Some(index - 1)
+                }
+                (None, '<' | '>') => return Some(index),
+                _ => {}
+            }
+            None
+        });
+        let Some(position) = position else {
+            return Ok((input, Redirects::default()));
+        };
+
+        let mut redirects = Redirects::default();
+        let mut rest = &input[position..];
+        let mut last_target = String::new();
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+            let Some(operator) = ["2>>", "2>", ">>", ">", "<"]
+                .into_iter()
+                .find(|operator| rest.starts_with(operator))
+            else {
+                if rest.starts_with('|') {
+                    return Err("redirections must come at the end of the command line".to_string());
+                }
+                let (word, _) = Self::split_word(rest);
+                return Err(format!(
+                    "{} {}: ambiguous redirect",
+                    last_target,
+                    Self::parse_command_line(word).join(" ")
+                ));
+            };
+
+            let (word, remainder) = Self::split_word(rest[operator.len()..].trim_start());
+            let Some(path) = Self::parse_command_line(word).pop() else {
+                let token = remainder.split_whitespace().next().unwrap_or("newline");
+                return Err(format!("syntax error near unexpected token `{}'", token));
+            };
+            last_target = path.clone();
+            let append = operator.ends_with(">>");
+            match operator {
+                "<" => redirects.stdin = Some(path),
+                "2>" | "2>>" => redirects.stderr = Some(RedirectTarget { path, append }),
+                _ => redirects.stdout = Some(RedirectTarget { path, append }),
+            }
+            rest = remainder;
+        }
+        Ok((input[..position].trim(), redirects))
+    }
+
+    /// Split off the first word, which ends at unquoted whitespace, `<` or `>`
+    fn split_word(input: &str) -> (&str, &str) {
+        let mut quote: Option<char> = None;
+        let end = input
+            .char_indices()
+            .find(|&(_, ch)| {
+                match (quote, ch) {
+                    (None, '"' | '\'') => quote = Some(ch),
+                    (Some(open), _) if ch == open => quote = None,
+                    (None, '<' | '>') => return true,
+                    (None, ch) if ch.is_whitespace() => return true,
+                    _ => {}
+                }
+                false
+            })
+            .map_or(input.len(), |(index, _)| index);
+        input.split_at(end)
+    }
+
+    /// Quote an argument so the command line parser keeps it as one word
+    ///
+    /// Whitespace, quotes and the `|`, `<` and `>` operators are quoted;
+    /// used to build the commands behind clickable links and cards.
+    pub fn quote_arg(arg: &str) -> String {
+        if !arg.contains([' ', '\t', '"', '\'', '|', '<', '>']) {
+            arg.to_string()
+        } else if arg.contains('\'') {
+            format!("\"{}\"", arg)
+        } else {
+            format!("'{}'", arg)
+        }
+    }
+
     /// Parse command line input with proper quote handling
     ///
     /// This function handles quoted strings properly, so "hello world" becomes a single argument.
@@ -242,9 +731,242 @@ impl CommandExecutor {
     }
 }
 
+/// Text as a shell writes it, ending with a newline unless empty
+fn with_newline(text: String) -> String {
+    if text.is_empty() || text.ends_with('\n') {
+        text
+    } else {
+        format!("{}\n", text)
+    }
+}
+
+/// A file that output is redirected to
+#[derive(Debug, Clone, PartialEq)]
+struct RedirectTarget {
+    path: String,
+    /// `>>` rather than `>`
+    append: bool,
+}
+
+/// Redirections of a command line
+#[derive(Debug, Default, PartialEq)]
+struct Redirects {
+    /// `< file`, read as the first command's stdin
+    stdin: Option<String>,
+    /// `> file` or `>> file`, for output
+    stdout: Option<RedirectTarget>,
+    /// `2> file` or `2>> file`, for errors
+    stderr: Option<RedirectTarget>,
+}
+
+/// Owned parts of a `TerminalContext`, so pipeline stages after an async
+/// one can be run with the same callbacks
+struct DetachedContext {
+    clear_screen: Rc<dyn Fn()>,
+    execute: Rc<dyn Fn(&str) -> CommandResult>,
+    app_config: AppConfigService,
+    set_theme: Option<ThemeSetter>,
+    set_code_theme: Option<ThemeSetter>,
+}
+
+impl DetachedContext {
+    fn new(context: &TerminalContext) -> Self {
+        Self {
+            clear_screen: context.clear_screen.clone(),
+            execute: context.execute.clone(),
+            app_config: context.app_config.clone(),
+            set_theme: context.set_theme.clone(),
+            set_code_theme: context.set_code_theme.clone(),
+        }
+    }
+
+    fn attach<'a>(&self, executor: &'a CommandExecutor, stdin: Option<String>) -> TerminalContext<'a> {
+        TerminalContext {
+            clear_screen: self.clear_screen.clone(),
+            command_executor: executor,
+            execute: self.execute.clone(),
+            app_config: self.app_config.clone(),
+            set_theme: self.set_theme.clone(),
+            set_code_theme: self.set_code_theme.clone(),
+            stdin,
+        }
+    }
+}
+
 /// Default implementation for CommandExecutor
 impl Default for CommandExecutor {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{context, overlay_files, run};
+
+    #[test]
+    fn test_split_pipeline() {
+        assert_eq!(
+            CommandExecutor::split_pipeline("cat a.md | grep -n 'x|y' | wc").unwrap(),
+            vec![
+                vec!["cat".to_string(), "a.md".to_string()],
+                vec!["grep".to_string(), "-n".to_string(), "x|y".to_string()],
+                vec!["wc".to_string()],
+            ]
+        );
+        assert!(CommandExecutor::split_pipeline("ls |").is_err());
+        assert!(CommandExecutor::split_pipeline("ls || pwd").is_err());
+    }
+
+    fn target(path: &str, append: bool) -> Option<RedirectTarget> {
+        Some(RedirectTarget {
+            path: path.to_string(),
+            append,
+        })
+    }
+
+    #[test]
+    fn test_split_redirects() {
+        assert_eq!(
+            CommandExecutor::split_redirects("echo 'a > b' | wc > \"my notes.txt\"").unwrap(),
+            (
+                "echo 'a > b' | wc",
+                Redirects {
+                    stdout: target("my notes.txt", false),
+                    ..Redirects::default()
+                }
+            )
+        );
+        assert_eq!(
+            CommandExecutor::split_redirects("> empty.md").unwrap(),
+            (
+                "",
+                Redirects {
+                    stdout: target("empty.md", false),
+                    ..Redirects::default()
+                }
+            )
+        );
+        assert_eq!(
+            CommandExecutor::split_redirects("wc -l<notes.txt>>log.txt 2> errors.txt").unwrap(),
+            (
+                "wc -l",
+                Redirects {
+                    stdin: Some("notes.txt".to_string()),
+                    stdout: target("log.txt", true),
+                    stderr: target("errors.txt", false),
+                }
+            )
+        );
+        assert_eq!(
+            CommandExecutor::split_redirects("echo a2> b").unwrap().0,
+            "echo a2"
+        );
+        assert_eq!(
+            CommandExecutor::split_redirects("ls").unwrap(),
+            ("ls", Redirects::default())
+        );
+        assert!(CommandExecutor::split_redirects("ls >").is_err());
+        assert!(CommandExecutor::split_redirects("ls > > a").is_err());
+        assert!(CommandExecutor::split_redirects("ls > a b").is_err());
+        assert!(CommandExecutor::split_redirects("cat < a | wc").is_err());
+    }
+
+    #[test]
+    fn test_quoted_arg_round_trips() {
+        for name in [
+            "notes.md",
+            "my notes.md",
+            "a>b",
+            "a<b",
+            "x|y",
+            "it's here",
+            "2>log",
+        ] {
+            let command = format!("cat {}", CommandExecutor::quote_arg(name));
+            let (line, redirects) = CommandExecutor::split_redirects(&command).unwrap();
+            assert_eq!(redirects, Redirects::default());
+            assert_eq!(
+                CommandExecutor::split_pipeline(line).unwrap(),
+                vec![vec!["cat".to_string(), name.to_string()]]
+            );
+        }
+    }
+
+    #[test]
+    fn test_cat_pipes_plain_text() {
+        let executor = CommandExecutor::new();
+        let context = context(&executor);
+        overlay_files(&executor, &[("a.txt", "hello <b>\nbye\n")]);
+
+        let CommandResult::Success(output) = run(&context, "cat a.txt | grep hello") else {
+            panic!("cat output was not piped");
+        };
+        assert_eq!(output, "\x1b[1;31mhello\x1b[0m <b>");
+    }
+
+    #[test]
+    fn test_pipeline_counts_the_last_line() {
+        let executor = CommandExecutor::new();
+        let context = context(&executor);
+        let CommandResult::Success(files) = run(&context, "find / -type f") else {
+            panic!("find failed");
+        };
+        assert!(!files.ends_with('\n'));
+
+        let CommandResult::Success(count) = run(&context, "find / -type f | wc -l") else {
+            panic!("wc failed");
+        };
+        assert_eq!(count.trim(), files.lines().count().to_string());
+    }
+
+    #[test]
+    fn test_cat_pipes_into_line_filters() {
+        let executor = CommandExecutor::new();
+        let context = context(&executor);
+        overlay_files(&executor, &[("a.txt", "one\ntwo\nthree")]);
+
+        let CommandResult::Success(count) = run(&context, "cat a.txt | wc -l") else {
+            panic!("wc failed");
+        };
+        assert_eq!(count.trim(), "3");
+        let CommandResult::Success(first) = run(&context, "cat a.txt | head -n 1") else {
+            panic!("head failed");
+        };
+        assert_eq!(first, "one");
+    }
+
+    #[test]
+    fn test_redirects_write_text() {
+        let executor = CommandExecutor::new();
+        let context = context(&executor);
+        overlay_files(&executor, &[("a.txt", "one <b>\n"), ("err.txt", "old")]);
+        let content = |path: &str| {
+            executor
+                .filesystem
+                .borrow()
+                .get_file_info(path)
+                .ok()
+                .and_then(|node| node.content.clone())
+        };
+
+        assert!(
+            matches!(run(&context, "cat a.txt > b.txt"), CommandResult::Success(out) if out.is_empty())
+        );
+        assert_eq!(content("b.txt").as_deref(), Some("one <b>\n"));
+        run(&context, "echo two >> b.txt");
+        assert_eq!(content("b.txt").as_deref(), Some("one <b>\ntwo\n"));
+
+        run(&context, "echo ok 2> err.txt");
+        assert_eq!(content("err.txt").as_deref(), Some(""));
+        run(&context, "cat missing.txt 2> err.txt >> new.txt");
+        assert!(content("err.txt").unwrap().starts_with("cat: "));
+        assert_eq!(content("new.txt").as_deref(), Some(""));
+
+        let CommandResult::Error(error) = run(&context, "tree > list.txt") else {
+            panic!("tree output was redirected");
+        };
+        assert_eq!(error, "tree: HTML output cannot be redirected");
+        assert_eq!(content("list.txt").as_deref(), Some(""));
+    }
+}
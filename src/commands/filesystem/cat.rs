@@ -3,7 +3,8 @@
 
 use crate::commands::{Command, CommandResult, TerminalContext};
 use crate::filesystem::FileSystem;
-use crate::utils::fetch_file_content;
+use crate::utils::file_utils::ansi_renderer::render_markdown_to_ansi;
+use crate::utils::syntax_highlighter::SyntaxHighlighter;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -12,33 +13,46 @@ pub struct CatCommand {
 }
 
 impl Command for CatCommand {
-    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
-        if args.is_empty() {
-            return CommandResult::Error("cat: missing filename".to_string());
+    fn execute(&self, args: &[String], context: &TerminalContext) -> CommandResult {
+        let mut pretty = false;
+        let mut filename = None;
+        for arg in args {
+            match arg.as_str() {
+                "--pretty" | "-p" => pretty = true,
+                flag if flag.starts_with('-') => {
+                    return CommandResult::Error(format!("cat: unknown option: {}", flag))
+                }
+                _ => filename = Some(arg),
+            }
         }
 
-        let filename = &args[0];
+        let Some(filename) = filename else {
+            return CommandResult::Error("cat: missing filename".to_string());
+        };
         let fs = self.filesystem.borrow();
 
         match fs.get_file_info(filename) {
             Ok(node) => {
-                let file_path = node.path.clone();
+                let source = node.content_source();
+                let extension = node.extension.clone().unwrap_or_default();
+                let markdown_config = context.app_config.config.markdown.clone();
 
                 // Return async future that will resolve to the file content
                 let future = Box::pin(async move {
-                    match fetch_file_content(&file_path).await {
-                        Ok(content) => {
-                            // Output file content as plain text (manually escape HTML)
-                            let escaped_content = content
-                                .replace('&', "&amp;")
-                                .replace('<', "&lt;")
-                                .replace('>', "&gt;")
-                                .replace('"', "&quot;")
-                                .replace('\'', "&#x27;");
-                            let html_content =
-                                format!("<pre class=\"file-content\">{}</pre>", escaped_content);
-                            CommandResult::Html(html_content)
+                    match source.read().await {
+                        Ok(content) if pretty => {
+                            // Styled text rather than HTML, so it stays pipeable
+                            let text = if extension == "md" || extension == "markdown" {
+                                render_markdown_to_ansi(&content, &markdown_config)
+                            } else {
+                                SyntaxHighlighter::shared()
+                                    .highlight_ansi_lines(&content, &extension)
+                                    .join("\n")
+                            };
+                            CommandResult::Success(text)
                         }
+                        // Plain text, so it can be piped and redirected as is
+                        Ok(content) => CommandResult::Success(content),
                         Err(error) => {
                             CommandResult::Error(format!("cat: Error reading file: {}", error))
                         }
@@ -56,7 +70,7 @@ impl Command for CatCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "cat <file>"
+        "cat [--pretty] <file>"
     }
 
     fn help(&self) -> Option<&'static str> {
@@ -64,14 +78,19 @@ impl Command for CatCommand {
 
 Usage:
   cat <file>              Display contents of file
+  cat --pretty <file>     Display markdown as styled text, or highlighted code
   cat --help              Show this help message
 
 Description:
   The cat command displays the contents of the specified file.
   The file path is relative to the current directory.
+  With --pretty (-p), markdown is rendered as styled text (headings, lists,
+  box-drawn tables, highlighted code) and other files are syntax highlighted
+  by their extension. The output is plain text with colors.
 
 Examples:
   cat README.md           Display contents of README.md
-  cat about/demo.md       Display contents of about/demo.md"#)
+  cat about/demo.md       Display contents of about/demo.md
+  cat -p index.md         Display index.md as styled text"#)
     }
 }
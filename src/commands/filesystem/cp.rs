@@ -0,0 +1,77 @@
+// CP Command Implementation
+// Copy files and directories in the writable overlay
+
+use super::mv::split_destination;
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::FileSystem;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct CpCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+impl Command for CpCommand {
+    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        let mut recursive = false;
+        let mut operands = Vec::new();
+        for arg in args {
+            match arg.as_str() {
+                "-r" | "-R" | "--recursive" => recursive = true,
+                flag if flag.starts_with('-') => {
+                    return CommandResult::Error(format!("cp: unknown option: {}", flag))
+                }
+                _ => operands.push(arg.clone()),
+            }
+        }
+        let mut fs = self.filesystem.borrow_mut();
+        let (sources, destination) = match split_destination(&fs, &operands) {
+            Ok(split) => split,
+            Err(error) => return CommandResult::Error(format!("cp: {}", error)),
+        };
+
+        let errors: Vec<String> = sources
+            .iter()
+            .filter_map(|source| fs.copy(source, destination, recursive).err())
+            .map(|error| format!("cp: {}", error))
+            .collect();
+        if errors.is_empty() {
+            CommandResult::Success(String::new())
+        } else {
+            CommandResult::Error(errors.join("\n"))
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        "Copy files and directories"
+    }
+
+    fn usage(&self) -> &'static str {
+        "cp [-r] <source...> <destination>"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"cp - Copy files and directories
+
+Usage:
+  cp <source> <destination>
+                          Copy a file, replacing destination if it exists
+  cp <source...> <directory>
+                          Copy each source into directory
+  cp -r <directory> <destination>
+                          Copy a directory and everything in it
+  cp --help               Show this help message
+
+Description:
+  Copies are made in the writable overlay and are marked with + in ls.
+  Copying a site file is cheap: the copy reads the original's content
+  until you change it. reset-fs discards all copies.
+
+Examples:
+  cp index.md notes.md    Copy index.md to notes.md
+  cp a.md b.md notes      Copy a.md and b.md into the notes directory
+  cp -r posts drafts      Copy the posts directory to drafts"#,
+        )
+    }
+}
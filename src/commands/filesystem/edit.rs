@@ -0,0 +1,132 @@
+// EDIT Command Implementation
+// Open a file in the full-screen editor
+
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::types::ContentSource;
+use crate::filesystem::FileSystem;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A file opened in the editor
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditorSession {
+    /// Absolute path the buffer is saved to
+    pub path: String,
+    pub content: String,
+    /// Extension, picking the syntax highlighting
+    pub extension: String,
+    /// Shipped with the site, so saving writes an overlay copy over it
+    pub shipped: bool,
+    /// Doesn't exist yet; saving creates it
+    pub new_file: bool,
+}
+
+/// Shows the editor for a session, replacing the terminal
+pub type EditorOpener = Rc<dyn Fn(EditorSession)>;
+
+pub struct EditCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+    /// Set once the terminal component can show the editor
+    pub opener: Rc<RefCell<Option<EditorOpener>>>,
+}
+
+impl Command for EditCommand {
+    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        let mut files = Vec::new();
+        for arg in args {
+            match arg.as_str() {
+                flag if flag.starts_with('-') => {
+                    return CommandResult::Error(format!("edit: unknown option: {}", flag))
+                }
+                _ => files.push(arg),
+            }
+        }
+        let [file] = files[..] else {
+            return CommandResult::Error(match files.len() {
+                0 => "edit: missing filename".to_string(),
+                _ => "edit: only one file can be edited at a time".to_string(),
+            });
+        };
+        let Some(open) = self.opener.borrow().clone() else {
+            return CommandResult::Error("edit: the editor is not available here".to_string());
+        };
+
+        let fs = self.filesystem.borrow();
+        let mut session = EditorSession {
+            path: fs.absolute_path(file),
+            content: String::new(),
+            extension: file
+                .rsplit_once('.')
+                .map(|(_, extension)| extension.to_lowercase())
+                .unwrap_or_default(),
+            shipped: false,
+            new_file: true,
+        };
+        let source = match fs.get_node(file) {
+            Ok(node) if node.node_type == "directory" => {
+                return CommandResult::Error(format!("edit: '{}' is a directory", file))
+            }
+            Ok(node) => {
+                session.shipped = !node.overlay;
+                session.new_file = false;
+                node.content_source()
+            }
+            Err(_) => ContentSource::Memory(String::new()),
+        };
+
+        match source {
+            ContentSource::Memory(content) => {
+                open(EditorSession { content, ..session });
+                CommandResult::Success(String::new())
+            }
+            source => CommandResult::Async(Box::pin(async move {
+                match source.read().await {
+                    Ok(content) => {
+                        open(EditorSession { content, ..session });
+                        CommandResult::Success(String::new())
+                    }
+                    Err(error) => CommandResult::Error(format!("edit: {}", error)),
+                }
+            })),
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        "Edit a file in a full-screen editor"
+    }
+
+    fn usage(&self) -> &'static str {
+        "edit <file>"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"edit - Edit a file in a full-screen editor
+
+Usage:
+  edit <file>             Open file in the editor, creating it on save
+  nano <file>             The same
+  edit --help             Show this help message
+
+Keys:
+  Arrows, Home, End       Move the cursor
+  PageUp, PageDown        Move a screen up or down
+  Ctrl+F                  Search; Enter finds the next match, Esc cancels,
+                          and an empty search repeats the last one
+  Ctrl+S                  Save
+  Ctrl+X                  Exit; press twice to discard unsaved changes
+
+Description:
+  Takes over the terminal with a line-numbered editor. Markdown, Rust and
+  other languages known to cat --pretty are syntax highlighted.
+
+  Saving writes to the writable overlay. The site's own files are
+  read-only, so saving one of them stores an edited copy in the overlay
+  that hides the original until reset-fs.
+
+Examples:
+  edit notes.md           Write notes, creating notes.md on save
+  nano /index.md          Edit a copy of the home page"#,
+        )
+    }
+}
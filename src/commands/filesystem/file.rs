@@ -0,0 +1,202 @@
+// FILE Command Implementation
+// Report the detected type of files
+
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::types::{ContentSource, FileSystemNode};
+use crate::filesystem::FileSystem;
+use futures::future::join_all;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct FileCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+/// How a path's type is found
+enum Detection {
+    /// Known from metadata alone
+    Known(String),
+    /// Needs the content sniffed
+    Sniff(ContentSource),
+}
+
+impl Command for FileCommand {
+    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        if args.is_empty() {
+            return CommandResult::Error("file: missing operand".to_string());
+        }
+        let fs = self.filesystem.borrow();
+
+        let detections: Vec<(String, Result<Detection, String>)> = args
+            .iter()
+            .map(|path| {
+                let lookup = match path.trim_end_matches('/') {
+                    "" => "/",
+                    trimmed => trimmed,
+                };
+                (path.clone(), fs.get_node(lookup).map(detect))
+            })
+            .collect();
+
+        CommandResult::Async(Box::pin(async move {
+            let types = join_all(detections.iter().map(|(_, detection)| async move {
+                match detection {
+                    Ok(Detection::Known(kind)) => Ok(kind.clone()),
+                    Ok(Detection::Sniff(source)) => {
+                        source.read_cached().await.map(|content| sniff(&content))
+                    }
+                    Err(error) => Err(error.clone()),
+                }
+            }))
+            .await;
+
+            let lines: Vec<String> = detections
+                .iter()
+                .zip(types)
+                .map(|((path, _), kind)| match kind {
+                    Ok(kind) => format!("{}: {}", path, kind),
+                    Err(error) => format!("{}: cannot open ({})", path, error),
+                })
+                .collect();
+            CommandResult::Success(lines.join("\n"))
+        }))
+    }
+
+    fn description(&self) -> &'static str {
+        "Report the type of files"
+    }
+
+    fn usage(&self) -> &'static str {
+        "file <path...>"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"file - Report the type of files
+
+Usage:
+  file <path...>          Print the detected type of each path
+  file --help             Show this help message
+
+Description:
+  Known extensions (markdown, source code, JSON, themes, images and so
+  on) are recognised from metadata alone. Other files are fetched and
+  their content is inspected: scripts, JSON, XML, binary data and text
+  are told apart.
+
+Examples:
+  file index.md           Markdown document, UTF-8 text
+  file a.md b.json        Types of a.md and b.json"#,
+        )
+    }
+}
+
+/// Type of `node` from its metadata, or a request to sniff its content
+fn detect(node: &FileSystemNode) -> Detection {
+    if node.node_type == "directory" {
+        return Detection::Known("directory".to_string());
+    }
+    if node.size == Some(0) {
+        return Detection::Known("empty".to_string());
+    }
+
+    let extension = node
+        .extension
+        .clone()
+        .or_else(|| {
+            node.name
+                .rsplit_once('.')
+                .map(|(_, extension)| extension.to_string())
+        })
+        .unwrap_or_default()
+        .to_lowercase();
+    let kind = match extension.as_str() {
+        "md" | "markdown" => {
+            let has_frontmatter =
+                node.title.is_some() || node.description.is_some() || node.tags.is_some();
+            if has_frontmatter {
+                "Markdown document with frontmatter, UTF-8 text"
+            } else {
+                "Markdown document, UTF-8 text"
+            }
+        }
+        "rs" => "Rust source, UTF-8 text",
+        "js" | "mjs" => "JavaScript source, UTF-8 text",
+        "ts" => "TypeScript source, UTF-8 text",
+        "py" => "Python script, UTF-8 text",
+        "sh" => "shell script, UTF-8 text",
+        "json" => "JSON data",
+        "toml" => "TOML document, UTF-8 text",
+        "yaml" | "yml" => "YAML document, UTF-8 text",
+        "html" | "htm" => "HTML document, UTF-8 text",
+        "css" => "CSS stylesheet, UTF-8 text",
+        "xml" => "XML document, UTF-8 text",
+        "svg" => "SVG Scalable Vector Graphics image",
+        "tmtheme" => "XML property list (TextMate theme)",
+        "txt" => "UTF-8 text",
+        "png" => "PNG image data",
+        "jpg" | "jpeg" => "JPEG image data",
+        "gif" => "GIF image data",
+        "webp" => "WebP image data",
+        "ico" => "MS Windows icon resource",
+        "pdf" => "PDF document",
+        "zip" => "Zip archive data",
+        "gz" => "gzip compressed data",
+        "wasm" => "WebAssembly (wasm) binary module",
+        "woff" | "woff2" | "ttf" | "otf" => "font data",
+        _ => return Detection::Sniff(node.content_source()),
+    };
+    Detection::Known(kind.to_string())
+}
+
+/// Type of a file with no known extension, judged by its content
+fn sniff(content: &str) -> String {
+    let trimmed = content.trim_start();
+    if content.is_empty() {
+        "empty".to_string()
+    } else if content.contains('\0') {
+        "data".to_string()
+    } else if let Some(shebang) = content.strip_prefix("#!") {
+        let interpreter = shebang
+            .lines()
+            .next()
+            .unwrap_or("")
+            .split_whitespace()
+            .map(|part| part.rsplit('/').next().unwrap_or(part))
+            .find(|part| *part != "env")
+            .unwrap_or("unknown");
+        format!("{} script, UTF-8 text executable", interpreter)
+    } else if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(content).is_ok()
+    {
+        "JSON data".to_string()
+    } else if trimmed.starts_with("<?xml") {
+        "XML document, UTF-8 text".to_string()
+    } else if content.is_ascii() {
+        "ASCII text".to_string()
+    } else {
+        "UTF-8 text".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(
+            sniff("#!/usr/bin/env bash\necho hi"),
+            "bash script, UTF-8 text executable"
+        );
+        assert_eq!(sniff("#!/bin/sh\n"), "sh script, UTF-8 text executable");
+        assert_eq!(sniff(" {\"a\": 1}"), "JSON data");
+        assert_eq!(sniff("{ not json"), "ASCII text");
+        assert_eq!(
+            sniff("<?xml version=\"1.0\"?><a/>"),
+            "XML document, UTF-8 text"
+        );
+        assert_eq!(sniff("a\0b"), "data");
+        assert_eq!(sniff("héllo"), "UTF-8 text");
+    }
+}
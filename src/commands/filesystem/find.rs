@@ -0,0 +1,234 @@
+// FIND Command Implementation
+// Search the filesystem by name, type, tag and modification date
+
+use crate::commands::filesystem::LsCommand;
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::find::{find, FindQuery, Predicate};
+use crate::filesystem::FileSystem;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct FindCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+/// Parsed command line
+#[derive(Debug, Default, PartialEq)]
+struct FindArgs {
+    path: Option<String>,
+    query: FindQuery,
+    link: bool,
+}
+
+impl Command for FindCommand {
+    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        let mut args = match parse_args(args) {
+            Ok(args) => args,
+            Err(error) => return CommandResult::Error(format!("find: {}", error)),
+        };
+        let fs = self.filesystem.borrow();
+
+        // `-newer` takes a date, or a file whose modification date to use
+        for predicate in &mut args.query.predicates {
+            if let Predicate::Newer(reference) = predicate {
+                match fs
+                    .get_file_info(reference)
+                    .ok()
+                    .and_then(|node| node.modified.clone())
+                {
+                    Some(modified) => *reference = modified,
+                    None if is_iso_date(reference) => {}
+                    None => {
+                        return CommandResult::Error(format!(
+                            "find: -newer: not a date (YYYY-MM-DD) or file: {}",
+                            reference
+                        ))
+                    }
+                }
+            }
+        }
+
+        let path = args.path.as_deref().unwrap_or(".");
+        let lookup = match path.trim_end_matches('/') {
+            "" => "/",
+            trimmed => trimmed,
+        };
+        let start = match fs.get_node(lookup) {
+            Ok(node) => node,
+            Err(error) => return CommandResult::Error(format!("find: {}", error)),
+        };
+        let matches = find(start, path, &args.query);
+
+        if args.link {
+            // The starting directory itself isn't a useful card
+            let items: Vec<_> = matches
+                .into_iter()
+                .filter(|(match_path, _)| match_path != path)
+                .map(|(_, node)| {
+                    // Absolute, since `navigate` treats every path as one
+                    let name = if node.node_type == "directory" {
+                        format!("/{}/", node.path)
+                    } else {
+                        format!("/{}", node.path)
+                    };
+                    (name, Some(node))
+                })
+                .collect();
+            if items.is_empty() {
+                return CommandResult::Html(
+                    r#"<div class="file-list-empty"><p>(no matches)</p></div>"#.to_string(),
+                );
+            }
+            CommandResult::Html(LsCommand::render_link_list(&items))
+        } else {
+            let paths: Vec<String> = matches.into_iter().map(|(path, _)| path).collect();
+            CommandResult::Success(paths.join("\n"))
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        "Search for files and directories"
+    }
+
+    fn usage(&self) -> &'static str {
+        "find [path] [-name pattern] [-type f|d] [-tag tag] [-newer date] [-maxdepth n] [--link]"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"find - Search for files and directories
+
+Usage:
+  find [path] [options]   List entries under path (default: .) that match
+  find --help             Show this help message
+
+Options:
+  -name <pattern>         Name matches a glob: * any text, ? one character, [a-z] a class
+  -iname <pattern>        Like -name, ignoring case
+  -type f|d               Only files (f) or directories (d)
+  -tag <tag>              Files whose metadata lists the tag
+  -newer <date|file>      Modified after a date (YYYY-MM-DD) or another file
+  -maxdepth <n>           Descend at most n levels below path
+  --link                  Show matches as clickable cards, like ls --link
+
+Description:
+  Walks the directory tree and prints one path per line for every entry
+  that passes all the given tests. Options may also be written with two
+  dashes (--tag). Quote patterns containing spaces.
+
+Examples:
+  find                    List everything below the current directory
+  find / -name '*.md'     Find all markdown files
+  find posts -type d      List directories under posts
+  find / -tag rust        Find files tagged rust
+  find -newer 2024-01-01 -maxdepth 2 --link"#,
+        )
+    }
+}
+
+/// Split arguments into the starting path, predicates and output mode
+fn parse_args(args: &[String]) -> Result<FindArgs, String> {
+    let mut parsed = FindArgs::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--link" {
+            parsed.link = true;
+            continue;
+        }
+        if !arg.starts_with('-') {
+            if parsed.path.is_some() {
+                return Err(format!("unexpected argument: {}", arg));
+            }
+            parsed.path = Some(arg.clone());
+            continue;
+        }
+
+        let option = arg.trim_start_matches('-');
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("missing argument to -{}", option))
+        };
+        match option {
+            "name" | "iname" => parsed.query.predicates.push(Predicate::Name {
+                pattern: value()?,
+                ignore_case: option == "iname",
+            }),
+            "type" => {
+                let directory = match value()?.as_str() {
+                    "f" => false,
+                    "d" => true,
+                    other => return Err(format!("-type: unknown type: {}", other)),
+                };
+                parsed.query.predicates.push(Predicate::Type { directory });
+            }
+            "tag" => parsed.query.predicates.push(Predicate::Tag(value()?)),
+            "newer" => parsed.query.predicates.push(Predicate::Newer(value()?)),
+            "maxdepth" => {
+                let depth = value()?;
+                parsed.query.max_depth = Some(
+                    depth
+                        .parse()
+                        .map_err(|_| format!("-maxdepth: not a number: {}", depth))?,
+                );
+            }
+            _ => return Err(format!("unknown option: {}", arg)),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// `YYYY-MM-DD`, optionally followed by a time
+fn is_iso_date(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() >= 10
+        && bytes[..10].iter().enumerate().all(|(i, byte)| match i {
+            4 | 7 => *byte == b'-',
+            _ => byte.is_ascii_digit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::args;
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = parse_args(&args(
+            "posts -name *.md --tag rust -type f -maxdepth 2 --link",
+        ))
+        .unwrap();
+        assert_eq!(parsed.path.as_deref(), Some("posts"));
+        assert!(parsed.link);
+        assert_eq!(parsed.query.max_depth, Some(2));
+        assert_eq!(
+            parsed.query.predicates,
+            [
+                Predicate::Name {
+                    pattern: "*.md".to_string(),
+                    ignore_case: false
+                },
+                Predicate::Tag("rust".to_string()),
+                Predicate::Type { directory: false },
+            ]
+        );
+
+        assert_eq!(
+            parse_args(&args("-type x")).unwrap_err(),
+            "-type: unknown type: x"
+        );
+        assert_eq!(
+            parse_args(&args("-name")).unwrap_err(),
+            "missing argument to -name"
+        );
+        assert_eq!(
+            parse_args(&args("-size 3")).unwrap_err(),
+            "unknown option: -size"
+        );
+        assert!(is_iso_date("2024-01-01"));
+        assert!(!is_iso_date("yesterday"));
+    }
+}
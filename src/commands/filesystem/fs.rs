@@ -0,0 +1,293 @@
+// FS Command Implementation
+// Show, export and import the writable overlay
+
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::persistence::OverlaySnapshot;
+use crate::filesystem::FileSystem;
+use crate::utils::file_transfer::{download_file, pick_file};
+use crate::utils::tar::{read_tar, write_tar, TarEntry};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct FsCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+/// Contents of an imported file
+#[derive(Debug, PartialEq)]
+enum Import {
+    /// An `fs export` JSON snapshot, replacing the overlay
+    Snapshot(OverlaySnapshot),
+    /// A tar archive, whose files are added to the overlay
+    Archive(Vec<TarEntry>),
+}
+
+/// Tell a JSON snapshot from a tar archive by its first bytes
+fn parse_import(bytes: &[u8]) -> Result<Import, String> {
+    let text = String::from_utf8_lossy(bytes);
+    if text.trim_start().starts_with('{') {
+        return OverlaySnapshot::from_json(&text)
+            .map(Import::Snapshot)
+            .map_err(|error| error.to_string());
+    }
+    if bytes.get(257..262) == Some(b"ustar".as_slice()) || bytes.len().is_multiple_of(512) {
+        return read_tar(bytes).map(Import::Archive);
+    }
+    Err("not an overlay export: expected JSON or a tar archive".to_string())
+}
+
+fn count(count: usize, one: &str, many: &str) -> String {
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+fn format_status(snapshot: &OverlaySnapshot, persistent: bool) -> String {
+    let directories = snapshot
+        .entries
+        .iter()
+        .filter(|entry| entry.node_type == "directory")
+        .count();
+    let files = snapshot.entries.len() - directories;
+    let saved = if persistent {
+        "Saved in browser storage"
+    } else {
+        "Not saved: changes last until the page is reloaded"
+    };
+    format!(
+        "Overlay: {} and {} created or changed, {} removed\n{}",
+        count(files, "file", "files"),
+        count(directories, "directory", "directories"),
+        count(snapshot.removed.len(), "entry", "entries"),
+        saved
+    )
+}
+
+impl FsCommand {
+    fn export(&self, args: &[String]) -> CommandResult {
+        let mut tar = false;
+        let mut name = None;
+        for arg in args {
+            match arg.as_str() {
+                "--tar" => tar = true,
+                flag if flag.starts_with('-') => {
+                    return CommandResult::Error(format!("fs: unknown option: {}", flag))
+                }
+                _ if name.is_some() => {
+                    return CommandResult::Error(format!("fs: unexpected argument: {}", arg))
+                }
+                _ => name = Some(arg.clone()),
+            }
+        }
+        let snapshot = self.filesystem.borrow().snapshot();
+        if snapshot.is_empty() {
+            return CommandResult::Error("fs: nothing to export, the overlay is empty".to_string());
+        }
+
+        if !tar {
+            let name = name.unwrap_or_else(|| "overlay.json".to_string());
+            return match download_file(&name, snapshot.to_json().as_bytes(), "application/json") {
+                Ok(()) => CommandResult::Success(format!(
+                    "Exported {} to {}",
+                    count(snapshot.entries.len(), "entry", "entries"),
+                    name
+                )),
+                Err(error) => CommandResult::Error(format!("fs: {}", error)),
+            };
+        }
+
+        // Files moved or copied from the site still have their content there
+        let name = name.unwrap_or_else(|| "overlay.tar".to_string());
+        CommandResult::Async(Box::pin(async move {
+            let mut entries = Vec::new();
+            for node in &snapshot.entries {
+                let directory = node.node_type == "directory";
+                let content = if directory {
+                    Vec::new()
+                } else {
+                    match node.content_source().read().await {
+                        Ok(content) => content.into_bytes(),
+                        Err(error) => {
+                            return CommandResult::Error(format!(
+                                "fs: cannot read '{}': {}",
+                                node.path, error
+                            ))
+                        }
+                    }
+                };
+                entries.push(TarEntry {
+                    path: node.path.clone(),
+                    directory,
+                    content,
+                });
+            }
+            match download_file(&name, &write_tar(&entries), "application/x-tar") {
+                Ok(()) => CommandResult::Success(format!(
+                    "Exported {} to {}",
+                    count(entries.len(), "entry", "entries"),
+                    name
+                )),
+                Err(error) => CommandResult::Error(format!("fs: {}", error)),
+            }
+        }))
+    }
+
+    fn import(&self, args: &[String], stdin: Option<String>) -> CommandResult {
+        if let Some(arg) = args.first() {
+            return CommandResult::Error(format!("fs: unexpected argument: {}", arg));
+        }
+        let filesystem = self.filesystem.clone();
+        CommandResult::Async(Box::pin(async move {
+            let (name, bytes) = match stdin {
+                Some(stdin) => ("stdin".to_string(), stdin.into_bytes()),
+                None => match pick_file().await {
+                    Ok(file) => file,
+                    Err(error) => return CommandResult::Error(format!("fs: {}", error)),
+                },
+            };
+            let import = match parse_import(&bytes) {
+                Ok(import) => import,
+                Err(error) => return CommandResult::Error(format!("fs: {}: {}", name, error)),
+            };
+            let mut fs = filesystem.borrow_mut();
+            match import {
+                Import::Snapshot(snapshot) => match fs.restore(&snapshot) {
+                    Ok(()) => CommandResult::Success(format!("Restored the overlay from {}", name)),
+                    Err(error) => CommandResult::Error(format!("fs: {}", error)),
+                },
+                Import::Archive(entries) => {
+                    let mut errors = Vec::new();
+                    let mut imported = 0;
+                    let saved = fs.batch(|fs| {
+                        for entry in entries {
+                            let path = format!("/{}", entry.path);
+                            let result = if entry.directory {
+                                fs.make_directory(&path, true)
+                            } else {
+                                match String::from_utf8(entry.content) {
+                                    Ok(content) => match entry.path.rsplit_once('/') {
+                                        Some((parent, _)) => {
+                                            fs.make_directory(&format!("/{}", parent), true)
+                                        }
+                                        None => Ok(()),
+                                    }
+                                    .and_then(|()| fs.write_file(&path, content)),
+                                    Err(_) => {
+                                        Err(format!("{}: not a text file, skipped", entry.path))
+                                    }
+                                }
+                            };
+                            match result {
+                                Ok(()) => imported += 1,
+                                Err(error) => errors.push(format!("fs: {}", error)),
+                            }
+                        }
+                    });
+                    if let Err(error) = saved {
+                        errors.push(format!("fs: {}", error));
+                    }
+                    if errors.is_empty() {
+                        CommandResult::Success(format!(
+                            "Imported {} from {}",
+                            count(imported, "entry", "entries"),
+                            name
+                        ))
+                    } else {
+                        CommandResult::Error(errors.join("\n"))
+                    }
+                }
+            }
+        }))
+    }
+}
+
+impl Command for FsCommand {
+    fn execute(&self, args: &[String], context: &TerminalContext) -> CommandResult {
+        let Some((subcommand, rest)) = args.split_first() else {
+            return CommandResult::Error(
+                "fs: missing subcommand (status, export or import)".to_string(),
+            );
+        };
+        match subcommand.as_str() {
+            "status" => {
+                if let Some(arg) = rest.first() {
+                    return CommandResult::Error(format!("fs: unexpected argument: {}", arg));
+                }
+                let fs = self.filesystem.borrow();
+                CommandResult::Success(format_status(&fs.snapshot(), fs.is_persistent()))
+            }
+            "export" => self.export(rest),
+            "import" => self.import(rest, context.stdin.clone()),
+            other => CommandResult::Error(format!("fs: unknown subcommand: {}", other)),
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        "Show, export or import created and changed files"
+    }
+
+    fn usage(&self) -> &'static str {
+        "fs <status|export [--tar] [name]|import>"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"fs - Show, export or import created and changed files
+
+Usage:
+  fs status               Count the overlay's changes and say if they are saved
+  fs export [name]        Download the overlay as JSON (default overlay.json)
+  fs export --tar [name]  Download its files as a tar archive (default overlay.tar)
+  fs import               Choose an exported file and load it
+  fs --help               Show this help message
+
+Description:
+  Files you create, change, move or remove are kept in a writable overlay
+  on top of the site's files, and saved in browser storage so they survive
+  a reload. If storage is unavailable or full, changes last until the page
+  is reloaded and commands that change files say so.
+
+  A JSON export records every change, including removals, and importing it
+  replaces the current overlay. A tar export holds only the created and
+  changed files and directories; importing one adds them to the overlay.
+  Non-text files in an archive are skipped. Output piped into fs import is
+  read instead of opening the file picker.
+
+Examples:
+  fs status               Show what has changed
+  fs export backup.json   Save all changes to backup.json
+  fs export --tar         Download the changed files as overlay.tar
+  fs import               Restore changes from an export"#,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_import() {
+        let snapshot =
+            OverlaySnapshot::from_json(r#"{"version":1,"entries":[],"removed":["index.md"]}"#)
+                .unwrap();
+        assert_eq!(
+            parse_import(snapshot.to_json().as_bytes()),
+            Ok(Import::Snapshot(snapshot.clone()))
+        );
+        assert_eq!(
+            format_status(&snapshot, false),
+            "Overlay: 0 files and 0 directories created or changed, 1 entry removed\n\
+             Not saved: changes last until the page is reloaded"
+        );
+
+        let entries = vec![TarEntry {
+            path: "notes.md".to_string(),
+            directory: false,
+            content: b"hi\n".to_vec(),
+        }];
+        assert_eq!(
+            parse_import(&write_tar(&entries)),
+            Ok(Import::Archive(entries))
+        );
+        assert!(parse_import(b"hello").is_err());
+    }
+}
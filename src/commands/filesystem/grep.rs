@@ -0,0 +1,413 @@
+// GREP Command Implementation
+// Search file contents or piped input for lines matching a regex
+
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::components::ansi::strip_ansi;
+use crate::filesystem::find::{find, FindQuery, Predicate};
+use crate::filesystem::types::ContentSource;
+use crate::filesystem::FileSystem;
+use futures::future::join_all;
+use regex::{Regex, RegexBuilder};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const RESET: &str = "\x1b[0m";
+const MATCH_STYLE: &str = "\x1b[1;31m";
+const PATH_STYLE: &str = "\x1b[35m";
+const LINE_NUMBER_STYLE: &str = "\x1b[32m";
+const SEPARATOR_STYLE: &str = "\x1b[36m";
+
+/// Extensions skipped by recursive searches, since their contents aren't text
+const BINARY_EXTENSIONS: [&str; 12] = [
+    "png", "jpg", "jpeg", "gif", "webp", "ico", "pdf", "zip", "woff", "woff2", "ttf", "mp4",
+];
+
+pub struct GrepCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+/// How matches are searched for and printed
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct GrepOptions {
+    recursive: bool,
+    ignore_case: bool,
+    line_numbers: bool,
+    /// Lines of context printed around each match
+    context: usize,
+    /// Only print the names of files with a match
+    files_only: bool,
+}
+
+/// Parsed command line
+#[derive(Debug, Default, PartialEq)]
+struct GrepArgs {
+    options: GrepOptions,
+    pattern: String,
+    paths: Vec<String>,
+}
+
+impl Command for GrepCommand {
+    fn execute(&self, args: &[String], context: &TerminalContext) -> CommandResult {
+        let GrepArgs {
+            options,
+            pattern,
+            mut paths,
+        } = match parse_args(args) {
+            Ok(args) => args,
+            Err(error) => return CommandResult::Error(format!("grep: {}", error)),
+        };
+        let regex = match RegexBuilder::new(&pattern)
+            .case_insensitive(options.ignore_case)
+            .build()
+        {
+            Ok(regex) => regex,
+            Err(error) => return CommandResult::Error(format!("grep: invalid pattern: {}", error)),
+        };
+
+        if paths.is_empty() {
+            if let Some(stdin) = &context.stdin {
+                let output = if options.files_only {
+                    if matching_lines(stdin, &regex).is_empty() {
+                        Vec::new()
+                    } else {
+                        vec![format!("{}(standard input){}", PATH_STYLE, RESET)]
+                    }
+                } else {
+                    search_text(stdin, None, &regex, &options)
+                };
+                return CommandResult::Success(output.join("\n"));
+            }
+            if !options.recursive {
+                return CommandResult::Error("grep: missing file operand".to_string());
+            }
+            paths.push(".".to_string());
+        }
+
+        let (files, mut messages) = self.collect_files(&paths, options.recursive);
+        let show_names = files.len() > 1 || options.recursive;
+
+        CommandResult::Async(Box::pin(async move {
+            let contents = join_all(files.iter().map(|(_, source)| source.read_cached())).await;
+
+            let mut output = Vec::new();
+            for ((display_path, _), content) in files.iter().zip(contents) {
+                let content = match content {
+                    Ok(content) => content,
+                    Err(error) => {
+                        messages.push(format!("grep: {}: {}", display_path, error));
+                        continue;
+                    }
+                };
+                if options.files_only {
+                    if !matching_lines(&content, &regex).is_empty() {
+                        output.push(format!("{}{}{}", PATH_STYLE, display_path, RESET));
+                    }
+                } else {
+                    let label = show_names.then_some(display_path.as_str());
+                    output.extend(search_text(&content, label, &regex, &options));
+                }
+            }
+
+            if output.is_empty() && !messages.is_empty() {
+                return CommandResult::Error(messages.join("\n"));
+            }
+            output.extend(messages);
+            CommandResult::Success(output.join("\n"))
+        }))
+    }
+
+    fn description(&self) -> &'static str {
+        "Search files for lines matching a pattern"
+    }
+
+    fn usage(&self) -> &'static str {
+        "grep [-r] [-i] [-n] [-C n] [-l] <pattern> [path...]"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"grep - Search files for lines matching a pattern
+
+Usage:
+  grep [options] <pattern> [path...]   Search files for a regular expression
+  command | grep [options] <pattern>   Search the output of another command
+  grep --help                          Show this help message
+
+Options:
+  -r                      Search directories recursively (default path: .)
+  -i                      Ignore case
+  -n                      Show line numbers
+  -C <n>                  Show n lines of context around each match
+  -l                      Only list the files that contain a match
+
+Description:
+  Prints every line that matches the pattern, with the matches highlighted.
+  When more than one file is searched each line starts with its path.
+  The pattern is a regular expression; quote it if it contains spaces or |.
+  Files are downloaded once and kept, so repeated searches are instant.
+
+Examples:
+  grep -rn rust /                 Find "rust" in every file, with line numbers
+  grep -i -C 2 install README.md  Show matches with two lines around them
+  grep -rl 'todo|fixme' posts     List files under posts mentioning either word
+  cat about.md | grep -n email    Search another command's output"#,
+        )
+    }
+}
+
+impl GrepCommand {
+    /// Files to search as `(display path, content source)`, and errors for the rest
+    fn collect_files(
+        &self,
+        paths: &[String],
+        recursive: bool,
+    ) -> (Vec<(String, ContentSource)>, Vec<String>) {
+        let fs = self.filesystem.borrow();
+        let mut files = Vec::new();
+        let mut messages = Vec::new();
+
+        for path in paths {
+            let lookup = match path.trim_end_matches('/') {
+                "" => "/",
+                trimmed => trimmed,
+            };
+            let node = match fs.get_node(lookup) {
+                Ok(node) => node,
+                Err(error) => {
+                    messages.push(format!("grep: {}", error));
+                    continue;
+                }
+            };
+
+            if node.node_type != "directory" {
+                files.push((path.clone(), node.content_source()));
+            } else if recursive {
+                let query = FindQuery {
+                    predicates: vec![Predicate::Type { directory: false }],
+                    max_depth: None,
+                };
+                files.extend(
+                    find(node, path, &query)
+                        .into_iter()
+                        .filter(|(_, file)| {
+                            !file
+                                .extension
+                                .as_deref()
+                                .is_some_and(|ext| BINARY_EXTENSIONS.contains(&ext))
+                        })
+                        .map(|(display_path, file)| (display_path, file.content_source())),
+                );
+            } else {
+                messages.push(format!("grep: {}: Is a directory", path));
+            }
+        }
+
+        (files, messages)
+    }
+}
+
+/// Split arguments into options, the pattern and the paths to search
+///
+/// Short options can be combined (`-rin`) and `-C` takes its count either
+/// attached (`-C2`) or as the next argument. `--` ends the options.
+fn parse_args(args: &[String]) -> Result<GrepArgs, String> {
+    let mut options = GrepOptions::default();
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            positional.extend(args.by_ref().cloned());
+            break;
+        }
+        let Some(flags) = arg.strip_prefix('-').filter(|flags| !flags.is_empty()) else {
+            positional.push(arg.clone());
+            continue;
+        };
+
+        for (index, flag) in flags.char_indices() {
+            match flag {
+                'r' | 'R' => options.recursive = true,
+                'i' => options.ignore_case = true,
+                'n' => options.line_numbers = true,
+                'l' => options.files_only = true,
+                'C' => {
+                    let attached = &flags[index + 1..];
+                    let count = if attached.is_empty() {
+                        args.next()
+                            .ok_or("option requires an argument -- 'C'")?
+                            .clone()
+                    } else {
+                        attached.to_string()
+                    };
+                    options.context = count
+                        .parse()
+                        .map_err(|_| format!("{}: invalid context length argument", count))?;
+                    break;
+                }
+                _ => return Err(format!("invalid option -- '{}'", flag)),
+            }
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let pattern = positional.next().ok_or("missing pattern")?;
+    Ok(GrepArgs {
+        options,
+        pattern,
+        paths: positional.collect(),
+    })
+}
+
+/// Indexes of the lines that match, ignoring any ANSI styling in the text
+fn matching_lines(text: &str, regex: &Regex) -> Vec<usize> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| regex.is_match(&strip_ansi(line)))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Output lines for the matches in `text`, prefixed by `label` if given
+///
+/// Matching lines use `:` after the label and line number, context lines
+/// `-`, and `--` separates groups of lines that aren't adjacent.
+fn search_text(
+    text: &str,
+    label: Option<&str>,
+    regex: &Regex,
+    options: &GrepOptions,
+) -> Vec<String> {
+    let lines: Vec<String> = text.lines().map(strip_ansi).collect();
+    let matches = matching_lines(text, regex);
+    let mut output = Vec::new();
+    // Index after the last line printed
+    let mut printed_until: Option<usize> = None;
+
+    for &matched in &matches {
+        let start = matched.saturating_sub(options.context);
+        let end = (matched + options.context + 1).min(lines.len());
+        let start = match printed_until {
+            Some(until) if start > until => {
+                if options.context > 0 {
+                    output.push(format!("{}--{}", SEPARATOR_STYLE, RESET));
+                }
+                start
+            }
+            Some(until) => until,
+            None => start,
+        };
+
+        for (index, line) in lines.iter().enumerate().take(end).skip(start) {
+            let is_match = matches.binary_search(&index).is_ok();
+            let separator = if is_match { ':' } else { '-' };
+            let mut prefix = String::new();
+            if let Some(label) = label {
+                prefix.push_str(&format!(
+                    "{}{}{}{}{}",
+                    PATH_STYLE, label, SEPARATOR_STYLE, separator, RESET
+                ));
+            }
+            if options.line_numbers {
+                prefix.push_str(&format!(
+                    "{}{}{}{}{}",
+                    LINE_NUMBER_STYLE,
+                    index + 1,
+                    SEPARATOR_STYLE,
+                    separator,
+                    RESET
+                ));
+            }
+            if !prefix.is_empty() {
+                prefix.push(' ');
+            }
+            let text = if is_match {
+                highlight(line, regex)
+            } else {
+                line.clone()
+            };
+            output.push(format!("{}{}", prefix, text));
+        }
+        printed_until = Some(end);
+    }
+
+    output
+}
+
+/// Wrap every non-empty match in the match color
+fn highlight(line: &str, regex: &Regex) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut last = 0;
+    for found in regex.find_iter(line).filter(|found| !found.is_empty()) {
+        output.push_str(&line[last..found.start()]);
+        output.push_str(&format!("{}{}{}", MATCH_STYLE, found.as_str(), RESET));
+        last = found.end();
+    }
+    output.push_str(&line[last..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::args;
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = parse_args(&args("-rin -C2 fn src docs")).unwrap();
+        assert_eq!(
+            parsed.options,
+            GrepOptions {
+                recursive: true,
+                ignore_case: true,
+                line_numbers: true,
+                context: 2,
+                files_only: false,
+            }
+        );
+        assert_eq!(parsed.pattern, "fn");
+        assert_eq!(parsed.paths, ["src", "docs"]);
+
+        assert_eq!(parse_args(&args("-l -C 1 -- -x")).unwrap().pattern, "-x");
+        assert_eq!(
+            parse_args(&args("-q x")).unwrap_err(),
+            "invalid option -- 'q'"
+        );
+        assert_eq!(parse_args(&args("-n")).unwrap_err(), "missing pattern");
+    }
+
+    #[test]
+    fn test_search_with_context_and_highlight() {
+        let text = "one\ntwo match\nthree\nfour\nfive\nsix match\n";
+        let regex = Regex::new("match").unwrap();
+        let options = GrepOptions {
+            line_numbers: true,
+            context: 1,
+            ..GrepOptions::default()
+        };
+
+        let output: Vec<String> = search_text(text, Some("a.md"), &regex, &options)
+            .iter()
+            .map(|line| strip_ansi(line))
+            .collect();
+        assert_eq!(
+            output,
+            [
+                "a.md-1- one",
+                "a.md:2: two match",
+                "a.md-3- three",
+                "--",
+                "a.md-5- five",
+                "a.md:6: six match",
+            ]
+        );
+
+        let plain = search_text(
+            "\x1b[1mbold match\x1b[0m",
+            None,
+            &regex,
+            &GrepOptions::default(),
+        );
+        assert_eq!(plain, ["bold \x1b[1;31mmatch\x1b[0m"]);
+    }
+}
@@ -0,0 +1,83 @@
+// HEAD Command Implementation
+// Print the first lines of files or piped input
+
+use super::text_input::{into_result, join_outputs, parse_line_count, TextSources};
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::FileSystem;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct HeadCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+impl Command for HeadCommand {
+    fn execute(&self, args: &[String], context: &TerminalContext) -> CommandResult {
+        let (count, paths) = match parse_line_count(args, 10) {
+            Ok(parsed) => parsed,
+            Err(error) => return CommandResult::Error(format!("head: {}", error)),
+        };
+        let sources =
+            match TextSources::resolve(&self.filesystem.borrow(), &paths, context.stdin.as_ref()) {
+                Ok(sources) => sources,
+                Err(error) => return CommandResult::Error(format!("head: {}", error)),
+            };
+        let multiple = sources.is_multiple();
+
+        CommandResult::Async(Box::pin(async move {
+            let inputs = sources.read().await;
+            let (output, errors) = join_outputs(inputs, multiple, "head", |content| {
+                first_lines(content, count)
+            });
+            into_result(output, errors)
+        }))
+    }
+
+    fn description(&self) -> &'static str {
+        "Print the first lines of files"
+    }
+
+    fn usage(&self) -> &'static str {
+        "head [-n lines] [file...]"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"head - Print the first lines of files
+
+Usage:
+  head [file...]          Print the first 10 lines of each file
+  head -n <lines> [file...]
+                          Print the first <lines> lines
+  command | head          Print the first lines of another command's output
+  head --help             Show this help message
+
+Description:
+  With several files, each one is printed under a ==> name <== header.
+  -<lines> (e.g. -5) is short for -n <lines>.
+
+Examples:
+  head index.md           First 10 lines of index.md
+  head -n 3 a.md b.md     First 3 lines of a.md and of b.md
+  grep -rn todo / | head -5
+                          First 5 matches"#,
+        )
+    }
+}
+
+/// The first `count` lines of `content`
+fn first_lines(content: &str, count: usize) -> String {
+    content.lines().take(count).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_lines() {
+        assert_eq!(first_lines("a\nb\nc\n", 2), "a\nb");
+        assert_eq!(first_lines("a\nb", 5), "a\nb");
+        assert_eq!(first_lines("a\nb", 0), "");
+    }
+}
@@ -0,0 +1,107 @@
+// HEADINGS Command Implementation
+// List the heading outline of a markdown file
+
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::FileSystem;
+use crate::utils::app_config::MarkdownConfig;
+use crate::utils::file_utils::toc::extract_headings;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct HeadingsCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+impl Command for HeadingsCommand {
+    fn execute(&self, args: &[String], context: &TerminalContext) -> CommandResult {
+        if args.is_empty() {
+            return CommandResult::Error("headings: missing filename".to_string());
+        }
+
+        let filename = &args[0];
+        let fs = self.filesystem.borrow();
+
+        match fs.get_file_info(filename) {
+            Ok(node) => {
+                let is_markdown = node
+                    .extension
+                    .as_ref()
+                    .is_some_and(|ext| ext == "md" || ext == "markdown");
+                if !is_markdown {
+                    return CommandResult::Error(format!(
+                        "headings: not a markdown file: {}",
+                        filename
+                    ));
+                }
+
+                let source = node.content_source();
+                let config = context.app_config.config.markdown.clone();
+                let future = Box::pin(async move {
+                    match source.read().await {
+                        Ok(content) => CommandResult::Success(format_outline(&content, &config)),
+                        Err(error) => {
+                            CommandResult::Error(format!("headings: Error reading file: {}", error))
+                        }
+                    }
+                });
+
+                CommandResult::Async(future)
+            }
+            Err(error) => CommandResult::Error(format!("headings: {}", error)),
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        "List the headings of a markdown file"
+    }
+
+    fn usage(&self) -> &'static str {
+        "headings <file.md>"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(r#"headings - List the headings of a markdown file
+
+Usage:
+  headings <file.md>      Show the heading outline with anchor ids
+  headings --help         Show this help message
+
+Description:
+  Prints one line per heading, indented by level, followed by the id
+  the heading gets when rendered. Pass the id to view to jump to it.
+
+Examples:
+  headings index.md       Outline index.md
+  view index.md#usage     Open index.md at the heading with id "usage""#)
+    }
+}
+
+/// One indented line per heading, e.g. `  Usage  #usage`
+fn format_outline(content: &str, config: &MarkdownConfig) -> String {
+    let headings = extract_headings(content, config);
+    let min_level = headings.iter().map(|h| h.level).min().unwrap_or(1);
+
+    headings
+        .iter()
+        .map(|heading| {
+            format!(
+                "{}{}  #{}",
+                "  ".repeat(heading.level - min_level),
+                heading.text,
+                heading.id
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outline_indents_by_level() {
+        let outline = format_outline("## Setup\n### Install\n## Usage\n", &MarkdownConfig::default());
+        assert_eq!(outline, "Setup  #setup\n  Install  #install\nUsage  #usage");
+    }
+}
@@ -1,8 +1,10 @@
 // LS Command Implementation
 // List directory contents command
 
-use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::commands::{Command, CommandExecutor, CommandResult, TerminalContext};
+use crate::filesystem::types::FileSystemNode;
 use crate::filesystem::FileSystem;
+use crate::utils::html_sanitizer::escape_html;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -10,32 +12,68 @@ pub struct LsCommand {
     pub filesystem: Rc<RefCell<FileSystem>>,
 }
 
+/// Order of entries within a listing
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum SortKey {
+    #[default]
+    Name,
+    /// Newest first
+    Time,
+    /// Largest first
+    Size,
+}
+
+/// What to list and how
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct LsOptions {
+    link: bool,
+    long: bool,
+    all: bool,
+    sort: SortKey,
+    reverse: bool,
+    human: bool,
+    recursive: bool,
+    color: bool,
+}
+
+/// One block of output: the file operands, or the entries of one directory
+struct Section<'a> {
+    /// `path:` line shown above a directory's entries
+    heading: Option<String>,
+    /// `(name, node)`, directory names ending in `/`
+    entries: Vec<(String, &'a FileSystemNode)>,
+}
+
 impl Command for LsCommand {
     fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        let (options, paths) = match parse_args(args) {
+            Ok(parsed) => parsed,
+            Err(error) => return CommandResult::Error(format!("ls: {}", error)),
+        };
         let fs = self.filesystem.borrow();
+        let (sections, errors) = collect_sections(&fs, &paths, &options);
 
-        // Check for --link option
-        let use_link_format = args.contains(&"--link".to_string());
-        let target_dir = if use_link_format {
-            // Find directory argument (not --link)
-            args.iter().find(|&arg| arg != "--link").map(|s| s.as_str())
-        } else {
-            args.first().map(|s| s.as_str())
-        };
+        if options.link {
+            return Self::execute_link_format(&sections, errors);
+        }
 
-        if use_link_format {
-            Self::execute_link_format(&*fs, target_dir)
-        } else {
-            fs.read_directory(target_dir)
-                .map(|items| {
-                    let output = if items.is_empty() {
-                        String::new()
-                    } else {
-                        items.join("  ")
-                    };
-                    CommandResult::Success(output)
-                })
-                .unwrap_or_else(|error| CommandResult::Error(format!("ls: {}", error)))
+        let output = sections
+            .iter()
+            .map(|section| {
+                let body = format_entries(&section.entries, &options);
+                match &section.heading {
+                    Some(heading) if body.is_empty() => format!("{}:", heading),
+                    Some(heading) => format!("{}:\n{}", heading, body),
+                    None => body,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        match (sections.is_empty(), errors.is_empty()) {
+            (true, false) => CommandResult::Error(errors.join("\n")),
+            (false, false) => CommandResult::Success(format!("{}\n{}", errors.join("\n"), output)),
+            _ => CommandResult::Success(output),
         }
     }
 
@@ -44,7 +82,7 @@ impl Command for LsCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "ls [directory] [--link]"
+        "ls [-latSrhR] [--link] [--color] [path...]"
     }
 
     fn help(&self) -> Option<&'static str> {
@@ -52,133 +90,449 @@ impl Command for LsCommand {
             r#"ls - List directory contents
 
 Usage:
-  ls [directory]          List contents of directory
-  ls --link [directory]   List with detailed format showing title, description, and modified date
+  ls [options] [path...]  List each directory's contents, or each file
   ls                      List contents of current directory
   ls --help               Show this help message
 
+Options:
+  -l                      Long format: type, size, modified date and name
+  -a                      Also show entries whose names start with .
+  -t                      Sort by modified date, newest first
+  -S                      Sort by size, largest first
+  -r                      Reverse the sort order
+  -h                      With -l, show sizes as 1.5K, 20M, ... (e.g. -lh;
+                          a lone first -h shows this help)
+  -R                      List subdirectories recursively
+  --color                 Color names by type (directories, markdown, images)
+  --link                  Show clickable cards with title, description and
+                          modified date
+  Short options can be combined, e.g. -lth.
+
 Description:
-  The ls command lists the contents of the specified directory.
-  If no directory is specified, lists the current directory.
-  All paths are relative to the current working directory.
+  The ls command lists the contents of the specified directories and
+  names the specified files. If no path is specified, lists the current
+  directory. With several paths or -R, each directory's entries are shown
+  under a path: heading. Entries are sorted by name unless -t or -S is
+  given. All paths are relative to the current working directory.
   
   The --link option shows a detailed vertical format with:
   - Icon (📁 for directories, 📄 for markdown files)
   - Title (from metadata or filename)
   - Description (from metadata if available)
   - Modified date
+  Sorting and -a, -R also apply to it.
 
 Examples:
   ls                      List current directory contents
-  ls about                List contents of about directory
+  ls about posts          List the about and posts directories
+  ls -lh                  Long listing with readable sizes
+  ls -ltr /posts          Oldest posts first
+  ls -R --color /         Every file, colored by type
   ls --link               List current directory with detailed format
-  ls --link about         List about directory with detailed format"#,
+  ls --link -t about      List about directory, newest first, as cards"#,
         )
     }
 }
 
-impl LsCommand {
-    fn execute_link_format(fs: &FileSystem, target_dir: Option<&str>) -> CommandResult {
-        // Use the new method that provides direct access to metadata
-        match fs.read_directory_with_metadata(target_dir) {
-            Ok(items) => {
-                let mut output_lines = Vec::new();
+/// Split arguments into options and paths; `--` ends the options
+fn parse_args(args: &[String]) -> Result<(LsOptions, Vec<String>), String> {
+    let mut options = LsOptions::default();
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--" => {
+                paths.extend(args.by_ref().cloned());
+                break;
+            }
+            "--link" => options.link = true,
+            "--color" => options.color = true,
+            long if long.starts_with("--") => {
+                return Err(format!("unrecognized option '{}'", long))
+            }
+            short if short.starts_with('-') && short.len() > 1 => {
+                for flag in short.chars().skip(1) {
+                    match flag {
+                        'l' => options.long = true,
+                        'a' => options.all = true,
+                        't' => options.sort = SortKey::Time,
+                        'S' => options.sort = SortKey::Size,
+                        'r' => options.reverse = true,
+                        'h' => options.human = true,
+                        'R' => options.recursive = true,
+                        _ => return Err(format!("invalid option -- '{}'", flag)),
+                    }
+                }
+            }
+            _ => paths.push(arg.clone()),
+        }
+    }
 
-                // No headers or separators - clean output
+    Ok((options, paths))
+}
+
+/// Sections for the path operands: files first, then each directory
+///
+/// Directories get a heading when there is more than one operand or with
+/// `-R`, which adds a section for every subdirectory below them.
+fn collect_sections<'a>(
+    fs: &'a FileSystem,
+    paths: &[String],
+    options: &LsOptions,
+) -> (Vec<Section<'a>>, Vec<String>) {
+    let operands = if paths.is_empty() {
+        vec![".".to_string()]
+    } else {
+        paths.to_vec()
+    };
+    let show_headings = operands.len() > 1 || options.recursive;
+
+    let mut errors = Vec::new();
+    let mut files = Vec::new();
+    let mut directories = Vec::new();
+    for path in &operands {
+        let lookup = match path.trim_end_matches('/') {
+            "" => "/",
+            trimmed => trimmed,
+        };
+        match fs.get_node(lookup) {
+            Ok(node) if is_directory(node) => directories.push((path.clone(), node)),
+            Ok(node) => files.push((path.clone(), node)),
+            Err(error) => errors.push(format!("ls: cannot access '{}': {}", path, error)),
+        }
+    }
+
+    let mut sections = Vec::new();
+    if !files.is_empty() {
+        sort_entries(&mut files, options);
+        sections.push(Section {
+            heading: None,
+            entries: files,
+        });
+    }
+    sort_entries(&mut directories, options);
+    for (path, node) in directories {
+        add_directory(&mut sections, path, node, show_headings, options);
+    }
+
+    (sections, errors)
+}
+
+fn add_directory<'a>(
+    sections: &mut Vec<Section<'a>>,
+    path: String,
+    node: &'a FileSystemNode,
+    show_heading: bool,
+    options: &LsOptions,
+) {
+    let mut entries: Vec<(String, &FileSystemNode)> = node
+        .children
+        .values()
+        .filter(|child| options.all || !child.name.starts_with('.'))
+        .map(|child| {
+            let name = if is_directory(child) {
+                format!("{}/", child.name)
+            } else {
+                child.name.clone()
+            };
+            (name, child)
+        })
+        .collect();
+    sort_entries(&mut entries, options);
+
+    let subdirectories: Vec<(String, &FileSystemNode)> = if options.recursive {
+        entries
+            .iter()
+            .filter(|(_, child)| is_directory(child))
+            .map(|(name, child)| {
+                (
+                    format!(
+                        "{}/{}",
+                        path.trim_end_matches('/'),
+                        name.trim_end_matches('/')
+                    ),
+                    *child,
+                )
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    sections.push(Section {
+        heading: show_heading.then_some(path),
+        entries,
+    });
+    for (path, child) in subdirectories {
+        add_directory(sections, path, child, true, options);
+    }
+}
+
+/// Sort by name, then by the `-t`/`-S` key, reversed with `-r`
+///
+/// The sorts are stable, so entries with equal keys stay in name order.
+fn sort_entries(entries: &mut [(String, &FileSystemNode)], options: &LsOptions) {
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    match options.sort {
+        SortKey::Name => {}
+        SortKey::Time => entries.sort_by(|a, b| modified(b.1).cmp(&modified(a.1))),
+        SortKey::Size => entries.sort_by_key(|entry| std::cmp::Reverse(entry.1.size)),
+    }
+    if options.reverse {
+        entries.reverse();
+    }
+}
+
+fn is_directory(node: &FileSystemNode) -> bool {
+    node.node_type == "directory"
+}
+
+/// Modified time, taken from index.md for directories as `--link` does
+fn modified(node: &FileSystemNode) -> Option<&str> {
+    let index = is_directory(node)
+        .then(|| node.children.get("index.md"))
+        .flatten();
+    index
+        .and_then(|index| index.modified.as_deref())
+        .or(node.modified.as_deref())
+}
 
-                // If no items, show an empty result message
-                if items.is_empty() {
-                    let html = r#"<div class="file-list-empty">
+/// Entries as a two-space separated line, or one `-l` line each
+fn format_entries(entries: &[(String, &FileSystemNode)], options: &LsOptions) -> String {
+    if !options.long {
+        return entries
+            .iter()
+            .map(|(name, node)| display_name(name, node, options))
+            .collect::<Vec<_>>()
+            .join("  ");
+    }
+
+    let sizes: Vec<String> = entries
+        .iter()
+        .map(|(_, node)| match node.size {
+            Some(size) if options.human => human_size(size),
+            Some(size) => size.to_string(),
+            None => "-".to_string(),
+        })
+        .collect();
+    let width = sizes.iter().map(|size| size.len()).max().unwrap_or(0);
+
+    entries
+        .iter()
+        .zip(sizes)
+        .map(|((name, node), size)| {
+            // Only overlay entries can be written
+            let mode = match (is_directory(node), node.overlay) {
+                (true, true) => "drwxr-xr-x",
+                (true, false) => "dr-xr-xr-x",
+                (false, true) => "-rw-r--r--",
+                (false, false) => "-r--r--r--",
+            };
+            // `2025-06-29T09:22:09.423Z` -> `2025-06-29 09:22`
+            let date = modified(node)
+                .map(|date| date.replacen('T', " ", 1).chars().take(16).collect())
+                .unwrap_or_else(|| "-".to_string());
+            format!(
+                "{} {:>width$} {:<16} {}",
+                mode,
+                size,
+                date,
+                display_name(name, node, options),
+                width = width
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Size with a K/M/G/T suffix, one decimal below 10, like `ls -h`
+fn human_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        return bytes.to_string();
+    }
+    let mut value = bytes as f64;
+    for unit in ["K", "M", "G", "T"] {
+        value /= 1024.0;
+        if value < 1024.0 || unit == "T" {
+            return if value < 10.0 {
+                format!("{:.1}{}", value, unit)
+            } else {
+                format!("{:.0}{}", value, unit)
+            };
+        }
+    }
+    unreachable!()
+}
+
+/// Name as listed: colored, and marked with `+` if it is in the overlay
+fn display_name(name: &str, node: &FileSystemNode, options: &LsOptions) -> String {
+    let name = colorize(name, node, options);
+    if node.overlay {
+        format!("{}+", name)
+    } else {
+        name
+    }
+}
+
+/// Name in the ANSI color of its type when `--color` is given
+fn colorize(name: &str, node: &FileSystemNode, options: &LsOptions) -> String {
+    if !options.color {
+        return name.to_string();
+    }
+    let extension = node.extension.as_deref().unwrap_or("").to_lowercase();
+    let code = if is_directory(node) {
+        "1;34"
+    } else if extension == "md" {
+        "32"
+    } else if matches!(
+        extension.as_str(),
+        "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "ico"
+    ) {
+        "35"
+    } else {
+        return name.to_string();
+    };
+    format!("\x1b[{}m{}\x1b[0m", code, name)
+}
+
+impl LsCommand {
+    fn execute_link_format(sections: &[Section], errors: Vec<String>) -> CommandResult {
+        if sections.is_empty() {
+            return CommandResult::Error(errors.join("\n"));
+        }
+        // If no items, show an empty result message
+        if sections.len() == 1 && sections[0].entries.is_empty() {
+            let html = r#"<div class="file-list-empty">
                         <p>(empty directory)</p>
                     </div>"#;
-                    return CommandResult::Html(html.to_string());
-                }
+            return CommandResult::Html(html.to_string());
+        }
 
-                // Start building HTML
-                output_lines.push("<div class=\"file-list\">".to_string());
-                
-                for (item_name, node_opt) in items {
-                    let item_name_clean = item_name.trim_end_matches('/');
-                    let is_directory = item_name.ends_with('/');
-
-                    let (icon, title, description, modified) = if let Some(node) = node_opt {
-                        if is_directory {
-                            // For directories, try to find index.md in children
-                            if let Some(index_node) = node.children.get("index.md") {
-                                (
-                                    Self::get_folder_svg(),
-                                    index_node.title.as_deref().unwrap_or(item_name_clean),
-                                    index_node.description.as_deref().unwrap_or("-"),
-                                    index_node.modified.as_deref().unwrap_or("-"),
-                                )
-                            } else {
-                                (Self::get_folder_svg(), item_name_clean, "-", "-")
-                            }
-                        } else {
-                            // Regular file - use its metadata directly
-                            let icon = if item_name_clean.ends_with(".md") {
-                                Self::get_markdown_svg()
-                            } else {
-                                Self::get_file_svg()
-                            };
-                            (
-                                icon,
-                                node.title.as_deref().unwrap_or(item_name_clean),
-                                node.description.as_deref().unwrap_or("-"),
-                                node.modified.as_deref().unwrap_or("-"),
-                            )
-                        }
-                    } else {
-                        // Fallback if no metadata
-                        let icon = if is_directory {
-                            Self::get_folder_svg()
-                        } else if item_name_clean.ends_with(".md") {
-                            Self::get_markdown_svg()
-                        } else {
-                            Self::get_file_svg()
-                        };
-                        (icon, item_name_clean, "-", "-")
-                    };
+        let mut output = errors
+            .iter()
+            .map(|error| {
+                format!(
+                    r#"<div class="file-list-error">{}</div>"#,
+                    escape_html(error)
+                )
+            })
+            .collect::<Vec<_>>();
+        for section in sections {
+            if let Some(heading) = &section.heading {
+                output.push(format!(
+                    r#"<div class="file-list-heading">{}:</div>"#,
+                    escape_html(heading)
+                ));
+            }
+            let items: Vec<(String, Option<&FileSystemNode>)> = section
+                .entries
+                .iter()
+                .map(|(name, node)| (name.clone(), Some(*node)))
+                .collect();
+            output.push(Self::render_link_list(&items));
+        }
+        CommandResult::Html(output.join("\n"))
+    }
+
+    /// Clickable cards for `(name, node)` items; directory names end in `/`
+    ///
+    /// Clicks open the node's absolute path, or the name when there is no node.
+    pub fn render_link_list(items: &[(String, Option<&FileSystemNode>)]) -> String {
+        let mut output_lines = Vec::new();
+
+        // No headers or separators - clean output
+        output_lines.push("<div class=\"file-list\">".to_string());
 
-                    // Determine click action based on file type
-                    let click_action = if is_directory {
-                        format!("navigate {}", item_name_clean)
-                    } else if item_name_clean.ends_with(".md") {
-                        format!("view {}", item_name_clean)
+        for (item_name, node_opt) in items {
+            let item_name_clean = item_name.trim_end_matches('/');
+            let is_directory = item_name.ends_with('/');
+
+            let (icon, title, description, modified) = if let Some(node) = node_opt {
+                if is_directory {
+                    // For directories, try to find index.md in children
+                    if let Some(index_node) = node.children.get("index.md") {
+                        (
+                            Self::get_folder_svg(),
+                            index_node.title.as_deref().unwrap_or(item_name_clean),
+                            index_node.description.as_deref().unwrap_or("-"),
+                            index_node.modified.as_deref().unwrap_or("-"),
+                        )
+                    } else {
+                        (Self::get_folder_svg(), item_name_clean, "-", "-")
+                    }
+                } else {
+                    // Regular file - use its metadata directly
+                    let icon = if item_name_clean.ends_with(".md") {
+                        Self::get_markdown_svg()
                     } else {
-                        format!("cat {}", item_name_clean)
+                        Self::get_file_svg()
                     };
-                    
-                    let formatted_modified = Self::format_date(modified);
-                    let truncated_title = Self::truncate(title, 40);
-                    let truncated_description = Self::truncate(description, 60);
-                    
-                    // Create clickable HTML list item
-                    let html_item = format!(
-                        r#"<div class="file-item" onclick="window.executeCommand('{}')">
-                            <span class="file-icon">{}</span>
-                            <div class="file-info">
-                                <div class="file-title">{}</div>
-                                <div class="file-description">{}</div>
-                            </div>
-                            <div class="file-modified">{}</div>
-                        </div>"#,
-                        click_action,
+                    (
                         icon,
-                        truncated_title,
-                        truncated_description,
-                        formatted_modified
-                    );
-                    
-                    output_lines.push(html_item);
+                        node.title.as_deref().unwrap_or(item_name_clean),
+                        node.description.as_deref().unwrap_or("-"),
+                        node.modified.as_deref().unwrap_or("-"),
+                    )
                 }
-                
-                output_lines.push("</div>".to_string());
+            } else {
+                // Fallback if no metadata
+                let icon = if is_directory {
+                    Self::get_folder_svg()
+                } else if item_name_clean.ends_with(".md") {
+                    Self::get_markdown_svg()
+                } else {
+                    Self::get_file_svg()
+                };
+                (icon, item_name_clean, "-", "-")
+            };
 
-                CommandResult::Html(output_lines.join("\n"))
-            }
-            Err(error) => CommandResult::Error(format!("ls --link error: {}", error)),
+            // Determine click action based on file type
+            let target = node_opt.map_or_else(
+                || item_name_clean.to_string(),
+                |node| format!("/{}", node.path),
+            );
+            let quoted_name = CommandExecutor::quote_arg(&target);
+            let click_action = if is_directory {
+                format!("navigate {}", quoted_name)
+            } else if item_name_clean.ends_with(".md") {
+                format!("view {}", quoted_name)
+            } else {
+                format!("cat {}", quoted_name)
+            };
+            
+            let formatted_modified = Self::format_date(modified);
+            let truncated_title = Self::truncate(title, 40);
+            let truncated_description = Self::truncate(description, 60);
+            let overlay_mark = if node_opt.is_some_and(|node| node.overlay) {
+                r#"<span class="file-overlay" title="Created in this session">+</span>"#
+            } else {
+                ""
+            };
+            
+            // Create clickable HTML list item
+            let html_item = format!(
+                r#"<div class="file-item" data-command="{}">
+                    <span class="file-icon">{}</span>
+                    <div class="file-info">
+                        <div class="file-title">{}{}</div>
+                        <div class="file-description">{}</div>
+                    </div>
+                    <div class="file-modified">{}</div>
+                </div>"#,
+                escape_html(&click_action),
+                icon,
+                escape_html(&truncated_title),
+                overlay_mark,
+                escape_html(&truncated_description),
+                escape_html(&formatted_modified)
+            );
+            
+            output_lines.push(html_item);
         }
+
+        output_lines.push("</div>".to_string());
+        output_lines.join("\n")
     }
 
     fn truncate(text: &str, max_len: usize) -> String {
@@ -222,7 +576,7 @@ impl LsCommand {
         )
     }
     
-    fn get_markdown_svg() -> String {
+    pub fn get_markdown_svg() -> String {
         format!(
             r#"<svg width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="{}" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
             <path d="M14 2H6a2 2 0 0 0-2 2v16a2 2 0 0 0 2 2h12a2 2 0 0 0 2-2V8z"/>
@@ -245,3 +599,74 @@ impl LsCommand {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{args, node};
+
+    fn entry(
+        name: &str,
+        node_type: &str,
+        size: Option<u64>,
+        modified: Option<&str>,
+    ) -> FileSystemNode {
+        FileSystemNode {
+            size,
+            modified: modified.map(String::from),
+            ..node(name, node_type, vec![])
+        }
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let (options, paths) = parse_args(&args("-lth posts --link -- -a")).unwrap();
+        assert!(options.long && options.human && options.link && !options.all);
+        assert_eq!(options.sort, SortKey::Time);
+        assert_eq!(paths, args("posts -a"));
+        assert!(parse_args(&args("-x")).is_err());
+        assert!(parse_args(&args("--nope")).is_err());
+    }
+
+    #[test]
+    fn test_sort_and_long_format() {
+        let old = entry("old.md", "file", Some(2389), Some("2024-01-01T08:00:00Z"));
+        let new = entry("new.md", "file", Some(24), Some("2025-06-29T09:22:09.423Z"));
+        let dir = entry("posts", "directory", None, None);
+        let mut entries = vec![
+            ("old.md".to_string(), &old),
+            ("posts/".to_string(), &dir),
+            ("new.md".to_string(), &new),
+        ];
+
+        let by_time = LsOptions {
+            sort: SortKey::Time,
+            ..LsOptions::default()
+        };
+        sort_entries(&mut entries, &by_time);
+        assert_eq!(format_entries(&entries, &by_time), "new.md  old.md  posts/");
+
+        let long = LsOptions {
+            long: true,
+            human: true,
+            sort: SortKey::Size,
+            reverse: true,
+            ..LsOptions::default()
+        };
+        sort_entries(&mut entries, &long);
+        assert_eq!(
+            format_entries(&entries, &long),
+            "dr-xr-xr-x    - -                posts/\n\
+             -r--r--r--   24 2025-06-29 09:22 new.md\n\
+             -r--r--r-- 2.3K 2024-01-01 08:00 old.md"
+        );
+        let mut created = entry("notes", "directory", None, Some("2026-01-01T00:00:00Z"));
+        created.overlay = true;
+        assert_eq!(
+            format_entries(&[("notes/".to_string(), &created)], &long),
+            "drwxr-xr-x - 2026-01-01 00:00 notes/+"
+        );
+        assert_eq!(human_size(1023), "1023");
+        assert_eq!(human_size(20 * 1024 * 1024), "20M");
+    }
+}
@@ -0,0 +1,71 @@
+// MKDIR Command Implementation
+// Create directories in the writable overlay
+
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::FileSystem;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct MkdirCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+impl Command for MkdirCommand {
+    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        let mut parents = false;
+        let mut paths = Vec::new();
+        for arg in args {
+            match arg.as_str() {
+                "-p" | "--parents" => parents = true,
+                flag if flag.starts_with('-') => {
+                    return CommandResult::Error(format!("mkdir: unknown option: {}", flag))
+                }
+                _ => paths.push(arg),
+            }
+        }
+        if paths.is_empty() {
+            return CommandResult::Error("mkdir: missing operand".to_string());
+        }
+        let mut fs = self.filesystem.borrow_mut();
+
+        let errors: Vec<String> = paths
+            .iter()
+            .filter_map(|path| fs.make_directory(path, parents).err())
+            .map(|error| format!("mkdir: {}", error))
+            .collect();
+        if errors.is_empty() {
+            CommandResult::Success(String::new())
+        } else {
+            CommandResult::Error(errors.join("\n"))
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        "Create directories"
+    }
+
+    fn usage(&self) -> &'static str {
+        "mkdir [-p] <directory...>"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"mkdir - Create directories
+
+Usage:
+  mkdir <directory...>    Create each directory
+  mkdir -p <directory...> Also create missing parent directories, and
+                          don't complain about existing ones
+  mkdir --help            Show this help message
+
+Description:
+  Creates directories in the writable overlay, kept in memory on top of
+  the site's files. They are marked with + in ls, and reset-fs discards
+  them.
+
+Examples:
+  mkdir notes             Create the notes directory
+  mkdir -p notes/2024/05  Create notes, notes/2024 and notes/2024/05"#,
+        )
+    }
+}
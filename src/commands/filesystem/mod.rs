@@ -3,12 +3,49 @@
 
 pub mod cat;
 pub mod cd;
+pub mod cp;
+pub mod edit;
+pub mod file;
+pub mod find;
+pub mod fs;
+pub mod grep;
+pub mod head;
+pub mod headings;
 pub mod ls;
+pub mod mkdir;
+pub mod mv;
 pub mod pwd;
+pub mod reset_fs;
+pub mod rm;
+pub mod search;
+pub mod stat;
+pub mod tail;
+pub mod text_input;
+pub mod touch;
+pub mod tree;
 pub mod view;
+pub mod wc;
 
 pub use cat::CatCommand;
 pub use cd::CdCommand;
+pub use cp::CpCommand;
+pub use edit::{EditCommand, EditorOpener, EditorSession};
+pub use file::FileCommand;
+pub use find::FindCommand;
+pub use fs::FsCommand;
+pub use grep::GrepCommand;
+pub use head::HeadCommand;
+pub use headings::HeadingsCommand;
 pub use ls::LsCommand;
+pub use mkdir::MkdirCommand;
+pub use mv::MvCommand;
 pub use pwd::PwdCommand;
-pub use view::ViewCommand;
\ No newline at end of file
+pub use reset_fs::ResetFsCommand;
+pub use rm::RmCommand;
+pub use search::SearchCommand;
+pub use stat::StatCommand;
+pub use tail::TailCommand;
+pub use touch::TouchCommand;
+pub use tree::TreeCommand;
+pub use view::ViewCommand;
+pub use wc::WcCommand;
\ No newline at end of file
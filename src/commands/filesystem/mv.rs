@@ -0,0 +1,90 @@
+// MV Command Implementation
+// Move and rename files and directories in the working tree
+
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::FileSystem;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct MvCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+impl Command for MvCommand {
+    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        if let Some(flag) = args.iter().find(|arg| arg.starts_with('-')) {
+            return CommandResult::Error(format!("mv: unknown option: {}", flag));
+        }
+        let mut fs = self.filesystem.borrow_mut();
+        let (sources, destination) = match split_destination(&fs, args) {
+            Ok(split) => split,
+            Err(error) => return CommandResult::Error(format!("mv: {}", error)),
+        };
+
+        let errors: Vec<String> = sources
+            .iter()
+            .filter_map(|source| fs.rename(source, destination).err())
+            .map(|error| format!("mv: {}", error))
+            .collect();
+        if errors.is_empty() {
+            CommandResult::Success(String::new())
+        } else {
+            CommandResult::Error(errors.join("\n"))
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        "Move or rename files and directories"
+    }
+
+    fn usage(&self) -> &'static str {
+        "mv <source...> <destination>"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"mv - Move or rename files and directories
+
+Usage:
+  mv <source> <destination>
+                          Rename source, replacing destination if it is a file
+  mv <source...> <directory>
+                          Move each source into directory
+  mv --help               Show this help message
+
+Description:
+  Moved entries become part of the writable overlay and are marked with
+  + in ls. Moving a site file keeps reading the original's content.
+  reset-fs puts everything back where it was.
+
+Examples:
+  mv notes.md ideas.md    Rename notes.md to ideas.md
+  mv a.md b.md archive    Move a.md and b.md into the archive directory"#,
+        )
+    }
+}
+
+/// Split `source... destination` operands, as shared by mv and cp
+///
+/// With several sources the destination must be an existing directory.
+pub fn split_destination<'a>(
+    fs: &FileSystem,
+    operands: &'a [String],
+) -> Result<(&'a [String], &'a String), String> {
+    match operands.split_last() {
+        None => Err("missing file operand".to_string()),
+        Some((destination, [])) => Err(format!(
+            "missing destination file operand after '{}'",
+            destination
+        )),
+        Some((destination, sources)) => {
+            let is_directory = fs
+                .get_node(destination)
+                .is_ok_and(|node| node.node_type == "directory");
+            if sources.len() > 1 && !is_directory {
+                return Err(format!("target '{}' is not a directory", destination));
+            }
+            Ok((sources, destination))
+        }
+    }
+}
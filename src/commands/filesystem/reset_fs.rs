@@ -0,0 +1,48 @@
+// RESET-FS Command Implementation
+// Discard every change made in the writable overlay
+
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::FileSystem;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct ResetFsCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+impl Command for ResetFsCommand {
+    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        if let Some(arg) = args.first() {
+            return CommandResult::Error(format!("reset-fs: unexpected argument: {}", arg));
+        }
+        match self.filesystem.borrow_mut().reset() {
+            Ok(()) => CommandResult::Success("Filesystem reset to the site's files".to_string()),
+            Err(error) => CommandResult::Error(format!("reset-fs: {}", error)),
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        "Discard all created, changed and removed files"
+    }
+
+    fn usage(&self) -> &'static str {
+        "reset-fs"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"reset-fs - Discard all created, changed and removed files
+
+Usage:
+  reset-fs                Restore the filesystem to the site's files
+  reset-fs --help         Show this help message
+
+Description:
+  Throws away the writable overlay: files and directories created with
+  touch, mkdir, cp, mv or > are deleted, changed files get their
+  original content back and removed files reappear. The copy saved in
+  browser storage is cleared as well. If the current
+  directory no longer exists, you are moved to its nearest parent."#,
+        )
+    }
+}
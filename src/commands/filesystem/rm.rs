@@ -0,0 +1,88 @@
+// RM Command Implementation
+// Remove files and directories from the working tree
+
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::FileSystem;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct RmCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+impl Command for RmCommand {
+    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        let mut recursive = false;
+        let mut force = false;
+        let mut paths = Vec::new();
+        for arg in args {
+            let Some(flags) = arg.strip_prefix('-').filter(|flags| !flags.is_empty()) else {
+                paths.push(arg);
+                continue;
+            };
+            for flag in flags.chars() {
+                match flag {
+                    'r' | 'R' => recursive = true,
+                    'f' => force = true,
+                    _ => return CommandResult::Error(format!("rm: invalid option -- '{}'", flag)),
+                }
+            }
+        }
+        if paths.is_empty() {
+            return if force {
+                CommandResult::Success(String::new())
+            } else {
+                CommandResult::Error("rm: missing operand".to_string())
+            };
+        }
+        let mut fs = self.filesystem.borrow_mut();
+
+        let mut errors = Vec::new();
+        for path in paths {
+            if force && fs.get_node(path).is_err() {
+                continue;
+            }
+            if let Err(error) = fs.remove(path, recursive) {
+                errors.push(format!("rm: {}", error));
+            }
+        }
+        if errors.is_empty() {
+            CommandResult::Success(String::new())
+        } else {
+            CommandResult::Error(errors.join("\n"))
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove files and directories"
+    }
+
+    fn usage(&self) -> &'static str {
+        "rm [-rf] <path...>"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"rm - Remove files and directories
+
+Usage:
+  rm <file...>            Remove each file
+  rm -r <path...>         Also remove directories and everything in them
+  rm --help               Show this help message
+
+Options:
+  -r, -R                  Remove directories recursively
+  -f                      Ignore paths that don't exist
+
+Description:
+  Removes entries from your view of the filesystem. The site's own files
+  are only hidden, not deleted: reset-fs brings them back and discards
+  everything you created.
+
+Examples:
+  rm notes.md             Remove notes.md
+  rm -r notes             Remove the notes directory
+  rm -rf scratch          Remove scratch if it exists"#,
+        )
+    }
+}
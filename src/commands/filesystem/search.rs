@@ -0,0 +1,118 @@
+// SEARCH Command Implementation
+// Ranked full-text search over the prebuilt search index
+
+use crate::commands::filesystem::LsCommand;
+use crate::commands::{Command, CommandExecutor, CommandResult, TerminalContext};
+use crate::filesystem::FileSystem;
+use crate::utils::html_sanitizer::escape_html;
+use crate::utils::search::{load_search_index, render_snippet};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Results shown for a query
+const MAX_RESULTS: usize = 10;
+
+pub struct SearchCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+impl Command for SearchCommand {
+    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        let query = args.join(" ");
+        if query.trim().is_empty() {
+            return CommandResult::Error("search: missing query".to_string());
+        }
+        let filesystem = self.filesystem.clone();
+
+        CommandResult::Async(Box::pin(async move {
+            let index = match load_search_index().await {
+                Ok(index) => index,
+                Err(error) => return CommandResult::Error(format!("search: {}", error)),
+            };
+            let hits = index.search(&query, MAX_RESULTS);
+            if hits.is_empty() {
+                return CommandResult::Html(format!(
+                    r#"<div class="file-list-empty"><p>No results for "{}"</p></div>"#,
+                    escape_html(&query)
+                ));
+            }
+
+            let fs = filesystem.borrow();
+            let mut output_lines = vec![r#"<div class="file-list search-results">"#.to_string()];
+            for hit in hits {
+                let doc = hit.doc;
+                let command = format!(
+                    "view {}",
+                    CommandExecutor::quote_arg(&format!("/{}", doc.path))
+                );
+                let modified = fs
+                    .get_file_info(&format!("/{}", doc.path))
+                    .ok()
+                    .and_then(|node| node.modified.as_deref())
+                    .and_then(|modified| modified.split('T').next())
+                    .unwrap_or("-")
+                    .to_string();
+                let tags: String = doc
+                    .tags
+                    .iter()
+                    .map(|tag| format!(r#"<span class="search-tag">#{}</span>"#, escape_html(tag)))
+                    .collect();
+
+                output_lines.push(format!(
+                    r#"<div class="file-item" data-command="{}" title="{}">
+                            <span class="file-icon">{}</span>
+                            <div class="file-info">
+                                <div class="file-title">{}{}</div>
+                                <div class="file-description search-snippet">{}</div>
+                            </div>
+                            <div class="file-modified">{}</div>
+                        </div>"#,
+                    escape_html(&command),
+                    escape_html(&doc.path),
+                    LsCommand::get_markdown_svg(),
+                    escape_html(&doc.title),
+                    tags,
+                    render_snippet(&doc.text, &query),
+                    escape_html(&modified)
+                ));
+            }
+            output_lines.push("</div>".to_string());
+
+            CommandResult::Html(output_lines.join("\n"))
+        }))
+    }
+
+    fn description(&self) -> &'static str {
+        "Search the text of all markdown files"
+    }
+
+    fn usage(&self) -> &'static str {
+        "search <query>"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"search - Search the text of all markdown files
+
+Usage:
+  search <query>          Show the files that best match the query
+  search --help           Show this help message
+
+Description:
+  Looks the query up in a search index of every markdown file's title,
+  description, tags and text, and shows the best matches ranked by
+  relevance (BM25), each with an excerpt around the first match.
+  Matches in titles and tags count more than matches in the text.
+  Chinese, Japanese and Korean text is matched by pairs of characters,
+  so queries don't need spaces between words.
+  Click a result to view the file.
+
+  The index is built by generate_metadata.js; rerun it after editing files.
+
+Examples:
+  search rust             Files about rust
+  search terminal theme   Files mentioning terminal, theme, or best both
+  search 元数据           Files mentioning 元数据"#,
+        )
+    }
+}
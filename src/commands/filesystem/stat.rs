@@ -0,0 +1,136 @@
+// STAT Command Implementation
+// Show every metadata field of files and directories
+
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::types::FileSystemNode;
+use crate::filesystem::FileSystem;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct StatCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+impl Command for StatCommand {
+    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        if args.is_empty() {
+            return CommandResult::Error("stat: missing operand".to_string());
+        }
+        let fs = self.filesystem.borrow();
+
+        let mut sections = Vec::new();
+        let mut errors = Vec::new();
+        for path in args {
+            let lookup = match path.trim_end_matches('/') {
+                "" => "/",
+                trimmed => trimmed,
+            };
+            match fs.get_node(lookup) {
+                Ok(node) => sections.push(format_stat(node)),
+                Err(error) => errors.push(format!("stat: {}", error)),
+            }
+        }
+
+        match (sections.is_empty(), errors.is_empty()) {
+            (true, _) => CommandResult::Error(errors.join("\n")),
+            (false, true) => CommandResult::Success(sections.join("\n\n")),
+            (false, false) => {
+                CommandResult::Success(format!("{}\n{}", sections.join("\n\n"), errors.join("\n")))
+            }
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        "Show file or directory metadata"
+    }
+
+    fn usage(&self) -> &'static str {
+        "stat <path...>"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"stat - Show file or directory metadata
+
+Usage:
+  stat <path...>          Show the metadata of each path
+  stat --help             Show this help message
+
+Description:
+  Prints every field the filesystem knows about a path: its type, size,
+  modification time and extension, and for markdown files the title,
+  description and tags from their frontmatter. Directories show how many
+  entries they contain. Missing fields are shown as -.
+
+Examples:
+  stat index.md           Metadata of index.md
+  stat /posts             Metadata of the posts directory"#,
+        )
+    }
+}
+
+/// Aligned `Field: value` lines for `node`
+fn format_stat(node: &FileSystemNode) -> String {
+    let display = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    let is_directory = node.node_type == "directory";
+
+    let mut fields = vec![
+        ("File", format!("/{}", node.path)),
+        ("Type", node.node_type.clone()),
+    ];
+    if is_directory {
+        fields.push(("Entries", node.children.len().to_string()));
+    } else {
+        fields.push((
+            "Size",
+            display(node.size.map(|size| format!("{} bytes", size))),
+        ));
+    }
+    fields.push(("Modified", display(node.modified.clone())));
+    if !is_directory {
+        fields.extend([
+            ("Extension", display(node.extension.clone())),
+            ("Title", display(node.title.clone())),
+            ("Description", display(node.description.clone())),
+            (
+                "Tags",
+                display(node.tags.as_ref().map(|tags| tags.join(", "))),
+            ),
+        ]);
+    }
+
+    let width = fields.iter().map(|(name, _)| name.len()).max().unwrap_or(0) + 1;
+    fields
+        .iter()
+        .map(|(name, value)| format!("{:<width$} {}", format!("{}:", name), value, width = width))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::node;
+
+    #[test]
+    fn test_format_stat() {
+        let node = FileSystemNode {
+            size: Some(42),
+            modified: Some("2024-05-01T10:00:00Z".to_string()),
+            title: Some("A".to_string()),
+            tags: Some(vec!["rust".to_string(), "web".to_string()]),
+            ..node("posts/a.md", "file", vec![])
+        };
+        assert_eq!(
+            format_stat(&node),
+            "File:        /posts/a.md\n\
+             Type:        file\n\
+             Size:        42 bytes\n\
+             Modified:    2024-05-01T10:00:00Z\n\
+             Extension:   md\n\
+             Title:       A\n\
+             Description: -\n\
+             Tags:        rust, web"
+        );
+    }
+}
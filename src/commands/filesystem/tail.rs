@@ -0,0 +1,84 @@
+// TAIL Command Implementation
+// Print the last lines of files or piped input
+
+use super::text_input::{into_result, join_outputs, parse_line_count, TextSources};
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::FileSystem;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct TailCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+impl Command for TailCommand {
+    fn execute(&self, args: &[String], context: &TerminalContext) -> CommandResult {
+        let (count, paths) = match parse_line_count(args, 10) {
+            Ok(parsed) => parsed,
+            Err(error) => return CommandResult::Error(format!("tail: {}", error)),
+        };
+        let sources =
+            match TextSources::resolve(&self.filesystem.borrow(), &paths, context.stdin.as_ref()) {
+                Ok(sources) => sources,
+                Err(error) => return CommandResult::Error(format!("tail: {}", error)),
+            };
+        let multiple = sources.is_multiple();
+
+        CommandResult::Async(Box::pin(async move {
+            let inputs = sources.read().await;
+            let (output, errors) = join_outputs(inputs, multiple, "tail", |content| {
+                last_lines(content, count)
+            });
+            into_result(output, errors)
+        }))
+    }
+
+    fn description(&self) -> &'static str {
+        "Print the last lines of files"
+    }
+
+    fn usage(&self) -> &'static str {
+        "tail [-n lines] [file...]"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"tail - Print the last lines of files
+
+Usage:
+  tail [file...]          Print the last 10 lines of each file
+  tail -n <lines> [file...]
+                          Print the last <lines> lines
+  command | tail          Print the last lines of another command's output
+  tail --help             Show this help message
+
+Description:
+  With several files, each one is printed under a ==> name <== header.
+  -<lines> (e.g. -5) is short for -n <lines>.
+
+Examples:
+  tail index.md           Last 10 lines of index.md
+  tail -n 3 a.md b.md     Last 3 lines of a.md and of b.md
+  find / -name '*.md' | tail -n 2
+                          Last 2 markdown files found"#,
+        )
+    }
+}
+
+/// The last `count` lines of `content`
+fn last_lines(content: &str, count: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    lines[lines.len().saturating_sub(count)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_lines() {
+        assert_eq!(last_lines("a\nb\nc\n", 2), "b\nc");
+        assert_eq!(last_lines("a\nb", 5), "a\nb");
+        assert_eq!(last_lines("a\nb", 0), "");
+    }
+}
@@ -0,0 +1,161 @@
+// Text Command Input
+// Reads the files named on a command line, or stdin when there are none
+
+use crate::commands::CommandResult;
+use crate::filesystem::types::ContentSource;
+use crate::filesystem::FileSystem;
+use futures::future::join_all;
+
+/// Content read by a text command, labelled with the name to print for it
+pub struct TextInput {
+    pub name: String,
+    pub content: Result<String, String>,
+}
+
+/// Where the input comes from, resolved before any fetching starts
+pub enum TextSources {
+    /// Piped input from the previous pipeline stage
+    Stdin(String),
+    /// `(name as given, content source or lookup error)` for each file argument
+    Files(Vec<(String, Result<ContentSource, String>)>),
+}
+
+impl TextSources {
+    /// Resolve `paths`, falling back to `stdin` when no paths are given
+    ///
+    /// Errors if there is neither, since there is nothing to read.
+    pub fn resolve(
+        fs: &FileSystem,
+        paths: &[String],
+        stdin: Option<&String>,
+    ) -> Result<Self, String> {
+        if paths.is_empty() {
+            return stdin
+                .map(|stdin| TextSources::Stdin(stdin.clone()))
+                .ok_or_else(|| "missing file operand".to_string());
+        }
+        Ok(TextSources::Files(
+            paths
+                .iter()
+                .map(|path| {
+                    let source = fs.get_file_info(path).map(|node| node.content_source());
+                    (path.clone(), source)
+                })
+                .collect(),
+        ))
+    }
+
+    /// Whether output should name each input, as with several files
+    pub fn is_multiple(&self) -> bool {
+        matches!(self, TextSources::Files(files) if files.len() > 1)
+    }
+
+    /// Fetch all files concurrently, keeping the order they were given in
+    pub async fn read(self) -> Vec<TextInput> {
+        match self {
+            TextSources::Stdin(content) => vec![TextInput {
+                name: String::new(),
+                content: Ok(content),
+            }],
+            TextSources::Files(files) => {
+                let contents = join_all(files.iter().map(|(_, source)| async move {
+                    match source {
+                        Ok(source) => source.read_cached().await,
+                        Err(error) => Err(error.clone()),
+                    }
+                }))
+                .await;
+                files
+                    .into_iter()
+                    .zip(contents)
+                    .map(|((name, _), content)| TextInput { name, content })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Parse the line count of `-n N`, `-nN` or `-N`, leaving other arguments as paths
+pub fn parse_line_count(args: &[String], default: usize) -> Result<(usize, Vec<String>), String> {
+    let mut count = default;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let value = if arg == "-n" {
+            args.next()
+                .ok_or("option requires an argument -- 'n'")?
+                .as_str()
+        } else if let Some(value) = arg.strip_prefix("-n") {
+            value
+        } else if let Some(value) = arg.strip_prefix('-').filter(|value| !value.is_empty()) {
+            if !value.chars().all(|c| c.is_ascii_digit()) {
+                return Err(format!("invalid option -- '{}'", value));
+            }
+            value
+        } else {
+            paths.push(arg.clone());
+            continue;
+        };
+        count = value
+            .parse()
+            .map_err(|_| format!("invalid number of lines: '{}'", value))?;
+    }
+
+    Ok((count, paths))
+}
+
+/// Join per-input outputs, under `==> name <==` headers when there are several
+pub fn join_outputs(
+    inputs: Vec<TextInput>,
+    multiple: bool,
+    command: &str,
+    render: impl Fn(&str) -> String,
+) -> (String, Vec<String>) {
+    let mut sections = Vec::new();
+    let mut errors = Vec::new();
+    for input in inputs {
+        match input.content {
+            Ok(content) if multiple => {
+                sections.push(format!("==> {} <==\n{}", input.name, render(&content)))
+            }
+            Ok(content) => sections.push(render(&content)),
+            Err(error) => errors.push(format!("{}: {}", command, error)),
+        }
+    }
+    (sections.join("\n\n"), errors)
+}
+
+/// Output with any per-file errors after it; an error if nothing was read
+pub fn into_result(output: String, errors: Vec<String>) -> CommandResult {
+    match (output.is_empty(), errors.is_empty()) {
+        (true, false) => CommandResult::Error(errors.join("\n")),
+        (false, false) => CommandResult::Success(format!("{}\n{}", output, errors.join("\n"))),
+        _ => CommandResult::Success(output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::args;
+
+    #[test]
+    fn test_parse_line_count() {
+        assert_eq!(
+            parse_line_count(&args("a.md"), 10).unwrap(),
+            (10, args("a.md"))
+        );
+        assert_eq!(
+            parse_line_count(&args("-n 3 a b"), 10).unwrap(),
+            (3, args("a b"))
+        );
+        assert_eq!(parse_line_count(&args("-n5"), 10).unwrap(), (5, vec![]));
+        assert_eq!(
+            parse_line_count(&args("-20 a"), 10).unwrap(),
+            (20, args("a"))
+        );
+        assert!(parse_line_count(&args("-n x"), 10).is_err());
+        assert!(parse_line_count(&args("-q"), 10).is_err());
+    }
+}
@@ -0,0 +1,59 @@
+// TOUCH Command Implementation
+// Create empty files in the writable overlay
+
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::FileSystem;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct TouchCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+impl Command for TouchCommand {
+    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        if args.is_empty() {
+            return CommandResult::Error("touch: missing file operand".to_string());
+        }
+        let mut fs = self.filesystem.borrow_mut();
+
+        let errors: Vec<String> = args
+            .iter()
+            .filter_map(|path| fs.touch(path).err())
+            .map(|error| format!("touch: {}", error))
+            .collect();
+        if errors.is_empty() {
+            CommandResult::Success(String::new())
+        } else {
+            CommandResult::Error(errors.join("\n"))
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        "Create empty files"
+    }
+
+    fn usage(&self) -> &'static str {
+        "touch <file...>"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"touch - Create empty files
+
+Usage:
+  touch <file...>         Create each file if it doesn't exist
+  touch --help            Show this help message
+
+Description:
+  Creates empty files in the writable overlay, a layer of changes kept
+  in memory on top of the site's files. Touching a file you created
+  updates its modified time; the site's own files are left unchanged.
+  Files you create are marked with + in ls, and reset-fs discards them.
+
+Examples:
+  touch notes.md          Create notes.md in the current directory
+  touch /links/todo.txt   Create todo.txt in /links"#,
+        )
+    }
+}
@@ -0,0 +1,331 @@
+// TREE Command Implementation
+// Draw the directory hierarchy with box characters, as a clickable sitemap
+
+use crate::commands::{Command, CommandExecutor, CommandResult, TerminalContext};
+use crate::filesystem::types::FileSystemNode;
+use crate::filesystem::FileSystem;
+use crate::utils::html_sanitizer::escape_html;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct TreeCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+/// What to draw
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct TreeOptions {
+    /// Levels below the starting directory to show
+    max_depth: Option<usize>,
+    directories_only: bool,
+    titles: bool,
+}
+
+/// One drawn line below the starting directory
+#[derive(Debug, Clone)]
+struct TreeEntry<'a> {
+    /// Box-drawing characters leading up to the name
+    prefix: String,
+    node: &'a FileSystemNode,
+}
+
+/// Entries under a directory, with the number of directories and files among them
+#[derive(Debug, Default)]
+struct Tree<'a> {
+    entries: Vec<TreeEntry<'a>>,
+    directories: usize,
+    files: usize,
+}
+
+impl Command for TreeCommand {
+    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        let (path, options) = match parse_args(args) {
+            Ok(parsed) => parsed,
+            Err(error) => return CommandResult::Error(format!("tree: {}", error)),
+        };
+        let path = path.unwrap_or_else(|| ".".to_string());
+        let fs = self.filesystem.borrow();
+
+        let lookup = match path.trim_end_matches('/') {
+            "" => "/",
+            trimmed => trimmed,
+        };
+        let root = match fs.get_node(lookup) {
+            Ok(node) if node.node_type == "directory" => node,
+            Ok(_) => return CommandResult::Error(format!("tree: not a directory: {}", path)),
+            Err(error) => return CommandResult::Error(format!("tree: {}", error)),
+        };
+
+        let tree = build_tree(root, &options);
+        let mut lines = vec![entry_html(root, &path, &options)];
+        lines.extend(tree.entries.iter().map(|entry| {
+            format!(
+                "{}{}",
+                entry.prefix,
+                entry_html(entry.node, &entry.node.name, &options)
+            )
+        }));
+        lines.push(String::new());
+        lines.push(summary(&tree, &options));
+
+        CommandResult::Html(format!(
+            r#"<pre class="tree-output">{}</pre>"#,
+            lines.join("\n")
+        ))
+    }
+
+    fn description(&self) -> &'static str {
+        "Show the directory tree"
+    }
+
+    fn usage(&self) -> &'static str {
+        "tree [path] [-L depth] [-d] [--titles]"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"tree - Show the directory tree
+
+Usage:
+  tree [path]             Draw the tree under path (default: current directory)
+  tree --help             Show this help message
+
+Options:
+  -L <depth>              Only descend depth levels
+  -d                      Only show directories
+  --titles                Show each file's title next to its name
+
+Description:
+  Draws every directory and file below path, ending with the number of
+  directories and files shown. Click a name to open it: directories are
+  navigated to, markdown files are viewed and other files are printed,
+  so the tree works as a sitemap.
+
+Examples:
+  tree                    Tree of the current directory
+  tree / -L 1             Top-level directories and files
+  tree posts --titles     Files under posts with their titles
+  tree -d /               Every directory"#,
+        )
+    }
+}
+
+/// Split arguments into the starting path and drawing options
+fn parse_args(args: &[String]) -> Result<(Option<String>, TreeOptions), String> {
+    let mut path = None;
+    let mut options = TreeOptions::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" => options.directories_only = true,
+            "--titles" => options.titles = true,
+            "-L" => {
+                let depth = args.next().ok_or("missing argument to -L")?;
+                options.max_depth = match depth.parse() {
+                    Ok(depth) if depth > 0 => Some(depth),
+                    _ => {
+                        return Err(format!(
+                            "-L: invalid level, must be greater than 0: {}",
+                            depth
+                        ))
+                    }
+                };
+            }
+            flag if flag.starts_with('-') => return Err(format!("unknown option: {}", flag)),
+            _ if path.is_some() => return Err(format!("unexpected argument: {}", arg)),
+            _ => path = Some(arg.clone()),
+        }
+    }
+
+    Ok((path, options))
+}
+
+/// Walk `root` depth-first in name order, drawing the branches of each entry
+fn build_tree<'a>(root: &'a FileSystemNode, options: &TreeOptions) -> Tree<'a> {
+    let mut tree = Tree::default();
+    add_children(root, "", 1, options, &mut tree);
+    tree
+}
+
+fn add_children<'a>(
+    node: &'a FileSystemNode,
+    indent: &str,
+    depth: usize,
+    options: &TreeOptions,
+    tree: &mut Tree<'a>,
+) {
+    if options.max_depth.is_some_and(|max_depth| depth > max_depth) {
+        return;
+    }
+
+    let mut children: Vec<&FileSystemNode> = node
+        .children
+        .values()
+        .filter(|child| !options.directories_only || is_directory(child))
+        .collect();
+    children.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for (index, child) in children.iter().enumerate() {
+        let last = index + 1 == children.len();
+        let (branch, continuation) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        tree.entries.push(TreeEntry {
+            prefix: format!("{}{}", indent, branch),
+            node: child,
+        });
+
+        if is_directory(child) {
+            tree.directories += 1;
+            add_children(
+                child,
+                &format!("{}{}", indent, continuation),
+                depth + 1,
+                options,
+                tree,
+            );
+        } else {
+            tree.files += 1;
+        }
+    }
+}
+
+fn is_directory(node: &FileSystemNode) -> bool {
+    node.node_type == "directory"
+}
+
+/// Title shown with `--titles`: a file's own, or a directory's index.md's
+fn title(node: &FileSystemNode) -> Option<&str> {
+    let title = if is_directory(node) {
+        node.children.get("index.md")?.title.as_deref()
+    } else {
+        node.title.as_deref()
+    };
+    title.filter(|title| *title != node.name)
+}
+
+/// Clickable name of an entry, opening it the way `ls --link` does
+fn entry_html(node: &FileSystemNode, label: &str, options: &TreeOptions) -> String {
+    let path = CommandExecutor::quote_arg(&format!("/{}", node.path));
+    let (class, command) = if is_directory(node) {
+        ("tree-dir", format!("navigate {}", path))
+    } else if node.name.ends_with(".md") {
+        ("tree-file", format!("view {}", path))
+    } else {
+        ("tree-file", format!("cat {}", path))
+    };
+
+    let mut html = format!(
+        r#"<span class="tree-entry {}" data-command="{}">{}</span>"#,
+        class,
+        escape_html(&command),
+        escape_html(label)
+    );
+    if options.titles {
+        if let Some(title) = title(node) {
+            html.push_str(&format!(
+                r#"<span class="tree-title">  {}</span>"#,
+                escape_html(title)
+            ));
+        }
+    }
+    html
+}
+
+/// Closing line, e.g. `2 directories, 7 files`
+fn summary(tree: &Tree, options: &TreeOptions) -> String {
+    let plural = |count: usize, singular: &str, plural: &str| {
+        format!("{} {}", count, if count == 1 { singular } else { plural })
+    };
+    let directories = plural(tree.directories, "directory", "directories");
+    if options.directories_only {
+        directories
+    } else {
+        format!("{}, {}", directories, plural(tree.files, "file", "files"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::node;
+
+    fn draw(root: &FileSystemNode, options: TreeOptions) -> Vec<String> {
+        let tree = build_tree(root, &options);
+        let mut lines: Vec<String> = tree
+            .entries
+            .iter()
+            .map(|entry| format!("{}{}", entry.prefix, entry.node.name))
+            .collect();
+        lines.push(summary(&tree, &options));
+        lines
+    }
+
+    #[test]
+    fn test_tree_drawing_and_counts() {
+        let root = node(
+            "",
+            "directory",
+            vec![
+                node("index.md", "file", vec![]),
+                node(
+                    "posts",
+                    "directory",
+                    vec![
+                        node("posts/a.md", "file", vec![]),
+                        node(
+                            "posts/old",
+                            "directory",
+                            vec![node("posts/old/b.md", "file", vec![])],
+                        ),
+                    ],
+                ),
+                node("links", "directory", vec![]),
+            ],
+        );
+
+        assert_eq!(
+            draw(&root, TreeOptions::default()),
+            [
+                "├── index.md",
+                "├── links",
+                "└── posts",
+                "    ├── a.md",
+                "    └── old",
+                "        └── b.md",
+                "3 directories, 3 files",
+            ]
+        );
+        assert_eq!(
+            draw(
+                &root,
+                TreeOptions {
+                    max_depth: Some(1),
+                    directories_only: true,
+                    titles: false,
+                }
+            ),
+            ["├── links", "└── posts", "2 directories"]
+        );
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args: Vec<String> = ["/", "-L", "2", "--titles"].map(String::from).to_vec();
+        assert_eq!(
+            parse_args(&args).unwrap(),
+            (
+                Some("/".to_string()),
+                TreeOptions {
+                    max_depth: Some(2),
+                    directories_only: false,
+                    titles: true,
+                }
+            )
+        );
+        assert!(parse_args(&["-L".to_string(), "0".to_string()]).is_err());
+    }
+}
@@ -3,6 +3,8 @@
 
 use crate::commands::{Command, CommandResult, TerminalContext};
 use crate::filesystem::FileSystem;
+use crate::utils::file_utils::markdown_processor::RenderOptions;
+use crate::utils::file_utils::page_meta::PageMeta;
 use crate::utils::fetch_and_render_markdown_with_executor;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -13,11 +15,31 @@ pub struct ViewCommand {
 
 impl Command for ViewCommand {
     fn execute(&self, args: &[String], context: &TerminalContext) -> CommandResult {
-        if args.is_empty() {
-            return CommandResult::Error("view: missing filename".to_string());
+        let mut options = RenderOptions {
+            markdown: context.app_config.config.markdown.clone(),
+            ..RenderOptions::default()
+        };
+        let mut target = None;
+        for arg in args {
+            match arg.as_str() {
+                "--toc" => options.toc = true,
+                flag if flag.starts_with('-') => {
+                    return CommandResult::Error(format!("view: unknown option: {}", flag))
+                }
+                _ => target = Some(arg),
+            }
         }
-        
-        let filename = &args[0];
+
+        let Some(target) = target else {
+            return CommandResult::Error("view: missing filename".to_string());
+        };
+
+        // `file.md#section` scrolls to the heading with that id
+        let (filename, anchor) = match target.split_once('#') {
+            Some((filename, anchor)) => (filename, Some(anchor)),
+            None => (target.as_str(), None),
+        };
+        options.anchor = anchor.filter(|a| !a.is_empty()).map(String::from);
         let fs = self.filesystem.borrow();
         
         match fs.get_file_info(filename) {
@@ -33,11 +55,13 @@ impl Command for ViewCommand {
                     CommandResult::Error(format!("view: not a markdown file: {}", filename))
                 } else {
                     let file_path = node.path.clone();
+                    options.markdown.trust_raw_html &= !node.overlay;
+                    options.meta = Some(PageMeta::from_node(node));
                     
                     // Return async future that will resolve to the rendered markdown with command execution support
                     let executor = context.command_executor.clone();
                     let future = Box::pin(async move {
-                        match fetch_and_render_markdown_with_executor(&file_path, &executor).await {
+                        match fetch_and_render_markdown_with_executor(&file_path, &executor, &options).await {
                             Ok(html_content) => {
                                 CommandResult::Html(html_content)
                             }
@@ -54,12 +78,24 @@ impl Command for ViewCommand {
         }
     }
     
+    fn trusts_html(&self, args: &[String], context: &TerminalContext) -> bool {
+        // Raw HTML in markdown is only kept as written when app.json says so,
+        // and only in the site's own files, not ones written in the overlay
+        context.app_config.config.markdown.trust_raw_html
+            && target_file(args).is_some_and(|file| {
+                self.filesystem
+                    .borrow()
+                    .get_file_info(file)
+                    .is_ok_and(|node| !node.overlay)
+            })
+    }
+
     fn description(&self) -> &'static str {
         "Render markdown files"
     }
     
     fn usage(&self) -> &'static str {
-        "view <file.md>"
+        "view [--toc] <file.md>[#section]"
     }
 
     fn help(&self) -> Option<&'static str> {
@@ -67,15 +103,55 @@ impl Command for ViewCommand {
 
 Usage:
   view <file.md>          Render and display markdown file
+  view <file.md>#<id>     Render and scroll to the heading with that id
+  view --toc <file.md>    Render with a table of contents
   view --help             Show this help message
 
 Description:
   The view command renders markdown files and displays them with formatting.
   Only markdown files (.md, .markdown) are supported.
   The output includes styled HTML with colors, headers, lists, and other markdown elements.
+  Headings get ids (lowercase, dashes for spaces); use `headings <file>` to list them.
+  A file whose frontmatter sets `toc: true` always shows its table of contents.
+  A header shows the file's title, date, reading time, word count and tags;
+  click a tag to find other files with it. `hide_meta: true` in the
+  frontmatter hides the header.
 
 Examples:
   view README.md          Render and display README.md
-  view about/demo.md      Render and display about/demo.md"#)
+  view about/demo.md      Render and display about/demo.md
+  view --toc index.md     Render index.md with a table of contents
+  view index.md#usage     Render index.md and jump to the "Usage" heading"#)
     }
-}
\ No newline at end of file
+}
+
+/// The file named on the command line, without any `#section`
+fn target_file(args: &[String]) -> Option<&str> {
+    let target = args.iter().rfind(|arg| !arg.starts_with('-'))?;
+    let file = target
+        .split_once('#')
+        .map_or(target.as_str(), |(file, _)| file);
+    Some(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::CommandExecutor;
+    use crate::test_fixtures::{args, context, overlay_files};
+
+    #[test]
+    fn test_overlay_files_are_never_trusted() {
+        let executor = CommandExecutor::new();
+        overlay_files(&executor, &[("notes.md", "<b data-command=\"rm -r /\">x</b>")]);
+        let view = ViewCommand {
+            filesystem: executor.filesystem.clone(),
+        };
+        let mut context = context(&executor);
+        context.app_config.config.markdown.trust_raw_html = true;
+
+        assert!(view.trusts_html(&args("--toc index.md#usage"), &context));
+        assert!(!view.trusts_html(&args("notes.md"), &context));
+        assert!(!view.trusts_html(&args("missing.md"), &context));
+    }
+}
@@ -0,0 +1,198 @@
+// WC Command Implementation
+// Count the lines, words and bytes of files or piped input
+
+use super::text_input::{into_result, TextSources};
+use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::filesystem::FileSystem;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub struct WcCommand {
+    pub filesystem: Rc<RefCell<FileSystem>>,
+}
+
+/// Which counts to print; all of them when none is asked for
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct WcOptions {
+    lines: bool,
+    words: bool,
+    bytes: bool,
+}
+
+/// Counts of one input
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Counts {
+    lines: usize,
+    words: usize,
+    bytes: usize,
+}
+
+impl Command for WcCommand {
+    fn execute(&self, args: &[String], context: &TerminalContext) -> CommandResult {
+        let (options, paths) = match parse_args(args) {
+            Ok(parsed) => parsed,
+            Err(error) => return CommandResult::Error(format!("wc: {}", error)),
+        };
+        let sources =
+            match TextSources::resolve(&self.filesystem.borrow(), &paths, context.stdin.as_ref()) {
+                Ok(sources) => sources,
+                Err(error) => return CommandResult::Error(format!("wc: {}", error)),
+            };
+        let multiple = sources.is_multiple();
+
+        CommandResult::Async(Box::pin(async move {
+            let mut rows = Vec::new();
+            let mut errors = Vec::new();
+            for input in sources.read().await {
+                match input.content {
+                    Ok(content) => rows.push((count(&content), input.name)),
+                    Err(error) => errors.push(format!("wc: {}", error)),
+                }
+            }
+            if multiple {
+                let total = rows
+                    .iter()
+                    .fold(Counts::default(), |total, (counts, _)| Counts {
+                        lines: total.lines + counts.lines,
+                        words: total.words + counts.words,
+                        bytes: total.bytes + counts.bytes,
+                    });
+                rows.push((total, "total".to_string()));
+            }
+            into_result(format_rows(&rows, &options), errors)
+        }))
+    }
+
+    fn description(&self) -> &'static str {
+        "Count lines, words and bytes"
+    }
+
+    fn usage(&self) -> &'static str {
+        "wc [-l] [-w] [-c] [file...]"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"wc - Count lines, words and bytes
+
+Usage:
+  wc [options] [file...]  Count each file, with a total for several files
+  command | wc [options]  Count another command's output
+  wc --help               Show this help message
+
+Options:
+  -l                      Count lines
+  -w                      Count words (separated by whitespace)
+  -c                      Count bytes
+  Without options all three are printed, in that order.
+
+Examples:
+  wc index.md             Lines, words and bytes of index.md
+  wc -l a.md b.md         Lines of a.md and b.md, and their total
+  find / -type f | wc -l  Number of files"#,
+        )
+    }
+}
+
+/// Split arguments into the counts to print and the files to count
+fn parse_args(args: &[String]) -> Result<(WcOptions, Vec<String>), String> {
+    let mut options = WcOptions::default();
+    let mut paths = Vec::new();
+
+    for arg in args {
+        let Some(flags) = arg.strip_prefix('-').filter(|flags| !flags.is_empty()) else {
+            paths.push(arg.clone());
+            continue;
+        };
+        for flag in flags.chars() {
+            match flag {
+                'l' => options.lines = true,
+                'w' => options.words = true,
+                'c' => options.bytes = true,
+                _ => return Err(format!("invalid option -- '{}'", flag)),
+            }
+        }
+    }
+
+    if options == WcOptions::default() {
+        options = WcOptions {
+            lines: true,
+            words: true,
+            bytes: true,
+        };
+    }
+    Ok((options, paths))
+}
+
+fn count(content: &str) -> Counts {
+    Counts {
+        lines: content.matches('\n').count(),
+        words: content.split_whitespace().count(),
+        bytes: content.len(),
+    }
+}
+
+/// One right-aligned row per input, followed by its name
+fn format_rows(rows: &[(Counts, String)], options: &WcOptions) -> String {
+    let selected = |counts: &Counts| {
+        [
+            (options.lines, counts.lines),
+            (options.words, counts.words),
+            (options.bytes, counts.bytes),
+        ]
+        .into_iter()
+        .filter(|(shown, _)| *shown)
+        .map(|(_, value)| value)
+        .collect::<Vec<_>>()
+    };
+    let width = rows
+        .iter()
+        .flat_map(|(counts, _)| selected(counts))
+        .map(|value| value.to_string().len())
+        .max()
+        .unwrap_or(1);
+
+    rows.iter()
+        .map(|(counts, name)| {
+            let mut row: Vec<String> = selected(counts)
+                .into_iter()
+                .map(|value| format!("{:>width$}", value, width = width))
+                .collect();
+            if !name.is_empty() {
+                row.push(name.clone());
+            }
+            row.join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_and_rows() {
+        let counts = count("hello world\nsecond line here\n");
+        assert_eq!(
+            counts,
+            Counts {
+                lines: 2,
+                words: 5,
+                bytes: 29
+            }
+        );
+
+        let all = parse_args(&[]).unwrap().0;
+        let rows = vec![
+            (counts, "a.md".to_string()),
+            (Counts::default(), String::new()),
+        ];
+        assert_eq!(format_rows(&rows, &all), " 2  5 29 a.md\n 0  0  0");
+
+        let (lines_only, paths) = parse_args(&["-l".to_string(), "a.md".to_string()]).unwrap();
+        assert_eq!(paths, ["a.md"]);
+        assert_eq!(format_rows(&rows[..1], &lines_only), "2 a.md");
+        assert!(parse_args(&["-x".to_string()]).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 // Command System Module
 // Modular command system with organized command categories
 
+pub mod environment;
 pub mod executor;
 pub mod filesystem;
 pub mod system;
@@ -8,6 +9,7 @@ pub mod types;
 pub mod utility;
 
 // Re-export main types and executor
+pub use environment::Environment;
 pub use executor::CommandExecutor;
 pub use types::{Command, CommandResult, TerminalContext};
 
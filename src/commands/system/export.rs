@@ -0,0 +1,106 @@
+// Export Command Implementation
+// This file contains the export command that sets shell environment variables
+
+use crate::commands::{Command, CommandResult, Environment, TerminalContext};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Built-in export command that sets or lists environment variables
+/// Variables such as PS1 and HOME change how the prompt is rendered
+pub struct ExportCommand {
+    pub environment: Rc<RefCell<Environment>>,
+}
+
+impl Command for ExportCommand {
+    fn execute(&self, args: &[String], _context: &TerminalContext) -> CommandResult {
+        if args.is_empty() {
+            let env = self.environment.borrow();
+            let lines: Vec<String> = env
+                .vars()
+                .map(|(name, value)| format!("export {}=\"{}\"", name, value))
+                .collect();
+            return CommandResult::Success(lines.join("\n"));
+        }
+
+        let mut env = self.environment.borrow_mut();
+
+        if args[0] == "-n" {
+            // Remove variables, e.g. to fall back to the configured prompt
+            for name in &args[1..] {
+                env.unset(name);
+            }
+            return CommandResult::Success(String::new());
+        }
+
+        for arg in args {
+            match arg.split_once('=') {
+                Some((name, value)) if Self::is_valid_name(name) => env.set(name, value),
+                Some((name, _)) => {
+                    return CommandResult::Error(format!(
+                        "export: '{}': not a valid identifier",
+                        name
+                    ))
+                }
+                None => {
+                    return CommandResult::Error(format!("export: '{}': expected NAME=value", arg))
+                }
+            }
+        }
+
+        CommandResult::Success(String::new())
+    }
+
+    fn description(&self) -> &'static str {
+        "Set or list environment variables"
+    }
+
+    fn usage(&self) -> &'static str {
+        "export [-n] [NAME=value...]"
+    }
+
+    fn help(&self) -> Option<&'static str> {
+        Some(
+            r#"export - Set or list environment variables
+
+Usage:
+  export                  List all exported variables
+  export NAME=value       Set NAME to value
+  export -n NAME          Remove NAME
+  export --help           Show this help message
+
+Description:
+  The export command sets variables in the terminal session.
+  Quote values that contain spaces.
+
+  Variables used by the terminal:
+  - PS1: prompt template, overrides terminal.prompt.format in app.json
+  - HOME: directory abbreviated as ~ in the prompt (default: /)
+
+Prompt escapes:
+  {user}                  Author name from app.json
+  {cwd}                   Current directory, with HOME shown as ~
+  {time}                  Current time (HH:MM:SS)
+  {status}                Exit status of the last command, when non-zero
+  {symbol}                Prompt symbol from app.json
+  {red} {green} ...       Switch color (any ANSI color name)
+  {#rrggbb}               Switch to a custom color
+  {bold} {normal}         Switch font weight
+  {reset}                 Restore the default color and weight
+
+Examples:
+  export PS1="{cwd} {symbol}"
+  export PS1="{green}{user}{reset}@zzhack {blue}{cwd}{reset} {status}{symbol}"
+  export HOME=/posts
+  export -n PS1"#,
+        )
+    }
+}
+
+impl ExportCommand {
+    /// Check that a variable name only contains letters, digits and underscores
+    fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+}
@@ -2,12 +2,14 @@
 // System-level and terminal control commands
 
 pub mod clear;
+pub mod export;
 pub mod help;
 pub mod history_push;
 pub mod theme;
 pub mod whoimi;
 
 pub use clear::ClearCommand;
+pub use export::ExportCommand;
 pub use help::HelpCommand;
 pub use history_push::HistoryPushCommand;
 pub use theme::ThemeCommand;
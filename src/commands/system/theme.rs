@@ -1,27 +1,35 @@
 use crate::commands::{Command, CommandResult, TerminalContext};
+use crate::utils::syntax_highlighter::SyntaxHighlighter;
+use crate::utils::theme::{Theme, ANSI_COLOR_NAMES};
 
 pub struct ThemeCommand;
 
 impl Command for ThemeCommand {
     fn execute(&self, args: &[String], context: &TerminalContext) -> CommandResult {
-        if args.is_empty() {
-            // Display current theme
-            let current_theme = context.app_config.get_current_theme();
-            let config_theme = &context.app_config.config.theme;
-            
-            if config_theme == "system" {
-                CommandResult::Success(format!(
-                    "Current theme: {} (resolved from system preference)\nDefault theme: {}",
-                    current_theme, config_theme
-                ))
-            } else {
-                CommandResult::Success(format!("Current theme: {}", current_theme))
+        match args.first().map(|s| s.as_str()) {
+            None => {
+                // Display current theme
+                let current_theme = context.app_config.get_current_theme();
+                let config_theme = &context.app_config.config.theme;
+
+                if context.app_config.is_following_system() {
+                    CommandResult::Success(format!(
+                        "Current theme: {} (resolved from system preference)\nDefault theme: {}",
+                        current_theme, config_theme
+                    ))
+                } else {
+                    CommandResult::Success(format!("Current theme: {}", current_theme))
+                }
             }
-        } else if args.len() == 2 && args[0] == "switch" {
-            // Switch theme
-            let new_theme = &args[1];
-            
-            if matches!(new_theme.as_str(), "light" | "dark") {
+            Some("list") => Self::list_themes(context),
+            Some("switch") if args.len() == 2 => {
+                // Switch theme
+                let new_theme = &args[1];
+
+                if new_theme != "system" && context.app_config.get_theme(new_theme).is_none() {
+                    return CommandResult::Error(Self::unknown_theme_message(new_theme, context));
+                }
+
                 if let Some(set_theme) = &context.set_theme {
                     if set_theme(new_theme) {
                         CommandResult::Success(format!("Theme switched to: {}", new_theme))
@@ -29,38 +37,149 @@ impl Command for ThemeCommand {
                         CommandResult::Error("Failed to switch theme".to_string())
                     }
                 } else {
-                    CommandResult::Error("Theme switching not available in this context".to_string())
+                    CommandResult::Error(
+                        "Theme switching not available in this context".to_string(),
+                    )
                 }
-            } else {
-                CommandResult::Error(format!(
-                    "Invalid theme: '{}'. Available themes: light, dark", 
-                    new_theme
-                ))
             }
-        } else {
-            CommandResult::Error("Invalid usage. Use 'theme' to show current theme or 'theme switch <light|dark>' to switch theme.".to_string())
+            Some("code") => Self::code_theme(args.get(1), context),
+            Some("preview") if args.len() == 2 => match context.app_config.get_theme(&args[1]) {
+                Some(theme) => CommandResult::Html(Self::render_preview(&args[1], &theme)),
+                None => CommandResult::Error(Self::unknown_theme_message(&args[1], context)),
+            },
+            _ => CommandResult::Error(
+                "Invalid usage. Use 'theme list' to see available themes or 'theme switch <name>' to switch theme."
+                    .to_string(),
+            ),
         }
     }
 
     fn description(&self) -> &'static str {
-        "Display or switch the current theme"
+        "Display, list, preview or switch themes"
     }
 
     fn usage(&self) -> &'static str {
-        "theme [switch <light|dark>]"
+        "theme [list|switch|preview|code]"
     }
 
     fn help(&self) -> Option<&'static str> {
         Some(
             "Theme command usage:
-  theme              - Display current theme
-  theme switch light - Switch to light theme
-  theme switch dark  - Switch to dark theme
+  theme                  - Display current theme
+  theme list             - List available themes
+  theme switch <name>    - Switch to the named theme
+  theme switch system    - Follow the system color scheme
+  theme preview <name>   - Show a theme's palette without switching
+  theme code             - Show the code highlighting theme and the choices
+  theme code <name>      - Highlight code blocks with the named theme
+  theme code auto        - Use the terminal theme's own code theme
+
+Themes are loaded from data/themes/*.json and the \"themes\" section of
+app.json. Each theme defines background, foreground, prompt, error and
+info colors, the 16 ANSI colors and a syntax highlighting theme.
+Custom code themes are loaded from data/themes/*.tmTheme.
 
 The default theme is configured in app.json and can be:
-- light: Always use light theme
-- dark: Always use dark theme  
-- system: Use system preference (prefers-color-scheme)"
+- any theme name: Always use that theme
+- system: Use system preference (prefers-color-scheme)",
+        )
+    }
+}
+
+impl ThemeCommand {
+    fn list_themes(context: &TerminalContext) -> CommandResult {
+        let current_theme = context.app_config.get_current_theme();
+        let lines: Vec<String> = context
+            .app_config
+            .available_themes()
+            .iter()
+            .map(|(name, theme)| {
+                let marker = if *name == current_theme { "*" } else { " " };
+                let appearance = if theme.dark { "dark" } else { "light" };
+                format!(
+                    "{} {:<20}{:<7}{}",
+                    marker,
+                    name,
+                    appearance,
+                    theme.description.as_deref().unwrap_or("")
+                )
+            })
+            .collect();
+
+        CommandResult::Success(lines.join("\n"))
+    }
+
+    fn code_theme(name: Option<&String>, context: &TerminalContext) -> CommandResult {
+        let Some(name) = name else {
+            let current = context.app_config.get_code_theme();
+            let lines: Vec<String> = SyntaxHighlighter::shared()
+                .theme_names()
+                .into_iter()
+                .map(|theme| {
+                    let marker = if theme == current { "*" } else { " " };
+                    format!("{} {}", marker, theme)
+                })
+                .collect();
+            return CommandResult::Success(format!(
+                "Current code theme: {}\n\n{}",
+                current,
+                lines.join("\n")
+            ));
+        };
+
+        match &context.set_code_theme {
+            Some(set_code_theme) if set_code_theme(name) => {
+                CommandResult::Success(format!("Code theme switched to: {}", name))
+            }
+            Some(_) => CommandResult::Error(format!(
+                "Invalid code theme: '{}'. Use 'theme code' to list code themes.",
+                name
+            )),
+            None => CommandResult::Error(
+                "Theme switching not available in this context".to_string(),
+            ),
+        }
+    }
+
+    fn unknown_theme_message(name: &str, context: &TerminalContext) -> String {
+        let names: Vec<String> = context.app_config.available_themes().into_keys().collect();
+        format!(
+            "Invalid theme: '{}'. Available themes: {}, system",
+            name,
+            names.join(", ")
+        )
+    }
+
+    /// Render a palette swatch using the theme's own colors
+    fn render_preview(name: &str, theme: &Theme) -> String {
+        let swatches: String = ANSI_COLOR_NAMES
+            .iter()
+            .enumerate()
+            .map(|(index, color_name)| {
+                format!(
+                    r#"<span title="{}" style="display: inline-block; width: 1.5rem; height: 1.5rem; margin-right: 0.25rem; border-radius: 0.25rem; background-color: {};"></span>"#,
+                    color_name,
+                    theme.ansi_color(index)
+                )
+            })
+            .collect();
+
+        format!(
+            r#"<div style="background-color: {bg}; color: {fg}; border: 1px solid {border}; padding: 1rem; border-radius: 0.5rem;">
+                <div><span style="color: {prompt}; font-weight: bold;">$</span> theme preview {name}</div>
+                <div style="color: {info};">info: syntax theme {syntax}</div>
+                <div style="color: {error};">Error: sample error output</div>
+                <div style="margin-top: 0.5rem;">{swatches}</div>
+            </div>"#,
+            bg = theme.background,
+            fg = theme.foreground,
+            border = theme.border.as_deref().unwrap_or(theme.ansi_color(8)),
+            prompt = theme.prompt,
+            name = name,
+            info = theme.info,
+            syntax = theme.syntax_theme,
+            error = theme.error,
+            swatches = swatches,
         )
     }
-}
\ No newline at end of file
+}
@@ -1,17 +1,24 @@
 // Command System Types
 // Core types and traits for the command system
 
+use crate::utils::html_sanitizer::sanitize_html;
 use crate::utils::AppConfigService;
 use std::future::Future;
 use std::pin::Pin;
 
+/// Applies a theme by name, returning whether the name was known
+pub type ThemeSetter = std::rc::Rc<dyn Fn(&str) -> bool>;
+
 /// Terminal context providing utility functions for commands
 pub struct TerminalContext<'a> {
     pub clear_screen: std::rc::Rc<dyn Fn()>,
     pub command_executor: &'a crate::commands::CommandExecutor,
     pub execute: std::rc::Rc<dyn Fn(&str) -> CommandResult>,
     pub app_config: AppConfigService,
-    pub set_theme: Option<std::rc::Rc<dyn Fn(&str) -> bool>>,
+    pub set_theme: Option<ThemeSetter>,
+    pub set_code_theme: Option<ThemeSetter>,
+    /// Output of the previous command in a pipeline, read by filters like grep
+    pub stdin: Option<String>,
 }
 
 /// Result of executing a terminal command
@@ -27,6 +34,29 @@ pub enum CommandResult {
     Async(Pin<Box<dyn Future<Output = CommandResult>>>),
 }
 
+impl CommandResult {
+    /// Shell-style exit status of a finished result (0 = success, 1 = error)
+    /// Returns None for pending async results
+    pub fn exit_status(&self) -> Option<i32> {
+        match self {
+            CommandResult::Success(_) | CommandResult::Html(_) => Some(0),
+            CommandResult::Error(_) => Some(1),
+            CommandResult::Async(_) => None,
+        }
+    }
+
+    /// Pass HTML output, including that of async results, through the sanitizer
+    pub fn sanitized(self) -> CommandResult {
+        match self {
+            CommandResult::Html(html) => CommandResult::Html(sanitize_html(&html)),
+            CommandResult::Async(future) => {
+                CommandResult::Async(Box::pin(async move { future.await.sanitized() }))
+            }
+            result => result,
+        }
+    }
+}
+
 /// Trait that all terminal commands must implement
 /// This provides a consistent interface for command execution and documentation
 pub trait Command {
@@ -45,4 +75,10 @@ pub trait Command {
     fn help(&self) -> Option<&'static str> {
         None
     }
+
+    /// Whether HTML output for these arguments is inserted into the page as-is
+    /// By default it goes through the allow-list sanitizer
+    fn trusts_html(&self, _args: &[String], _context: &TerminalContext) -> bool {
+        false
+    }
 }
\ No newline at end of file
@@ -46,11 +46,11 @@ impl Command for EvalCommand {
                     if !is_js_file {
                         CommandResult::Error(format!("eval: not a JavaScript file: {}", filename))
                     } else {
-                        let file_path = node.path.clone();
+                        let source = node.content_source();
 
                         // Return async future to fetch and execute the JavaScript file
                         let future = Box::pin(async move {
-                            match crate::utils::fetch_file_content(&file_path).await {
+                            match source.read().await {
                                 Ok(js_content) => EvalCommand::execute_javascript(&js_content),
                                 Err(error) => CommandResult::Error(format!(
                                     "eval: Error reading file: {}",
@@ -10,6 +10,8 @@ pub struct AnsiSegment {
     pub color: Option<String>,
     pub bold: bool,
     pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
 }
 
 /// Parse ANSI escape sequences and convert them to HTML spans
@@ -20,6 +22,8 @@ pub fn parse_ansi_text(text: &str) -> Vec<AnsiSegment> {
         color: None,
         bold: false,
         italic: false,
+        underline: false,
+        strikethrough: false,
     };
 
     let mut chars = text.chars().peekable();
@@ -59,6 +63,17 @@ pub fn parse_ansi_text(text: &str) -> Vec<AnsiSegment> {
     segments
 }
 
+/// Plain text of a string with its ANSI escape sequences removed
+pub fn strip_ansi(text: &str) -> String {
+    if !text.contains('\x1b') {
+        return text.to_string();
+    }
+    parse_ansi_text(text)
+        .into_iter()
+        .map(|segment| segment.text)
+        .collect()
+}
+
 /// Apply ANSI color codes to a text segment
 pub fn apply_ansi_code(code: &str, segment: &mut AnsiSegment) {
     let codes: Vec<u32> = code.split(';').filter_map(|s| s.parse().ok()).collect();
@@ -70,11 +85,17 @@ pub fn apply_ansi_code(code: &str, segment: &mut AnsiSegment) {
                 segment.color = None;
                 segment.bold = false;
                 segment.italic = false;
+                segment.underline = false;
+                segment.strikethrough = false;
             }
             1 => segment.bold = true,
             3 => segment.italic = true,
+            4 => segment.underline = true,
+            9 => segment.strikethrough = true,
             22 => segment.bold = false,
             23 => segment.italic = false,
+            24 => segment.underline = false,
+            29 => segment.strikethrough = false,
             30 => segment.color = Some("ansi-black".to_string()),        // Black
             31 => segment.color = Some("ansi-red".to_string()),          // Red
             32 => segment.color = Some("ansi-green".to_string()),        // Green
@@ -91,6 +112,7 @@ pub fn apply_ansi_code(code: &str, segment: &mut AnsiSegment) {
             95 => segment.color = Some("ansi-bright-magenta".to_string()), // Bright Magenta
             96 => segment.color = Some("ansi-bright-cyan".to_string()),  // Bright Cyan
             97 => segment.color = Some("ansi-bright-white".to_string()), // Bright White
+            39 => segment.color = None,                                  // Default color
             _ => {}                                            // Ignore unknown codes
         }
     }
@@ -101,7 +123,7 @@ pub fn render_ansi_segments(segments: &[AnsiSegment]) -> Html {
     html! {
         <>
             {for segments.iter().map(|segment| {
-                let mut classes = vec!["text-sm", "font-mono"];
+                let mut classes = vec!["text-sm", "font-mono", "whitespace-pre-wrap"];
 
                 // Apply color class
                 if let Some(color_class) = &segment.color {
@@ -121,6 +143,14 @@ pub fn render_ansi_segments(segments: &[AnsiSegment]) -> Html {
                     classes.push("italic");
                 }
 
+                if segment.underline {
+                    classes.push("underline");
+                }
+
+                if segment.strikethrough {
+                    classes.push("line-through");
+                }
+
                 html! {
                     <span class={classes.join(" ")}>{&segment.text}</span>
                 }
@@ -4,6 +4,9 @@
 use crate::components::ansi::{parse_ansi_text, render_ansi_segments};
 use crate::components::syntax::render_command_with_syntax;
 use crate::components::terminal::input::TerminalPrompt;
+use crate::utils::file_utils::run_block::fill_resolved_outputs;
+use crate::utils::file_utils::toc::scroll_to_heading;
+use crate::utils::prompt::PromptSegment;
 use yew::prelude::*;
 
 /// Represents a single entry in the terminal history
@@ -20,6 +23,8 @@ pub struct HistoryEntry {
     pub is_error: bool,
     /// Whether the output contains HTML that should be rendered
     pub is_html: bool,
+    /// The prompt as it was rendered when the command was run
+    pub prompt: Vec<PromptSegment>,
 }
 
 /// Properties for the HistoryItem component
@@ -45,6 +50,13 @@ pub fn history_item(props: &HistoryItemProps) -> Html {
             if is_html {
                 if let Some(element) = html_ref.cast::<web_sys::HtmlElement>() {
                     element.set_inner_html(&output);
+                    fill_resolved_outputs(&element);
+                    // `view file.md#section` jumps to the requested heading
+                    if let Ok(Some(content)) = element.query_selector("[data-scroll-target]") {
+                        if let Some(anchor) = content.get_attribute("data-scroll-target") {
+                            scroll_to_heading(&content, &anchor);
+                        }
+                    }
                 }
             }
             || {}
@@ -62,7 +74,9 @@ pub fn history_item(props: &HistoryItemProps) -> Html {
     } else if segments.len() > 1
         || segments
             .get(0)
-            .map_or(false, |s| s.color.is_some() || s.bold || s.italic)
+            .map_or(false, |s| {
+                s.color.is_some() || s.bold || s.italic || s.underline || s.strikethrough
+            })
     {
         // Has ANSI styling - render with parsed segments
         render_ansi_segments(&segments)
@@ -91,7 +105,7 @@ pub fn history_item(props: &HistoryItemProps) -> Html {
             {if !props.entry.command.is_empty() {
                 html! {
                     <div class="mb-1 flex items-start">
-                        <TerminalPrompt />
+                        <TerminalPrompt segments={props.entry.prompt.clone()} />
                         <div class="flex-1 text-sm font-mono">
                             {render_command_with_syntax(&props.entry.command_text, &props.valid_commands)}
                         </div>
@@ -116,27 +130,39 @@ pub fn create_welcome_entry() -> HistoryEntry {
         output: String::new(),
         is_error: false,
         is_html: false,
+        prompt: Vec::new(),
     }
 }
 
 /// Create a new command history entry
-pub fn create_command_entry(command_text: String, output: String, is_error: bool) -> HistoryEntry {
+pub fn create_command_entry(
+    command_text: String,
+    output: String,
+    is_error: bool,
+    prompt: Vec<PromptSegment>,
+) -> HistoryEntry {
     HistoryEntry {
         command: "command".to_string(), // This will be used to check if it's not empty
         command_text,
         output,
         is_error,
         is_html: false,
+        prompt,
     }
 }
 
 /// Create a new command history entry with HTML content
-pub fn create_html_entry(command_text: String, html_output: String) -> HistoryEntry {
+pub fn create_html_entry(
+    command_text: String,
+    html_output: String,
+    prompt: Vec<PromptSegment>,
+) -> HistoryEntry {
     HistoryEntry {
         command: "command".to_string(),
         command_text,
         output: html_output,
         is_error: false,
         is_html: true,
+        prompt,
     }
 }
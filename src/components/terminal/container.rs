@@ -2,13 +2,19 @@
 // Main container that manages all terminal state and coordinates child components
 
 use super::content::TerminalContent;
+use super::editor::TerminalEditor;
 use super::handlers::{
-    create_click_handler, create_focus_handler, create_input_handler, create_keydown_handler,
-    create_keyup_handler, create_terminal_click_handler,
+    create_click_handler, create_code_action_handler, create_focus_handler, create_input_handler,
+    create_keydown_handler, create_keyup_handler, create_terminal_click_handler,
 };
+use crate::commands::filesystem::EditorSession;
 use crate::commands::CommandExecutor;
 use crate::components::history::create_welcome_entry;
-use crate::hooks::{use_app_config, use_auto_focus, use_auto_navigation, use_trailing_effect};
+use crate::filesystem::persistence::{LocalStorage, OVERLAY_STORAGE_KEY};
+use crate::hooks::{
+    use_app_config, use_auto_focus, use_auto_navigation, use_command_queue, use_trailing_effect,
+};
+use std::rc::Rc;
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
@@ -27,16 +33,59 @@ pub fn terminal(_props: &TerminalProps) -> Html {
     let history_index = use_state(|| None::<usize>);
     let input_ref = use_node_ref();
     let container_ref = use_node_ref();
-    let executor = use_state(|| CommandExecutor::new());
+    let editor = use_state(|| None::<EditorSession>);
+    let executor = use_state(|| {
+        let executor = CommandExecutor::new();
+        let storage = Rc::new(LocalStorage::new(OVERLAY_STORAGE_KEY));
+        if let Err(error) = executor.filesystem.borrow_mut().attach_storage(storage) {
+            gloo_console::warn!(format!("Saved files could not be restored: {}", error));
+        }
+        let editor = editor.clone();
+        executor.set_editor_opener(Rc::new(move |session| editor.set(Some(session))));
+        executor
+    });
     let app_config = use_app_config();
 
     // Custom hooks
     use_auto_focus(input_ref.clone());
+
+    // Give the prompt the keyboard back when the editor closes, scrolled
+    // to the bottom as it was
+    {
+        let input_ref = input_ref.clone();
+        let container_ref = container_ref.clone();
+        use_effect_with(editor.is_some(), move |editing| {
+            if !*editing {
+                if let Some(container) = container_ref.cast::<web_sys::HtmlElement>() {
+                    container.set_scroll_top(container.scroll_height());
+                }
+                if let Some(input) = input_ref.cast::<web_sys::HtmlInputElement>() {
+                    let _ = input.focus();
+                }
+            }
+            || {}
+        });
+    }
     use_auto_navigation(history.clone(), executor.clone());
     let (trailing_class, trailing_timeout, set_trailing) = use_trailing_effect();
+    let run_commands = use_command_queue(
+        history.clone(),
+        command_history.clone(),
+        history_index.clone(),
+        executor.clone(),
+        container_ref.clone(),
+        app_config.clone(),
+    );
 
     // Event handlers
-    let on_terminal_click = create_terminal_click_handler(input_ref.clone());
+    let on_terminal_click = {
+        let focus_input = create_terminal_click_handler(input_ref.clone());
+        let code_action = create_code_action_handler(run_commands, (*executor).clone());
+        Callback::from(move |e: MouseEvent| {
+            code_action.emit(e.clone());
+            focus_input.emit(e);
+        })
+    };
 
     let on_input = create_input_handler(
         input_value.clone(),
@@ -65,8 +114,18 @@ pub fn terminal(_props: &TerminalProps) -> Html {
         app_config.clone(),
     );
 
+    // The terminal stays mounted under the editor, so blocks still running
+    // keep their place in the history
+    let editing = editor.is_some();
     html! {
         <div class="w-full h-full bg-terminal-bg text-terminal-text font-mono flex flex-col">
+            if let Some(session) = (*editor).clone() {
+                <TerminalEditor
+                    session={session}
+                    executor={(*executor).clone()}
+                    on_exit={Callback::from(move |_| editor.set(None))}
+                />
+            }
             <TerminalContent
                 container_ref={container_ref}
                 history={(*history).clone()}
@@ -81,6 +140,7 @@ pub fn terminal(_props: &TerminalProps) -> Html {
                 on_keyup={on_keyup}
                 on_focus={on_focus}
                 on_click={on_click}
+                hidden={editing}
             />
         </div>
     }
@@ -22,6 +22,9 @@ pub struct TerminalContentProps {
     pub on_keyup: Callback<KeyboardEvent>,
     pub on_focus: Callback<FocusEvent>,
     pub on_click: Callback<MouseEvent>,
+    /// Kept in the page but not shown, e.g. while the editor is open
+    #[prop_or_default]
+    pub hidden: bool,
 }
 
 #[function_component(TerminalContent)]
@@ -29,7 +32,14 @@ pub fn terminal_content(props: &TerminalContentProps) -> Html {
     html! {
         <div 
             ref={props.container_ref.clone()} 
-            class="flex-1 p-4 overflow-y-auto terminal-scrollbar cursor-text" 
+            class={classes!(
+                "flex-1",
+                "p-4",
+                "overflow-y-auto",
+                "terminal-scrollbar",
+                "cursor-text",
+                props.hidden.then_some("hidden")
+            )}
             onclick={props.on_terminal_click.clone()}
         >
             <TerminalHistory 
@@ -0,0 +1,409 @@
+// Terminal Editor Component
+// Full-screen text editor shown in place of the terminal by `edit`
+
+use crate::commands::filesystem::EditorSession;
+use crate::commands::CommandExecutor;
+use crate::utils::html_sanitizer::escape_html;
+use crate::utils::syntax_highlighter::SyntaxHighlighter;
+use crate::utils::text_buffer::TextBuffer;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+use yew::prelude::*;
+
+/// Lines PageUp and PageDown move when the screen can't be measured
+const PAGE_LINES: usize = 20;
+
+#[derive(Properties, PartialEq)]
+pub struct TerminalEditorProps {
+    pub session: EditorSession,
+    pub executor: CommandExecutor,
+    /// Called when the editor is closed
+    pub on_exit: Callback<()>,
+}
+
+/// What a key asks of the component beyond changing the editor state
+#[derive(Debug, PartialEq)]
+enum KeyOutcome {
+    Handled,
+    Ignored,
+    Save,
+    Exit,
+}
+
+/// Everything the editor changes as keys are pressed
+///
+/// Kept in one cell rather than in separate states, so keys pressed faster
+/// than the component renders all apply to the latest text.
+struct EditorState {
+    buffer: TextBuffer,
+    /// Search text being typed, after Ctrl+F
+    search: Option<String>,
+    last_search: String,
+    /// Line, column and length of the match just found
+    found: Option<(usize, usize, usize)>,
+    message: String,
+    /// Ctrl+X was pressed once with unsaved changes
+    confirm_exit: bool,
+    /// Still the site's own file, not yet saved over in the overlay
+    shipped: bool,
+    new_file: bool,
+}
+
+impl EditorState {
+    fn new(session: &EditorSession) -> Self {
+        let message = if session.shipped {
+            "This is one of the site's files: saving stores an edited copy"
+        } else {
+            ""
+        };
+        Self {
+            buffer: TextBuffer::new(&session.content),
+            search: None,
+            last_search: String::new(),
+            found: None,
+            message: message.to_string(),
+            confirm_exit: false,
+            shipped: session.shipped,
+            new_file: session.new_file,
+        }
+    }
+
+    fn handle_key(&mut self, key: &str, ctrl: bool, page: usize) -> KeyOutcome {
+        if matches!(key, "Shift" | "Control" | "Alt" | "Meta" | "CapsLock") {
+            return KeyOutcome::Ignored;
+        }
+        if self.search.is_some() {
+            return self.handle_search_key(key, ctrl);
+        }
+        let confirm_exit = std::mem::take(&mut self.confirm_exit);
+        self.found = None;
+        self.message.clear();
+
+        match (ctrl, key) {
+            (true, "s" | "S") => return KeyOutcome::Save,
+            (true, "x" | "X") if confirm_exit || !self.buffer.is_modified() => {
+                return KeyOutcome::Exit
+            }
+            (true, "x" | "X") => {
+                self.confirm_exit = true;
+                self.message =
+                    "Unsaved changes: Ctrl+S saves them, Ctrl+X again discards them".to_string();
+            }
+            (true, "f" | "F") => self.search = Some(String::new()),
+            (true, _) => return KeyOutcome::Ignored,
+            (false, "ArrowLeft") => self.buffer.move_left(),
+            (false, "ArrowRight") => self.buffer.move_right(),
+            (false, "ArrowUp") => self.buffer.move_lines(-1),
+            (false, "ArrowDown") => self.buffer.move_lines(1),
+            (false, "PageUp") => self.buffer.move_lines(-(page as isize)),
+            (false, "PageDown") => self.buffer.move_lines(page as isize),
+            (false, "Home") => self.buffer.move_home(),
+            (false, "End") => self.buffer.move_end(),
+            (false, "Enter") => self.buffer.insert("\n"),
+            (false, "Tab") => self.buffer.insert("    "),
+            (false, "Backspace") => self.buffer.backspace(),
+            (false, "Delete") => self.buffer.delete(),
+            (false, key) if key.chars().count() == 1 => self.buffer.insert(key),
+            _ => return KeyOutcome::Ignored,
+        }
+        KeyOutcome::Handled
+    }
+
+    fn handle_search_key(&mut self, key: &str, ctrl: bool) -> KeyOutcome {
+        let Some(query) = self.search.as_mut() else {
+            return KeyOutcome::Ignored;
+        };
+        match key {
+            "Enter" => {
+                let query = self.search.take().unwrap_or_default();
+                // An empty search repeats the last one
+                let query = if query.is_empty() {
+                    self.last_search.clone()
+                } else {
+                    query
+                };
+                if query.is_empty() {
+                    self.message = "Nothing to search for".to_string();
+                } else if self.buffer.find_next(&query) {
+                    let (row, column) = self.buffer.cursor();
+                    self.found = Some((row, column, query.chars().count()));
+                } else {
+                    self.message = format!("Not found: {}", query);
+                }
+                self.last_search = query;
+            }
+            "Escape" => {
+                self.search = None;
+                self.message = "Search cancelled".to_string();
+            }
+            "Backspace" => {
+                query.pop();
+            }
+            key if !ctrl && key.chars().count() == 1 => query.push_str(key),
+            _ => return KeyOutcome::Ignored,
+        }
+        KeyOutcome::Handled
+    }
+
+    /// Insert pasted text, or its first line into a search
+    fn paste(&mut self, text: &str) {
+        match self.search.as_mut() {
+            Some(query) => query.push_str(text.lines().next().unwrap_or_default()),
+            None => {
+                self.found = None;
+                self.buffer.insert(text);
+            }
+        }
+    }
+
+    /// Write the buffer to `path` in the overlay, reporting how it went
+    fn save(&mut self, executor: &CommandExecutor, path: &str) {
+        let result = executor
+            .filesystem
+            .borrow_mut()
+            .write_file(path, self.buffer.text());
+        self.message = match result {
+            Ok(()) => {
+                self.buffer.mark_saved();
+                self.new_file = false;
+                if std::mem::take(&mut self.shipped) {
+                    format!(
+                        "Saved an edited copy of {}; reset-fs restores the original",
+                        path
+                    )
+                } else {
+                    format!("Saved {}", path)
+                }
+            }
+            Err(error) => format!("Error: {}", error),
+        };
+    }
+}
+
+#[function_component(TerminalEditor)]
+pub fn terminal_editor(props: &TerminalEditorProps) -> Html {
+    let state = use_mut_ref(|| EditorState::new(&props.session));
+    let force_update = use_force_update();
+    let editor_ref = use_node_ref();
+    let body_ref = use_node_ref();
+    let cursor_line_ref = use_node_ref();
+
+    // Take the keyboard as soon as the editor opens
+    {
+        let editor_ref = editor_ref.clone();
+        use_effect_with((), move |_| {
+            if let Some(editor) = editor_ref.cast::<HtmlElement>() {
+                let _ = editor.focus();
+            }
+            || {}
+        });
+    }
+
+    let on_keydown = {
+        let state = state.clone();
+        let force_update = force_update.clone();
+        let body_ref = body_ref.clone();
+        let executor = props.executor.clone();
+        let path = props.session.path.clone();
+        let on_exit = props.on_exit.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            let ctrl = e.ctrl_key() || e.meta_key();
+            if e.alt_key() {
+                return;
+            }
+            let outcome = state
+                .borrow_mut()
+                .handle_key(&e.key(), ctrl, page_lines(&body_ref));
+            match outcome {
+                KeyOutcome::Ignored => return,
+                KeyOutcome::Handled => {}
+                KeyOutcome::Save => state.borrow_mut().save(&executor, &path),
+                KeyOutcome::Exit => {
+                    e.prevent_default();
+                    on_exit.emit(());
+                    return;
+                }
+            }
+            e.prevent_default();
+            force_update.force_update();
+        })
+    };
+
+    let on_paste = {
+        let state = state.clone();
+        let force_update = force_update.clone();
+        Callback::from(move |e: Event| {
+            let text = e
+                .dyn_ref::<web_sys::ClipboardEvent>()
+                .and_then(|e| e.clipboard_data())
+                .and_then(|data| data.get_data("text/plain").ok());
+            if let Some(text) = text {
+                e.prevent_default();
+                state.borrow_mut().paste(&text);
+                force_update.force_update();
+            }
+        })
+    };
+
+    let on_click = {
+        let editor_ref = editor_ref.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(editor) = editor_ref.cast::<HtmlElement>() {
+                let _ = editor.focus();
+            }
+        })
+    };
+
+    let state = state.borrow();
+    let (row, column) = state.buffer.cursor();
+
+    // Keep the cursor's line on screen
+    {
+        let body_ref = body_ref.clone();
+        let cursor_line_ref = cursor_line_ref.clone();
+        use_effect_with((row, column), move |_| {
+            if let (Some(body), Some(line)) = (
+                body_ref.cast::<HtmlElement>(),
+                cursor_line_ref.cast::<HtmlElement>(),
+            ) {
+                let top = line.offset_top();
+                let bottom = top + line.offset_height();
+                if top < body.scroll_top() {
+                    body.set_scroll_top(top);
+                } else if bottom > body.scroll_top() + body.client_height() {
+                    body.set_scroll_top(bottom - body.client_height());
+                }
+            }
+            || {}
+        });
+    }
+
+    let highlighted = use_memo(
+        (state.buffer.revision(), props.session.extension.clone()),
+        |(_, extension)| {
+            SyntaxHighlighter::shared().highlight_lines(&state.buffer.text(), extension)
+        },
+    );
+
+    let lines = state.buffer.lines();
+    let gutter_width = lines.len().to_string().len() + 1;
+    let flags = [
+        (state.new_file, "[new file]"),
+        (state.shipped, "[site file]"),
+        (state.buffer.is_modified(), "[modified]"),
+    ];
+    let status = match &state.search {
+        Some(query) => html! {
+            <span>{"Search: "}{query}<span class="editor-search-caret">{" "}</span></span>
+        },
+        None if state.message.starts_with("Error:") => html! {
+            <span class="editor-error">{&state.message}</span>
+        },
+        None => html! { <span>{&state.message}</span> },
+    };
+
+    html! {
+        <div
+            ref={editor_ref}
+            class="editor"
+            tabindex="0"
+            onkeydown={on_keydown}
+            onpaste={on_paste}
+            onclick={on_click}
+        >
+            <div class="editor-bar">
+                <span>{"edit "}{&props.session.path}</span>
+                <span>
+                    {for flags.iter().filter(|(shown, _)| *shown).map(|(_, flag)| html! {
+                        <span class="editor-flag">{*flag}</span>
+                    })}
+                    <span class="editor-position">
+                        {format!("{}:{}", row + 1, column + 1)}
+                    </span>
+                </span>
+            </div>
+            <div ref={body_ref} class="editor-body terminal-scrollbar">
+                {for lines.iter().enumerate().map(|(index, line)| {
+                    let html = highlighted
+                        .get(index)
+                        .cloned()
+                        .unwrap_or_else(|| escape_html(line));
+                    let is_cursor_line = index == row;
+                    html! {
+                        <div
+                            class="editor-line"
+                            ref={if is_cursor_line { cursor_line_ref.clone() } else { NodeRef::default() }}
+                        >
+                            <span class="editor-gutter" style={format!("width: {}ch", gutter_width)}>
+                                {index + 1}
+                            </span>
+                            <span class="editor-text">
+                                <LineText html={html} />
+                                {match state.found {
+                                    Some((found_row, found_column, length)) if found_row == index => {
+                                        let start = state.buffer.display_column(index, found_column);
+                                        let end = state.buffer.display_column(index, found_column + length);
+                                        html! {
+                                            <span
+                                                class="editor-match"
+                                                style={format!("left: {}ch; width: {}ch", start, end - start)}
+                                            />
+                                        }
+                                    }
+                                    _ => html! {},
+                                }}
+                                if is_cursor_line {
+                                    <span
+                                        class="editor-cursor"
+                                        style={format!("left: {}ch", state.buffer.display_column(index, column))}
+                                    />
+                                }
+                            </span>
+                        </div>
+                    }
+                })}
+            </div>
+            <div class="editor-bar">{status}</div>
+            <div class="editor-keys">
+                {"^S Save   ^X Exit   ^F Search   Arrows, Home, End, PgUp, PgDn Move"}
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct LineTextProps {
+    pub html: String,
+}
+
+/// A line of highlighted HTML, only rewritten when it changes
+#[function_component(LineText)]
+fn line_text(props: &LineTextProps) -> Html {
+    let text_ref = use_node_ref();
+    {
+        let text_ref = text_ref.clone();
+        use_effect_with(props.html.clone(), move |html| {
+            if let Some(element) = text_ref.cast::<HtmlElement>() {
+                element.set_inner_html(html);
+            }
+            || {}
+        });
+    }
+    html! { <span ref={text_ref} /> }
+}
+
+/// Lines that fit in the editor body, for PageUp and PageDown
+fn page_lines(body_ref: &NodeRef) -> usize {
+    let Some(body) = body_ref.cast::<HtmlElement>() else {
+        return PAGE_LINES;
+    };
+    let line_height = body
+        .first_element_child()
+        .and_then(|line| line.dyn_into::<HtmlElement>().ok())
+        .map_or(0, |line| line.offset_height());
+    if line_height > 0 {
+        (body.client_height() / line_height).max(2) as usize - 1
+    } else {
+        PAGE_LINES
+    }
+}
@@ -0,0 +1,95 @@
+// Code Block Handlers
+// Handlers for the copy and run buttons on rendered markdown code blocks
+
+use crate::commands::CommandExecutor;
+use crate::utils::file_utils::markdown_processor::runnable_commands;
+use crate::utils::file_utils::run_block::run_into;
+use crate::utils::file_utils::toc::scroll_to_heading;
+use gloo::timers::callback::Timeout;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Element;
+use yew::prelude::*;
+
+/// How long the copy button shows its confirmation
+const COPIED_LABEL_MS: u32 = 1500;
+
+/// Create click handler for markdown output in the terminal history
+///
+/// Clicks are delegated from the terminal area, since rendered markdown is
+/// inserted as raw HTML: `data-code-action` buttons copy or run the code of
+/// their `.code-block` or run a lazy ```` ```run ```` command in place,
+/// elements with `data-command` run that command, and `#id` links scroll
+/// to the heading within the same rendered file.
+pub fn create_code_action_handler(
+    run_commands: Callback<Vec<String>>,
+    executor: CommandExecutor,
+) -> Callback<MouseEvent> {
+    Callback::from(move |e: MouseEvent| {
+        let Some(target) = e.target().and_then(|t| t.dyn_into::<Element>().ok()) else {
+            return;
+        };
+
+        if let Ok(Some(button)) = target.closest("[data-code-action]") {
+            let code = button
+                .closest(".code-block")
+                .ok()
+                .flatten()
+                .and_then(|block| block.get_attribute("data-code"))
+                .unwrap_or_default();
+
+            match button.get_attribute("data-code-action").as_deref() {
+                Some("copy") => copy_to_clipboard(code, button),
+                Some("run") => run_commands.emit(runnable_commands(&code)),
+                Some("run-inline") => {
+                    if let (Some(command), Some(target)) = (
+                        button.get_attribute("data-run-command"),
+                        button.get_attribute("data-run-target"),
+                    ) {
+                        run_into(&command, &target, &executor);
+                    }
+                }
+                _ => {}
+            }
+        } else if let Ok(Some(element)) = target.closest("[data-command]") {
+            // Let modified clicks open the link's href in a new tab
+            if e.ctrl_key() || e.meta_key() || e.shift_key() {
+                return;
+            }
+            if let Some(command) = element.get_attribute("data-command") {
+                e.prevent_default();
+                run_commands.emit(vec![command]);
+            }
+        } else if let Ok(Some(link)) = target.closest(".markdown-content a[href^='#']") {
+            // The page has a <base href>, so a plain anchor would reload the site
+            let id = link
+                .get_attribute("href")
+                .map(|href| href.trim_start_matches('#').to_string())
+                .unwrap_or_default();
+            if let Ok(Some(content)) = link.closest(".markdown-content") {
+                e.prevent_default();
+                scroll_to_heading(&content, &id);
+            }
+        }
+    })
+}
+
+/// Write text with the Clipboard API and briefly relabel the button
+fn copy_to_clipboard(text: String, button: Element) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let promise = window.navigator().clipboard().write_text(&text);
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let label = match JsFuture::from(promise).await {
+            Ok(_) => "copied",
+            Err(_) => "failed",
+        };
+        button.set_text_content(Some(label));
+        Timeout::new(COPIED_LABEL_MS, move || {
+            button.set_text_content(Some("copy"));
+        })
+        .forget();
+    });
+}
@@ -5,6 +5,7 @@ use crate::commands::{CommandExecutor, CommandResult, TerminalContext};
 use crate::components::history::{
     create_command_entry, create_html_entry, create_welcome_entry, HistoryEntry,
 };
+use crate::utils::prompt::{current_prompt, PromptSegment};
 use crate::utils::AppConfigService;
 use yew::prelude::*;
 
@@ -74,24 +75,44 @@ fn handle_enter_key(
     app_config: &UseStateHandle<AppConfigService>,
 ) {
     let command = (**input_value).clone();
+    run_command(
+        &command,
+        history,
+        command_history,
+        history_index,
+        executor,
+        container_ref,
+        app_config,
+    );
 
+    // Clear input
+    input_value.set(String::new());
+    cursor_position.set(0);
+}
+
+/// Run a command as if it had been typed at the prompt
+///
+/// Records it in the command history, appends its result to the terminal
+/// history and scrolls to the bottom.
+pub fn run_command(
+    command: &str,
+    history: &UseStateHandle<Vec<HistoryEntry>>,
+    command_history: &UseStateHandle<Vec<String>>,
+    history_index: &UseStateHandle<Option<usize>>,
+    executor: &UseStateHandle<CommandExecutor>,
+    container_ref: &NodeRef,
+    app_config: &UseStateHandle<AppConfigService>,
+) {
     // Add to command history
     let mut new_command_history = (**command_history).clone();
-    if !new_command_history
-        .last()
-        .map_or(false, |last| last == &command)
-    {
-        new_command_history.push(command.clone());
+    if new_command_history.last().is_none_or(|last| last != command) {
+        new_command_history.push(command.to_string());
     }
     command_history.set(new_command_history);
     history_index.set(None);
 
     // Execute command
-    execute_command(&command, history, executor, app_config);
-
-    // Clear input and scroll
-    input_value.set(String::new());
-    cursor_position.set(0);
+    execute_command(command, history, executor, app_config);
 
     if let Some(container) = container_ref.cast::<web_sys::HtmlElement>() {
         let _ = container.set_scroll_top(container.scroll_height());
@@ -107,6 +128,7 @@ fn execute_command(
     let history_clone_for_clear = history.clone();
     let executor_clone_for_execute = executor.clone();
     let app_config_clone_for_theme = app_config.clone();
+    let app_config_clone_for_code_theme = app_config.clone();
 
     let context = TerminalContext {
         app_config: (**app_config).clone(),
@@ -124,6 +146,8 @@ fn execute_command(
                 }),
                 app_config: AppConfigService::new(),
                 set_theme: None,
+                set_code_theme: None,
+                stdin: None,
             };
             executor_clone_for_execute.execute(command_str, &minimal_context)
         }),
@@ -136,23 +160,42 @@ fn execute_command(
                 false
             }
         })),
+        set_code_theme: Some(std::rc::Rc::new(move |theme: &str| {
+            let mut config = (*app_config_clone_for_code_theme).clone();
+            if config.set_code_theme(theme) {
+                app_config_clone_for_code_theme.set(config);
+                true
+            } else {
+                false
+            }
+        })),
+        stdin: None,
     };
 
+    // Snapshot the prompt before running, so the entry keeps the cwd and
+    // status it was run with
+    let prompt = current_prompt(executor, &app_config.config);
     let result = executor.execute(command, &context);
-    handle_command_result(result, command.to_string(), history);
+    handle_command_result(result, command.to_string(), prompt, history, executor);
 }
 
 /// Handle command execution result
 pub fn handle_command_result(
     result: CommandResult,
     command: String,
+    prompt: Vec<PromptSegment>,
     history: &UseStateHandle<Vec<HistoryEntry>>,
+    executor: &CommandExecutor,
 ) {
+    if let Some(status) = result.exit_status() {
+        executor.environment.borrow_mut().set_last_status(status);
+    }
+
     match result {
         CommandResult::Success(output) => {
             if command.trim() != "clear" {
                 let mut current_history = (**history).clone();
-                current_history.push(create_command_entry(command, output, false));
+                current_history.push(create_command_entry(command, output, false, prompt));
                 history.set(current_history);
             }
         }
@@ -162,12 +205,13 @@ pub fn handle_command_result(
                 command,
                 format!("Error: {}", error),
                 true,
+                prompt,
             ));
             history.set(current_history);
         }
         CommandResult::Html(html_content) => {
             let mut current_history = (**history).clone();
-            current_history.push(create_html_entry(command, html_content));
+            current_history.push(create_html_entry(command, html_content, prompt));
             history.set(current_history);
         }
         CommandResult::Async(future) => {
@@ -176,16 +220,22 @@ pub fn handle_command_result(
                 command.clone(),
                 "Loading...".to_string(),
                 false,
+                prompt.clone(),
             ));
             let loading_index = current_history.len() - 1;
             history.set(current_history);
 
             let history_clone = history.clone();
             let command_clone = command.clone();
+            let environment = executor.environment.clone();
             wasm_bindgen_futures::spawn_local(async move {
                 let async_result = future.await;
                 let mut current_history = (*history_clone).clone();
 
+                if let Some(status) = async_result.exit_status() {
+                    environment.borrow_mut().set_last_status(status);
+                }
+
                 // Remove loading entry
                 if current_history.len() > loading_index {
                     if let Some(entry) = current_history.get(loading_index) {
@@ -200,23 +250,27 @@ pub fn handle_command_result(
                 // Add final result
                 match async_result {
                     CommandResult::Success(output) => {
-                        current_history.push(create_command_entry(command_clone, output, false));
+                        current_history
+                            .push(create_command_entry(command_clone, output, false, prompt));
                     }
                     CommandResult::Error(error) => {
                         current_history.push(create_command_entry(
                             command_clone,
                             format!("Error: {}", error),
                             true,
+                            prompt,
                         ));
                     }
                     CommandResult::Html(html_content) => {
-                        current_history.push(create_html_entry(command_clone, html_content));
+                        current_history.push(create_html_entry(command_clone, html_content, prompt));
                     }
                     CommandResult::Async(_) => {
+                        environment.borrow_mut().set_last_status(1);
                         current_history.push(create_command_entry(
                             command_clone,
                             "Error: Nested async operations not supported".to_string(),
                             true,
+                            prompt,
                         ));
                     }
                 }
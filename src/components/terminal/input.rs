@@ -4,6 +4,7 @@
 use crate::commands::CommandExecutor;
 use crate::components::syntax::{parse_syntax_segments, render_syntax_segments};
 use crate::hooks::use_app_config;
+use crate::utils::prompt::{current_prompt, PromptSegment};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
@@ -22,9 +23,12 @@ pub struct TerminalInputProps {
 
 #[function_component(TerminalInput)]
 pub fn terminal_input(props: &TerminalInputProps) -> Html {
+    let app_config = use_app_config();
+    let prompt = current_prompt(&props.executor, &app_config.config);
+
     html! {
         <div class="flex items-start">
-            <TerminalPrompt />
+            <TerminalPrompt segments={prompt} />
             <div class="flex-1 relative">
                 <HiddenInput
                     input_ref={props.input_ref.clone()}
@@ -48,13 +52,25 @@ pub fn terminal_input(props: &TerminalInputProps) -> Html {
     }
 }
 
-#[function_component(TerminalPrompt)]
-pub fn terminal_prompt() -> Html {
-    let app_config = use_app_config();
+#[derive(Properties, PartialEq)]
+pub struct TerminalPromptProps {
+    pub segments: Vec<PromptSegment>,
+}
 
+#[function_component(TerminalPrompt)]
+pub fn terminal_prompt(props: &TerminalPromptProps) -> Html {
     html! {
-        <span class="mr-2 mt-0.5 text-sm font-mono font-bold" style={format!("color: {}", app_config.config.terminal.prompt.color)}>
-            {format!("{} ", app_config.config.terminal.prompt.symbol)}
+        <span class="mr-2 mt-0.5 text-sm font-mono whitespace-pre">
+            {for props.segments.iter().map(|segment| {
+                html! {
+                    <span
+                        class={classes!(segment.bold.then_some("font-bold"))}
+                        style={format!("color: {}", segment.color)}
+                    >
+                        {&segment.text}
+                    </span>
+                }
+            })}
         </span>
     }
 }
@@ -3,6 +3,7 @@
 
 pub mod container;
 pub mod content;
+pub mod editor;
 pub mod handlers;
 pub mod history;
 pub mod input;
@@ -4,14 +4,24 @@
 use super::{
     completion::get_completion_suggestions as get_fs_completion,
     navigation::{get_node_at_path, path_to_string, resolve_path},
-    operations::{get_file_info as get_fs_file_info, read_directory as read_fs_directory, read_directory_with_metadata as read_fs_directory_with_metadata},
+    operations::{get_file_info as get_fs_file_info, get_node as get_fs_node},
+    overlay,
+    persistence::{OverlaySnapshot, OverlayStorage, SnapshotError},
     types::FileSystemNode,
 };
+use std::rc::Rc;
 
 #[derive(Debug, Clone)]
 pub struct FileSystem {
+    /// Shipped tree, as embedded at build time
+    base: FileSystemNode,
+    /// Working tree: the shipped tree with the overlay's changes applied
     root: FileSystemNode,
     current_path: Vec<String>,
+    /// Where overlay changes are saved, if anywhere
+    storage: Option<Rc<dyn OverlayStorage>>,
+    /// Current time, for the `modified` date of changed entries
+    clock: fn() -> String,
 }
 
 impl FileSystem {
@@ -22,11 +32,20 @@ impl FileSystem {
             serde_json::from_str(metadata_json).expect("Failed to parse filesystem metadata");
 
         Self {
+            base: root.clone(),
             root,
             current_path: vec![],
+            storage: None,
+            clock: overlay::timestamp,
         }
     }
 
+    /// Date changes with `clock` instead of the browser's, which tests don't have
+    #[cfg(test)]
+    pub fn set_clock(&mut self, clock: fn() -> String) {
+        self.clock = clock;
+    }
+
     /// Get current directory path as string
     pub fn current_path_string(&self) -> String {
         path_to_string(&self.current_path)
@@ -52,9 +71,9 @@ impl FileSystem {
         }
     }
 
-    /// Read directory contents
-    pub fn read_directory(&self, target_dir: Option<&str>) -> Result<Vec<String>, String> {
-        read_fs_directory(&self.root, &self.current_path, target_dir)
+    /// Absolute path of `target`, resolved from the current directory
+    pub fn absolute_path(&self, target: &str) -> String {
+        path_to_string(&resolve_path(&self.current_path, target))
     }
 
     /// Check if a file exists and get its metadata
@@ -62,12 +81,9 @@ impl FileSystem {
         get_fs_file_info(&self.root, &self.current_path, filename)
     }
 
-    /// Get directory contents with metadata (for ls --link)
-    pub fn read_directory_with_metadata(
-        &self,
-        target_dir: Option<&str>,
-    ) -> Result<Vec<(String, Option<&FileSystemNode>)>, String> {
-        read_fs_directory_with_metadata(&self.root, &self.current_path, target_dir)
+    /// Get a file or directory node
+    pub fn get_node(&self, target: &str) -> Result<&FileSystemNode, String> {
+        get_fs_node(&self.root, &self.current_path, target)
     }
 
     /// Get completion suggestions for tab completion
@@ -78,4 +94,139 @@ impl FileSystem {
     ) -> (Vec<String>, String) {
         get_fs_completion(&self.root, &self.current_path, input, cursor_position)
     }
-}
\ No newline at end of file
+
+    /// Create or overwrite a file in the overlay
+    pub fn write_file(&mut self, target: &str, content: String) -> Result<(), String> {
+        let path = resolve_path(&self.current_path, target);
+        overlay::write_file(&mut self.root, &path, target, content, &(self.clock)())?;
+        self.persist()
+    }
+
+    /// Create an empty file if there is none
+    pub fn touch(&mut self, target: &str) -> Result<(), String> {
+        let path = resolve_path(&self.current_path, target);
+        overlay::touch(&mut self.root, &path, target, &(self.clock)())?;
+        self.persist()
+    }
+
+    /// Create a directory; with `parents`, also any missing ones above it
+    pub fn make_directory(&mut self, target: &str, parents: bool) -> Result<(), String> {
+        let path = resolve_path(&self.current_path, target);
+        overlay::make_directory(&mut self.root, &path, target, parents, &(self.clock)())?;
+        self.persist()
+    }
+
+    /// Remove a file, or a directory and everything in it when `recursive`
+    pub fn remove(&mut self, target: &str, recursive: bool) -> Result<(), String> {
+        let path = resolve_path(&self.current_path, target);
+        overlay::remove(&mut self.root, &path, target, recursive)?;
+        self.leave_missing_directory();
+        self.persist()
+    }
+
+    /// Copy a file, or a directory when `recursive`, to or into `to`
+    pub fn copy(&mut self, from: &str, to: &str, recursive: bool) -> Result<(), String> {
+        let from_path = resolve_path(&self.current_path, from);
+        let to_path = resolve_path(&self.current_path, to);
+        overlay::copy(
+            &mut self.root,
+            &from_path,
+            &to_path,
+            (from, to),
+            recursive,
+            &(self.clock)(),
+        )?;
+        self.persist()
+    }
+
+    /// Move or rename an entry to or into `to`
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), String> {
+        let from_path = resolve_path(&self.current_path, from);
+        let to_path = resolve_path(&self.current_path, to);
+        overlay::rename(&mut self.root, &from_path, &to_path, (from, to))?;
+        self.leave_missing_directory();
+        self.persist()
+    }
+
+    /// Discard every overlay change, restoring the shipped tree
+    pub fn reset(&mut self) -> Result<(), String> {
+        self.root = self.base.clone();
+        self.leave_missing_directory();
+        self.persist()
+    }
+
+    /// The overlay's changes to the shipped tree
+    pub fn snapshot(&self) -> OverlaySnapshot {
+        OverlaySnapshot::diff(&self.base, &self.root)
+    }
+
+    /// Replace the overlay with `snapshot`'s changes
+    pub fn restore(&mut self, snapshot: &OverlaySnapshot) -> Result<(), String> {
+        self.root = snapshot.apply(&self.base);
+        self.leave_missing_directory();
+        self.persist()
+    }
+
+    /// Make several changes with `changes`, saving the overlay once at the end
+    pub fn batch<T>(&mut self, changes: impl FnOnce(&mut Self) -> T) -> Result<T, String> {
+        let storage = self.storage.take();
+        let result = changes(self);
+        self.storage = storage;
+        self.persist().map(|()| result)
+    }
+
+    /// Save overlay changes to `storage` from now on, restoring any saved there
+    ///
+    /// Unreadable saved data is reported and left as it is until the next
+    /// change overwrites it; data from a newer schema is never overwritten,
+    /// so changes are then kept for this session only.
+    pub fn attach_storage(&mut self, storage: Rc<dyn OverlayStorage>) -> Result<(), String> {
+        let saved = storage.load().map_err(|error| error.to_string())?;
+        if let Some(json) = saved {
+            match OverlaySnapshot::from_json(&json) {
+                Ok(snapshot) => self.root = snapshot.apply(&self.base),
+                Err(error @ SnapshotError::NewerVersion(_)) => return Err(error.to_string()),
+                Err(error) => {
+                    self.storage = Some(storage);
+                    return Err(error.to_string());
+                }
+            }
+        }
+        self.storage = Some(storage);
+        Ok(())
+    }
+
+    /// Whether overlay changes are being saved
+    pub fn is_persistent(&self) -> bool {
+        self.storage.is_some()
+    }
+
+    /// Save the overlay to storage, if attached
+    fn persist(&self) -> Result<(), String> {
+        let Some(storage) = &self.storage else {
+            return Ok(());
+        };
+        let snapshot = self.snapshot();
+        let saved = if snapshot.is_empty() {
+            storage.clear()
+        } else {
+            storage.save(&snapshot.to_json())
+        };
+        saved.map_err(|error| {
+            format!(
+                "changes not saved, {}; they last until the page is reloaded",
+                error
+            )
+        })
+    }
+
+    /// Step up out of the current directory while it no longer exists
+    fn leave_missing_directory(&mut self) {
+        while !matches!(
+            get_node_at_path(&self.root, &self.current_path),
+            Some(node) if node.node_type == "directory"
+        ) {
+            self.current_path.pop();
+        }
+    }
+}
@@ -0,0 +1,266 @@
+// Filesystem Search
+// Walks the node tree collecting entries that match find predicates
+
+use super::types::FileSystemNode;
+
+/// A test an entry must pass to be listed; all predicates must match
+#[derive(Debug, Clone, PartialEq)]
+pub enum Predicate {
+    /// Name matches a glob pattern (`*`, `?`, `[abc]`)
+    Name { pattern: String, ignore_case: bool },
+    /// Entry is a directory (`true`) or a file (`false`)
+    Type { directory: bool },
+    /// File metadata lists the tag, compared case-insensitively
+    Tag(String),
+    /// Modified after an ISO date or timestamp
+    Newer(String),
+}
+
+impl Predicate {
+    fn matches(&self, node: &FileSystemNode) -> bool {
+        match self {
+            Predicate::Name {
+                pattern,
+                ignore_case: true,
+            } => glob_match(&pattern.to_lowercase(), &node.name.to_lowercase()),
+            Predicate::Name { pattern, .. } => glob_match(pattern, &node.name),
+            Predicate::Type { directory } => (node.node_type == "directory") == *directory,
+            Predicate::Tag(tag) => node
+                .tags
+                .as_ref()
+                .is_some_and(|tags| tags.iter().any(|t| t.eq_ignore_ascii_case(tag))),
+            // ISO timestamps order the same as strings
+            Predicate::Newer(date) => node
+                .modified
+                .as_deref()
+                .is_some_and(|modified| modified > date.as_str()),
+        }
+    }
+}
+
+/// What to search for below a starting directory
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FindQuery {
+    pub predicates: Vec<Predicate>,
+    /// Deepest level to descend to; the starting point is level 0
+    pub max_depth: Option<usize>,
+}
+
+/// Entries under `start` matching `query`, in depth-first name order
+///
+/// Paths are `display_path` (how the user named `start`) joined with the
+/// names below it, the way `find` prints them.
+pub fn find<'a>(
+    start: &'a FileSystemNode,
+    display_path: &str,
+    query: &FindQuery,
+) -> Vec<(String, &'a FileSystemNode)> {
+    let mut matches = Vec::new();
+    walk(start, display_path.to_string(), 0, query, &mut matches);
+    matches
+}
+
+fn walk<'a>(
+    node: &'a FileSystemNode,
+    path: String,
+    depth: usize,
+    query: &FindQuery,
+    matches: &mut Vec<(String, &'a FileSystemNode)>,
+) {
+    if query
+        .predicates
+        .iter()
+        .all(|predicate| predicate.matches(node))
+    {
+        matches.push((path.clone(), node));
+    }
+    if query.max_depth.is_some_and(|max_depth| depth >= max_depth) {
+        return;
+    }
+
+    let mut children: Vec<&FileSystemNode> = node.children.values().collect();
+    children.sort_by(|a, b| a.name.cmp(&b.name));
+    let prefix = path.trim_end_matches('/');
+    for child in children {
+        let child_path = format!("{}/{}", prefix, child.name);
+        walk(child, child_path, depth + 1, query, matches);
+    }
+}
+
+/// Match a name against a shell glob with `*`, `?` and `[...]` classes
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Where to resume after the last `*` if the rest fails to match
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+                continue;
+            }
+            Some('?') => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            Some('[') => {
+                if let Some((matched, next)) = match_class(&pattern, p, name[n]) {
+                    if matched {
+                        p = next;
+                        n += 1;
+                        continue;
+                    }
+                } else if name[n] == '[' {
+                    // Unclosed `[` is a literal
+                    p += 1;
+                    n += 1;
+                    continue;
+                }
+            }
+            Some(&c) if c == name[n] => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        match backtrack {
+            Some((star, start)) => {
+                p = star + 1;
+                n = start + 1;
+                backtrack = Some((star, start + 1));
+            }
+            None => return false,
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Match `c` against the class starting at `pattern[start] == '['`
+///
+/// Returns whether it matched and the index after the closing `]`, or
+/// `None` if the class is never closed.
+fn match_class(pattern: &[char], start: usize, c: char) -> Option<(bool, usize)> {
+    let mut i = start + 1;
+    let negated = matches!(pattern.get(i), Some('!' | '^'));
+    if negated {
+        i += 1;
+    }
+
+    let mut matched = false;
+    let mut first = true;
+    while let Some(&class_char) = pattern.get(i) {
+        if class_char == ']' && !first {
+            return Some((matched != negated, i + 1));
+        }
+        first = false;
+        if pattern.get(i + 1) == Some(&'-') && pattern.get(i + 2).is_some_and(|&end| end != ']') {
+            matched |= (class_char..=pattern[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            matched |= class_char == c;
+            i += 1;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::node;
+
+    fn file(name: &str, modified: &str, tags: &[&str]) -> FileSystemNode {
+        FileSystemNode {
+            modified: Some(modified.to_string()),
+            tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+            ..node(name, "file", vec![])
+        }
+    }
+
+    fn tree() -> FileSystemNode {
+        node(
+            "",
+            "directory",
+            vec![
+                file("index.md", "2023-06-01T00:00:00Z", &[]),
+                node(
+                    "posts",
+                    "directory",
+                    vec![
+                        file("rust.md", "2024-03-01T12:00:00Z", &["Rust", "web"]),
+                        file("notes.txt", "2024-05-01T12:00:00Z", &["rust"]),
+                        node(
+                            "old",
+                            "directory",
+                            vec![file("a.md", "2022-01-01T00:00:00Z", &["rust"])],
+                        ),
+                    ],
+                ),
+            ],
+        )
+    }
+
+    fn paths(query: FindQuery) -> Vec<String> {
+        let root = tree();
+        find(&root, "/", &query)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.md", "index.md"));
+        assert!(!glob_match("*.md", "index.mdx"));
+        assert!(glob_match("a*b*c", "aXXbYYbc"));
+        assert!(glob_match("?at", "cat"));
+        assert!(glob_match("[a-c]at", "bat"));
+        assert!(!glob_match("[!a-c]at", "bat"));
+        assert!(glob_match("[abc", "[abc"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[test]
+    fn test_find_predicates() {
+        assert_eq!(
+            paths(FindQuery::default()),
+            [
+                "/",
+                "/index.md",
+                "/posts",
+                "/posts/notes.txt",
+                "/posts/old",
+                "/posts/old/a.md",
+                "/posts/rust.md"
+            ]
+        );
+        assert_eq!(
+            paths(FindQuery {
+                predicates: vec![
+                    Predicate::Name {
+                        pattern: "*.md".to_string(),
+                        ignore_case: false
+                    },
+                    Predicate::Tag("rust".to_string()),
+                    Predicate::Newer("2024-01-01".to_string()),
+                ],
+                max_depth: None,
+            }),
+            ["/posts/rust.md"]
+        );
+        assert_eq!(
+            paths(FindQuery {
+                predicates: vec![Predicate::Type { directory: true }],
+                max_depth: Some(1),
+            }),
+            ["/", "/posts"]
+        );
+    }
+}
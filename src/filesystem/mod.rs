@@ -3,8 +3,11 @@
 
 pub mod completion;
 pub mod filesystem;
+pub mod find;
 pub mod navigation;
 pub mod operations;
+pub mod overlay;
+pub mod persistence;
 pub mod types;
 
 // Re-export main filesystem for backward compatibility
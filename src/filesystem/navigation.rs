@@ -5,41 +5,22 @@ use super::types::FileSystemNode;
 
 /// Resolve a relative or absolute path from current location
 pub fn resolve_path(current_path: &[String], target: &str) -> Vec<String> {
-    if target.starts_with('/') {
-        // Absolute path
-        if target == "/" {
-            vec![]
-        } else {
-            target
-                .trim_start_matches('/')
-                .split('/')
-                .map(|s| s.to_string())
-                .collect()
-        }
-    } else if target == ".." {
-        // Go up one directory
-        if current_path.is_empty() {
-            vec![]
-        } else {
-            let mut new_path = current_path.to_vec();
-            new_path.pop();
-            new_path
-        }
-    } else if target == "." {
-        // Current directory
-        current_path.to_vec()
+    // Absolute paths start from the root, relative ones from here
+    let mut new_path = if target.starts_with('/') {
+        vec![]
     } else {
-        // Relative path
-        let mut new_path = current_path.to_vec();
-        for component in target.split('/') {
-            if component == ".." {
+        current_path.to_vec()
+    };
+    for component in target.split('/') {
+        match component {
+            ".." => {
                 new_path.pop();
-            } else if component != "." && !component.is_empty() {
-                new_path.push(component.to_string());
             }
+            "." | "" => {}
+            component => new_path.push(component.to_string()),
         }
-        new_path
     }
+    new_path
 }
 
 /// Navigate to a node at the given path
@@ -0,0 +1,440 @@
+// Filesystem Overlay
+// Copy-on-write changes to the working tree, kept in memory
+
+use super::navigation::get_node_at_path;
+use super::types::FileSystemNode;
+use crate::utils::file_utils::frontmatter::Frontmatter;
+
+/// Current time in the ISO 8601 format of `modified` in the metadata
+pub fn timestamp() -> String {
+    String::from(js_sys::Date::new_0().to_iso_string())
+}
+
+/// Mutable counterpart of `get_node_at_path`
+pub(super) fn get_node_at_path_mut<'a>(
+    root: &'a mut FileSystemNode,
+    path: &[String],
+) -> Option<&'a mut FileSystemNode> {
+    let mut current = root;
+    for component in path {
+        if current.node_type != "directory" {
+            return None;
+        }
+        current = current.children.get_mut(component)?;
+    }
+    Some(current)
+}
+
+/// The directory that holds `path`, with the name of the entry in it
+fn parent_mut<'a>(
+    root: &'a mut FileSystemNode,
+    path: &'a [String],
+    display: &str,
+) -> Result<(&'a mut FileSystemNode, &'a String), String> {
+    let (name, parent_path) = path
+        .split_last()
+        .ok_or_else(|| format!("is a directory: {}", display))?;
+    match get_node_at_path_mut(root, parent_path) {
+        Some(parent) if parent.node_type == "directory" => Ok((parent, name)),
+        _ => Err(format!("no such file or directory: {}", display)),
+    }
+}
+
+/// A new, empty overlay entry
+fn overlay_node(node_type: &str, path: &[String], now: &str) -> FileSystemNode {
+    let name = path.last().cloned().unwrap_or_default();
+    let is_file = node_type == "file";
+    FileSystemNode {
+        node_type: node_type.to_string(),
+        extension: is_file
+            .then(|| {
+                name.rsplit_once('.')
+                    .map(|(_, extension)| extension.to_string())
+            })
+            .flatten(),
+        name,
+        path: path.join("/"),
+        children: Default::default(),
+        size: is_file.then_some(0),
+        modified: Some(now.to_string()),
+        title: None,
+        description: None,
+        tags: None,
+        overlay: true,
+        content: is_file.then(String::new),
+        source: None,
+    }
+}
+
+/// Replace a file's content, refreshing the metadata generate_metadata.js
+/// would record for it
+fn set_content(node: &mut FileSystemNode, content: String, now: &str) {
+    if matches!(node.extension.as_deref(), Some("md" | "markdown")) {
+        let frontmatter = Frontmatter::parse(&content);
+        node.title = frontmatter.get("title").map(String::from);
+        node.description = frontmatter.get("description").map(String::from);
+        node.tags = frontmatter
+            .get("tags")
+            .or_else(|| frontmatter.get("tag"))
+            .map(|tags| {
+                tags.split(',')
+                    .map(|tag| tag.trim().to_string())
+                    .filter(|tag| !tag.is_empty())
+                    .collect()
+            });
+    }
+    node.size = Some(content.len() as u64);
+    node.modified = Some(now.to_string());
+    node.content = Some(content);
+    node.source = None;
+    node.overlay = true;
+}
+
+/// Create or overwrite the file at `path`
+pub fn write_file(
+    root: &mut FileSystemNode,
+    path: &[String],
+    display: &str,
+    content: String,
+    now: &str,
+) -> Result<(), String> {
+    let (parent, name) = parent_mut(root, path, display)?;
+    let node = parent
+        .children
+        .entry(name.clone())
+        .or_insert_with(|| overlay_node("file", path, now));
+    if node.node_type == "directory" {
+        return Err(format!("is a directory: {}", display));
+    }
+    set_content(node, content, now);
+    Ok(())
+}
+
+/// Create an empty file, or update the time of an overlay entry
+///
+/// Shipped files are left as they are.
+pub fn touch(
+    root: &mut FileSystemNode,
+    path: &[String],
+    display: &str,
+    now: &str,
+) -> Result<(), String> {
+    let (parent, name) = parent_mut(root, path, display)?;
+    match parent.children.get_mut(name) {
+        Some(node) if node.overlay => node.modified = Some(now.to_string()),
+        Some(_) => {}
+        None => {
+            parent
+                .children
+                .insert(name.clone(), overlay_node("file", path, now));
+        }
+    }
+    Ok(())
+}
+
+/// Create a directory; with `parents`, also any missing ones above it
+pub fn make_directory(
+    root: &mut FileSystemNode,
+    path: &[String],
+    display: &str,
+    parents: bool,
+    now: &str,
+) -> Result<(), String> {
+    if let Some(existing) = get_node_at_path(root, path) {
+        return if parents && existing.node_type == "directory" {
+            Ok(())
+        } else {
+            Err(format!(
+                "cannot create directory '{}': File exists",
+                display
+            ))
+        };
+    }
+
+    let start = if parents { 1 } else { path.len() };
+    for end in start..=path.len() {
+        let (parent, name) = parent_mut(root, &path[..end], display).map_err(|_| {
+            format!(
+                "cannot create directory '{}': No such file or directory",
+                display
+            )
+        })?;
+        let node = parent
+            .children
+            .entry(name.clone())
+            .or_insert_with(|| overlay_node("directory", &path[..end], now));
+        if node.node_type != "directory" {
+            return Err(format!(
+                "cannot create directory '{}': Not a directory",
+                display
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Remove the entry at `path`; directories only when `recursive`
+pub fn remove(
+    root: &mut FileSystemNode,
+    path: &[String],
+    display: &str,
+    recursive: bool,
+) -> Result<(), String> {
+    if path.is_empty() {
+        return Err("cannot remove '/': Permission denied".to_string());
+    }
+    let (parent, name) = parent_mut(root, path, display)
+        .map_err(|_| format!("cannot remove '{}': No such file or directory", display))?;
+    match parent.children.get(name) {
+        None => Err(format!(
+            "cannot remove '{}': No such file or directory",
+            display
+        )),
+        Some(node) if node.node_type == "directory" && !recursive => {
+            Err(format!("cannot remove '{}': Is a directory", display))
+        }
+        Some(_) => {
+            parent.children.remove(name);
+            Ok(())
+        }
+    }
+}
+
+/// Copy `from` to `to`, or into `to` if it is a directory
+///
+/// Copies of shipped files keep reading from the original's data path.
+pub fn copy(
+    root: &mut FileSystemNode,
+    from: &[String],
+    to: &[String],
+    displays: (&str, &str),
+    recursive: bool,
+    now: &str,
+) -> Result<(), String> {
+    let (from_display, _) = displays;
+    let source = get_node_at_path(root, from)
+        .ok_or_else(|| format!("cannot stat '{}': No such file or directory", from_display))?;
+    if source.node_type == "directory" && !recursive {
+        return Err(format!(
+            "-r not specified; omitting directory '{}'",
+            from_display
+        ));
+    }
+    let mut copy = source.clone();
+    let destination = destination(root, from, to, displays, "copy")?;
+    relocate(&mut copy, &destination, Some(now));
+    insert(root, &destination, copy, displays.1)
+}
+
+/// Move `from` to `to`, or into `to` if it is a directory
+pub fn rename(
+    root: &mut FileSystemNode,
+    from: &[String],
+    to: &[String],
+    displays: (&str, &str),
+) -> Result<(), String> {
+    let (from_display, _) = displays;
+    if get_node_at_path(root, from).is_none() {
+        return Err(format!(
+            "cannot stat '{}': No such file or directory",
+            from_display
+        ));
+    }
+    let destination = destination(root, from, to, displays, "move")?;
+    if destination == from {
+        return Ok(());
+    }
+
+    let (parent, name) = parent_mut(root, from, from_display)?;
+    let mut node = parent
+        .children
+        .remove(name)
+        .ok_or_else(|| format!("cannot stat '{}': No such file or directory", from_display))?;
+    relocate(&mut node, &destination, None);
+    insert(root, &destination, node, displays.1)
+}
+
+/// Where `from` ends up when copied or moved to `to`
+fn destination(
+    root: &FileSystemNode,
+    from: &[String],
+    to: &[String],
+    (from_display, to_display): (&str, &str),
+    action: &str,
+) -> Result<Vec<String>, String> {
+    let Some(name) = from.last() else {
+        return Err(format!("cannot {} '/'", action));
+    };
+    let destination = match get_node_at_path(root, to) {
+        Some(node) if node.node_type == "directory" => {
+            let mut inside = to.to_vec();
+            inside.push(name.clone());
+            inside
+        }
+        _ => to.to_vec(),
+    };
+
+    let is_directory =
+        get_node_at_path(root, from).is_some_and(|node| node.node_type == "directory");
+    if is_directory && destination.len() > from.len() && destination.starts_with(from) {
+        return Err(format!(
+            "cannot {} '{}' to a subdirectory of itself, '{}'",
+            action, from_display, to_display
+        ));
+    }
+    if let Some(existing) = get_node_at_path(root, &destination) {
+        match (is_directory, existing.node_type == "directory") {
+            (false, true) => {
+                return Err(format!(
+                    "cannot overwrite directory '{}' with non-directory",
+                    to_display
+                ))
+            }
+            (true, false) => {
+                return Err(format!(
+                    "cannot overwrite non-directory '{}' with directory '{}'",
+                    to_display, from_display
+                ))
+            }
+            _ => {}
+        }
+    }
+    Ok(destination)
+}
+
+/// Put `node` at `path`, replacing whatever is there
+fn insert(
+    root: &mut FileSystemNode,
+    path: &[String],
+    node: FileSystemNode,
+    display: &str,
+) -> Result<(), String> {
+    let (parent, name) = parent_mut(root, path, display)?;
+    parent.children.insert(name.clone(), node);
+    Ok(())
+}
+
+/// Give a copied or moved subtree its new paths, marking it as overlay
+///
+/// Shipped files remember their original data path so they can still be read.
+fn relocate(node: &mut FileSystemNode, path: &[String], now: Option<&str>) {
+    if node.node_type == "file" && node.content.is_none() && node.source.is_none() {
+        node.source = Some(node.path.clone());
+    }
+    node.path = path.join("/");
+    node.name = path.last().cloned().unwrap_or_default();
+    node.overlay = true;
+    if let Some(now) = now {
+        node.modified = Some(now.to_string());
+    }
+    for (name, child) in node.children.iter_mut() {
+        let mut child_path = path.to_vec();
+        child_path.push(name.clone());
+        relocate(child, &child_path, now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: &str = "2026-01-01T00:00:00.000Z";
+
+    fn path(path: &str) -> Vec<String> {
+        path.split('/')
+            .filter(|part| !part.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    fn root() -> FileSystemNode {
+        let mut root = overlay_node("directory", &[], NOW);
+        root.overlay = false;
+        let mut posts = overlay_node("directory", &path("posts"), NOW);
+        posts.overlay = false;
+        let mut shipped = overlay_node("file", &path("posts/a.md"), NOW);
+        shipped.overlay = false;
+        shipped.content = None;
+        posts.children.insert("a.md".to_string(), shipped);
+        root.children.insert("posts".to_string(), posts);
+        root
+    }
+
+    #[test]
+    fn test_write_and_directories() {
+        let mut root = root();
+        write_file(
+            &mut root,
+            &path("notes.md"),
+            "notes.md",
+            "---\ntitle: Notes\n---\nhi\n".to_string(),
+            NOW,
+        )
+        .unwrap();
+        let notes = get_node_at_path(&root, &path("notes.md")).unwrap();
+        assert!(notes.overlay);
+        assert_eq!(notes.title.as_deref(), Some("Notes"));
+        assert_eq!(notes.size, Some(24));
+
+        assert!(write_file(&mut root, &path("missing/x"), "x", String::new(), NOW).is_err());
+        assert!(write_file(&mut root, &path("posts"), "posts", String::new(), NOW).is_err());
+
+        assert!(make_directory(&mut root, &path("a/b"), "a/b", false, NOW).is_err());
+        make_directory(&mut root, &path("a/b"), "a/b", true, NOW).unwrap();
+        make_directory(&mut root, &path("a/b"), "a/b", true, NOW).unwrap();
+        assert!(make_directory(&mut root, &path("a"), "a", false, NOW).is_err());
+
+        assert!(remove(&mut root, &path("a"), "a", false).is_err());
+        remove(&mut root, &path("a"), "a", true).unwrap();
+        assert!(get_node_at_path(&root, &path("a")).is_none());
+        assert!(remove(&mut root, &[], "/", true).is_err());
+    }
+
+    #[test]
+    fn test_copy_and_move() {
+        let mut root = root();
+        copy(
+            &mut root,
+            &path("posts"),
+            &path("backup"),
+            ("posts", "backup"),
+            true,
+            NOW,
+        )
+        .unwrap();
+        let copied = get_node_at_path(&root, &path("backup/a.md")).unwrap();
+        assert_eq!(copied.path, "backup/a.md");
+        assert_eq!(copied.source.as_deref(), Some("posts/a.md"));
+        assert!(copied.overlay);
+        assert!(copy(
+            &mut root,
+            &path("posts"),
+            &path("x"),
+            ("posts", "x"),
+            false,
+            NOW
+        )
+        .is_err());
+
+        // Moving into a directory keeps the name
+        rename(
+            &mut root,
+            &path("backup/a.md"),
+            &path("posts"),
+            ("backup/a.md", "posts"),
+        )
+        .unwrap();
+        assert!(get_node_at_path(&root, &path("backup/a.md")).is_none());
+        let moved = get_node_at_path(&root, &path("posts/a.md")).unwrap();
+        assert_eq!(moved.source.as_deref(), Some("posts/a.md"));
+
+        assert!(rename(
+            &mut root,
+            &path("posts"),
+            &path("posts/sub"),
+            ("posts", "posts/sub")
+        )
+        .is_err());
+        assert!(rename(&mut root, &path("nope"), &path("x"), ("nope", "x")).is_err());
+    }
+}
@@ -0,0 +1,354 @@
+// Overlay Persistence
+// Saves the writable overlay in browser storage so it survives reloads
+
+use super::navigation::resolve_path;
+use super::overlay::get_node_at_path_mut;
+use super::types::FileSystemNode;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// localStorage key the overlay is saved under
+pub const OVERLAY_STORAGE_KEY: &str = "terminal-overlay";
+/// Snapshot format written by this build
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Why browser storage could not be used
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageError {
+    /// No storage, e.g. when disabled by privacy settings
+    Unavailable(String),
+    /// The browser refused to store more data
+    QuotaExceeded,
+    Failed(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Unavailable(reason) => {
+                write!(f, "browser storage is unavailable: {}", reason)
+            }
+            StorageError::QuotaExceeded => write!(f, "browser storage is full"),
+            StorageError::Failed(reason) => write!(f, "browser storage failed: {}", reason),
+        }
+    }
+}
+
+/// Why a saved or imported snapshot could not be read
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotError {
+    /// Written by a newer build in a schema this one cannot read
+    NewerVersion(u64),
+    Invalid(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::NewerVersion(version) => write!(
+                f,
+                "overlay data is schema version {}, newer than this site reads ({})",
+                version, SCHEMA_VERSION
+            ),
+            SnapshotError::Invalid(reason) => write!(f, "invalid overlay data: {}", reason),
+        }
+    }
+}
+
+/// Where the serialized overlay is kept between sessions
+pub trait OverlayStorage: fmt::Debug {
+    /// The saved overlay, if any
+    fn load(&self) -> Result<Option<String>, StorageError>;
+    fn save(&self, data: &str) -> Result<(), StorageError>;
+    fn clear(&self) -> Result<(), StorageError>;
+}
+
+/// The browser's localStorage
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    key: String,
+}
+
+impl LocalStorage {
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+
+    fn storage() -> Result<web_sys::Storage, StorageError> {
+        let window =
+            web_sys::window().ok_or_else(|| StorageError::Unavailable("no window".to_string()))?;
+        match window.local_storage() {
+            Ok(Some(storage)) => Ok(storage),
+            Ok(None) => Err(StorageError::Unavailable("no localStorage".to_string())),
+            Err(error) => Err(StorageError::Unavailable(describe_js_error(&error))),
+        }
+    }
+}
+
+impl OverlayStorage for LocalStorage {
+    fn load(&self) -> Result<Option<String>, StorageError> {
+        Self::storage()?
+            .get_item(&self.key)
+            .map_err(|error| StorageError::Failed(describe_js_error(&error)))
+    }
+
+    fn save(&self, data: &str) -> Result<(), StorageError> {
+        Self::storage()?.set_item(&self.key, data).map_err(|error| {
+            match js_error_name(&error).as_deref() {
+                // Firefox used its own name before standardising
+                Some("QuotaExceededError" | "NS_ERROR_DOM_QUOTA_REACHED") => {
+                    StorageError::QuotaExceeded
+                }
+                _ => StorageError::Failed(describe_js_error(&error)),
+            }
+        })
+    }
+
+    fn clear(&self) -> Result<(), StorageError> {
+        Self::storage()?
+            .remove_item(&self.key)
+            .map_err(|error| StorageError::Failed(describe_js_error(&error)))
+    }
+}
+
+fn js_error_name(error: &wasm_bindgen::JsValue) -> Option<String> {
+    js_sys::Reflect::get(error, &"name".into())
+        .ok()?
+        .as_string()
+}
+
+fn describe_js_error(error: &wasm_bindgen::JsValue) -> String {
+    js_sys::Reflect::get(error, &"message".into())
+        .ok()
+        .and_then(|message| message.as_string())
+        .or_else(|| error.as_string())
+        .unwrap_or_else(|| "unknown error".to_string())
+}
+
+/// In-memory storage with an optional size limit, standing in for the browser in tests
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    data: std::cell::RefCell<Option<String>>,
+    quota: Option<usize>,
+}
+
+#[cfg(test)]
+impl MemoryStorage {
+    pub fn with_quota(bytes: usize) -> Self {
+        Self {
+            quota: Some(bytes),
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+impl OverlayStorage for MemoryStorage {
+    fn load(&self) -> Result<Option<String>, StorageError> {
+        Ok(self.data.borrow().clone())
+    }
+
+    fn save(&self, data: &str) -> Result<(), StorageError> {
+        if self.quota.is_some_and(|quota| data.len() > quota) {
+            return Err(StorageError::QuotaExceeded);
+        }
+        *self.data.borrow_mut() = Some(data.to_string());
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), StorageError> {
+        *self.data.borrow_mut() = None;
+        Ok(())
+    }
+}
+
+/// The overlay as changes to the shipped tree
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OverlaySnapshot {
+    pub version: u32,
+    /// Overlay entries without their children, parents before children
+    pub entries: Vec<FileSystemNode>,
+    /// Paths of shipped entries that were removed or moved away
+    pub removed: Vec<String>,
+}
+
+impl OverlaySnapshot {
+    /// Changes that turn `base` into `root`
+    pub fn diff(base: &FileSystemNode, root: &FileSystemNode) -> Self {
+        let mut snapshot = Self {
+            version: SCHEMA_VERSION,
+            ..Self::default()
+        };
+        collect_entries(root, &mut snapshot.entries);
+        collect_removed(base, root, &mut snapshot.removed);
+        snapshot
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.removed.is_empty()
+    }
+
+    /// `base` with these changes applied
+    ///
+    /// Changes that no longer fit, e.g. entries whose directory was removed
+    /// from the site since they were saved, are skipped.
+    pub fn apply(&self, base: &FileSystemNode) -> FileSystemNode {
+        let mut root = base.clone();
+        for path in &self.removed {
+            let path = resolve_path(&[], path);
+            if let Some((name, parent_path)) = path.split_last() {
+                if let Some(parent) = get_node_at_path_mut(&mut root, parent_path) {
+                    parent.children.remove(name);
+                }
+            }
+        }
+
+        let mut entries: Vec<&FileSystemNode> = self.entries.iter().collect();
+        entries.sort_by_key(|entry| entry.path.matches('/').count());
+        for entry in entries {
+            let path = resolve_path(&[], &entry.path);
+            let Some((name, parent_path)) = path.split_last() else {
+                continue;
+            };
+            let Some(parent) = get_node_at_path_mut(&mut root, parent_path)
+                .filter(|parent| parent.node_type == "directory")
+            else {
+                continue;
+            };
+            let mut node = entry.clone();
+            if let Some(existing) = parent.children.get_mut(name) {
+                if existing.node_type == "directory" && node.node_type == "directory" {
+                    node.children = std::mem::take(&mut existing.children);
+                }
+            }
+            parent.children.insert(name.clone(), node);
+        }
+        root
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Parse a snapshot, checking it was written in a schema this build reads
+    pub fn from_json(json: &str) -> Result<Self, SnapshotError> {
+        let value: serde_json::Value = serde_json::from_str(json)
+            .map_err(|error| SnapshotError::Invalid(error.to_string()))?;
+        match value.get("version").and_then(|version| version.as_u64()) {
+            None => return Err(SnapshotError::Invalid("missing version".to_string())),
+            Some(version) if version > SCHEMA_VERSION as u64 => {
+                return Err(SnapshotError::NewerVersion(version))
+            }
+            Some(_) => {}
+        }
+        serde_json::from_value(value).map_err(|error| SnapshotError::Invalid(error.to_string()))
+    }
+}
+
+/// Overlay entries under `node`, in name order
+fn collect_entries(node: &FileSystemNode, entries: &mut Vec<FileSystemNode>) {
+    let mut children: Vec<&FileSystemNode> = node.children.values().collect();
+    children.sort_by(|a, b| a.name.cmp(&b.name));
+    for child in children {
+        if child.overlay {
+            let mut entry = child.clone();
+            entry.children.clear();
+            entries.push(entry);
+        }
+        collect_entries(child, entries);
+    }
+}
+
+/// Paths of shipped entries missing from `root`; only the topmost of a removed subtree
+fn collect_removed(base: &FileSystemNode, root: &FileSystemNode, removed: &mut Vec<String>) {
+    let mut children: Vec<&FileSystemNode> = base.children.values().collect();
+    children.sort_by(|a, b| a.name.cmp(&b.name));
+    for base_child in children {
+        match root.children.get(&base_child.name) {
+            None => removed.push(base_child.path.clone()),
+            Some(root_child)
+                if base_child.node_type == "directory" && root_child.node_type == "directory" =>
+            {
+                collect_removed(base_child, root_child, removed)
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filesystem::navigation::get_node_at_path;
+    use crate::filesystem::overlay;
+
+    const NOW: &str = "2026-01-01T00:00:00.000Z";
+
+    fn path(path: &str) -> Vec<String> {
+        resolve_path(&[], path)
+    }
+
+    fn base() -> FileSystemNode {
+        let json = r#"{"type":"directory","name":"","path":"","children":{
+            "index.md":{"type":"file","name":"index.md","path":"index.md","size":5},
+            "posts":{"type":"directory","name":"posts","path":"posts","children":{
+                "a.md":{"type":"file","name":"a.md","path":"posts/a.md","size":3},
+                "b.md":{"type":"file","name":"b.md","path":"posts/b.md","size":3}}}}}"#;
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_through_storage() {
+        let base = base();
+        let mut root = base.clone();
+        overlay::write_file(&mut root, &path("notes.md"), "notes.md", "hi\n".into(), NOW).unwrap();
+        overlay::make_directory(&mut root, &path("drafts/old"), "drafts/old", true, NOW).unwrap();
+        overlay::rename(
+            &mut root,
+            &path("posts/a.md"),
+            &path("drafts"),
+            ("a", "drafts"),
+        )
+        .unwrap();
+        overlay::remove(&mut root, &path("index.md"), "index.md", false).unwrap();
+
+        let snapshot = OverlaySnapshot::diff(&base, &root);
+        assert_eq!(snapshot.removed, ["index.md", "posts/a.md"]);
+        let paths: Vec<&str> = snapshot.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["drafts", "drafts/a.md", "drafts/old", "notes.md"]);
+
+        let storage = MemoryStorage::default();
+        storage.save(&snapshot.to_json()).unwrap();
+        let loaded = OverlaySnapshot::from_json(&storage.load().unwrap().unwrap()).unwrap();
+        assert_eq!(loaded, snapshot);
+        let restored = loaded.apply(&base);
+        assert_eq!(OverlaySnapshot::diff(&base, &restored), snapshot);
+        assert_eq!(
+            get_node_at_path(&restored, &path("drafts/a.md"))
+                .unwrap()
+                .source
+                .as_deref(),
+            Some("posts/a.md")
+        );
+        assert!(get_node_at_path(&restored, &path("posts/b.md")).is_some());
+
+        assert_eq!(
+            MemoryStorage::with_quota(10).save(&snapshot.to_json()),
+            Err(StorageError::QuotaExceeded)
+        );
+    }
+
+    #[test]
+    fn test_schema_version() {
+        assert!(OverlaySnapshot::from_json(r#"{"version":1,"entries":[],"removed":[]}"#).is_ok());
+        assert_eq!(
+            OverlaySnapshot::from_json(r#"{"version":2,"entries":[],"removed":[]}"#),
+            Err(SnapshotError::NewerVersion(2))
+        );
+        assert!(OverlaySnapshot::from_json(r#"{"entries":[]}"#).is_err());
+        assert!(OverlaySnapshot::from_json("not json").is_err());
+    }
+}
@@ -1,10 +1,11 @@
 // Filesystem Types
 // Core data structures for the virtual filesystem
 
+use crate::utils::{fetch_file_content, fetch_file_content_cached};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct FileSystemNode {
     #[serde(rename = "type")]
     pub node_type: String,
@@ -25,4 +26,51 @@ pub struct FileSystemNode {
     pub description: Option<String>,
     #[serde(default)]
     pub tags: Option<Vec<String>>,
+    // Overlay fields, for entries written in this session
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub overlay: bool,
+    /// Content of a file written in the overlay
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Data path of the shipped file a copied or moved file was made from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// Where a file's content is read from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentSource {
+    /// Written in the overlay and held in memory
+    Memory(String),
+    /// Shipped in the data directory at this path
+    Data(String),
+}
+
+impl FileSystemNode {
+    /// Where to read this file's content from
+    pub fn content_source(&self) -> ContentSource {
+        match (&self.content, &self.source) {
+            (Some(content), _) => ContentSource::Memory(content.clone()),
+            (None, Some(source)) => ContentSource::Data(source.clone()),
+            (None, None) => ContentSource::Data(self.path.clone()),
+        }
+    }
+}
+
+impl ContentSource {
+    /// Read the content, fetching shipped files
+    pub async fn read(&self) -> Result<String, String> {
+        match self {
+            ContentSource::Memory(content) => Ok(content.clone()),
+            ContentSource::Data(path) => fetch_file_content(path).await,
+        }
+    }
+
+    /// Read the content, reusing earlier fetches of shipped files
+    pub async fn read_cached(&self) -> Result<String, String> {
+        match self {
+            ContentSource::Memory(content) => Ok(content.clone()),
+            ContentSource::Data(path) => fetch_file_content_cached(path).await,
+        }
+    }
 }
\ No newline at end of file
@@ -1,9 +1,11 @@
 mod use_app_config;
 mod use_auto_focus;
 mod use_auto_navigation;
+mod use_command_queue;
 mod use_trailing_effect;
 
 pub use use_app_config::use_app_config;
 pub use use_auto_focus::use_auto_focus;
 pub use use_auto_navigation::use_auto_navigation;
+pub use use_command_queue::use_command_queue;
 pub use use_trailing_effect::use_trailing_effect;
\ No newline at end of file
@@ -3,6 +3,7 @@ use crate::components::history::{
     create_command_entry, create_html_entry, create_welcome_entry, HistoryEntry,
 };
 use crate::utils::config::{get_base_url, start_with_slash};
+use crate::utils::prompt::{current_prompt, PromptSegment};
 use crate::utils::AppConfigService;
 use yew::prelude::*;
 
@@ -66,6 +67,8 @@ fn execute_auto_command(
         app_config: AppConfigService::new(),
         command_executor: &executor,
         set_theme: None,
+        set_code_theme: None,
+        stdin: None,
         execute: std::rc::Rc::new(move |command_str: &str| {
             let minimal_context = TerminalContext {
                 app_config: AppConfigService::new(),
@@ -75,12 +78,15 @@ fn execute_auto_command(
                     CommandResult::Error("Nested execute not supported".to_string())
                 }),
                 set_theme: None,
+                set_code_theme: None,
+                stdin: None,
             };
             executor_clone_for_execute.execute(command_str, &minimal_context)
         }),
     };
 
     // Execute the navigate command
+    let prompt = current_prompt(&executor, &context.app_config.config);
     let result = executor.execute(command, &context);
 
     // Handle the result and add to history
@@ -88,6 +94,7 @@ fn execute_auto_command(
         result,
         command.to_string(),
         navigate_command_for_async,
+        prompt,
         &history,
     );
 }
@@ -96,13 +103,14 @@ fn handle_auto_navigation_result(
     result: CommandResult,
     command: String,
     navigate_command_for_async: String,
+    prompt: Vec<PromptSegment>,
     history: &UseStateHandle<Vec<HistoryEntry>>,
 ) {
     match result {
         CommandResult::Success(output) => {
             if !output.is_empty() {
                 let mut current_history = (**history).clone();
-                current_history.push(create_command_entry(command, output, false));
+                current_history.push(create_command_entry(command, output, false, prompt));
                 history.set(current_history);
             }
         }
@@ -112,12 +120,13 @@ fn handle_auto_navigation_result(
                 command,
                 format!("Error: {}", error),
                 true,
+                prompt,
             ));
             history.set(current_history);
         }
         CommandResult::Html(html_content) => {
             let mut current_history = (**history).clone();
-            current_history.push(create_html_entry(command, html_content));
+            current_history.push(create_html_entry(command, html_content, prompt));
             history.set(current_history);
         }
         CommandResult::Async(future) => {
@@ -127,6 +136,7 @@ fn handle_auto_navigation_result(
                 command.clone(),
                 "Loading...".to_string(),
                 false,
+                prompt.clone(),
             ));
             let loading_index = current_history.len() - 1;
             history.set(current_history);
@@ -155,6 +165,7 @@ fn handle_auto_navigation_result(
                             navigate_command_for_async.clone(),
                             output,
                             false,
+                            prompt,
                         ));
                     }
                     CommandResult::Error(error) => {
@@ -162,17 +173,19 @@ fn handle_auto_navigation_result(
                             navigate_command_for_async.clone(),
                             format!("Error: {}", error),
                             true,
+                            prompt,
                         ));
                     }
                     CommandResult::Html(html_content) => {
                         current_history
-                            .push(create_html_entry(navigate_command_for_async, html_content));
+                            .push(create_html_entry(navigate_command_for_async, html_content, prompt));
                     }
                     CommandResult::Async(_) => {
                         current_history.push(create_command_entry(
                             navigate_command_for_async,
                             "Error: Nested async operations not supported".to_string(),
                             true,
+                            prompt,
                         ));
                     }
                 }
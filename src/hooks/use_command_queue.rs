@@ -0,0 +1,42 @@
+use crate::commands::CommandExecutor;
+use crate::components::history::HistoryEntry;
+use crate::components::terminal::handlers::command_handlers::run_command;
+use crate::utils::AppConfigService;
+use yew::prelude::*;
+
+/// Hook for running commands from outside the prompt (e.g. code block run buttons)
+///
+/// Commands are run one per render, so each one sees the history produced by
+/// the previous one. Returns a callback that replaces the queue.
+#[hook]
+pub fn use_command_queue(
+    history: UseStateHandle<Vec<HistoryEntry>>,
+    command_history: UseStateHandle<Vec<String>>,
+    history_index: UseStateHandle<Option<usize>>,
+    executor: UseStateHandle<CommandExecutor>,
+    container_ref: NodeRef,
+    app_config: UseStateHandle<AppConfigService>,
+) -> Callback<Vec<String>> {
+    let queue = use_state(Vec::<String>::new);
+
+    {
+        let queue = queue.clone();
+        use_effect_with((*queue).clone(), move |pending| {
+            if let Some((command, rest)) = pending.split_first() {
+                run_command(
+                    command,
+                    &history,
+                    &command_history,
+                    &history_index,
+                    &executor,
+                    &container_ref,
+                    &app_config,
+                );
+                queue.set(rest.to_vec());
+            }
+            || {}
+        });
+    }
+
+    Callback::from(move |commands: Vec<String>| queue.set(commands))
+}
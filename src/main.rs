@@ -6,6 +6,8 @@ mod commands;
 mod components;
 mod filesystem;
 mod hooks;
+#[cfg(test)]
+mod test_fixtures;
 mod utils;
 
 use app::App;
@@ -0,0 +1,101 @@
+// Test Fixtures
+// Helpers shared by the unit tests of several modules
+
+use crate::commands::{CommandExecutor, CommandResult, TerminalContext};
+use crate::filesystem::persistence::{OverlaySnapshot, SCHEMA_VERSION};
+use crate::filesystem::types::FileSystemNode;
+use crate::utils::AppConfigService;
+use std::future::Future;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+/// Split a command line on whitespace into arguments
+pub fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+}
+
+/// A node at `path` holding `children`, named after its last component
+///
+/// Files get their extension from the name; everything else is unset.
+pub fn node(path: &str, node_type: &str, children: Vec<FileSystemNode>) -> FileSystemNode {
+    let name = path.rsplit('/').next().unwrap_or(path).to_string();
+    FileSystemNode {
+        node_type: node_type.to_string(),
+        extension: name
+            .rsplit_once('.')
+            .filter(|_| node_type == "file")
+            .map(|(_, extension)| extension.to_string()),
+        name,
+        path: path.to_string(),
+        children: children
+            .into_iter()
+            .map(|child| (child.name.clone(), child))
+            .collect(),
+        size: None,
+        modified: None,
+        title: None,
+        description: None,
+        tags: None,
+        overlay: false,
+        content: None,
+        source: None,
+    }
+}
+
+/// Replace the overlay with files at the given paths holding the given text
+///
+/// Later changes are dated with a fixed time, as there is no browser clock.
+pub fn overlay_files(executor: &CommandExecutor, files: &[(&str, &str)]) {
+    let entries = files
+        .iter()
+        .map(|(path, content)| FileSystemNode {
+            overlay: true,
+            content: Some(content.to_string()),
+            ..node(path, "file", vec![])
+        })
+        .collect();
+    let mut filesystem = executor.filesystem.borrow_mut();
+    filesystem.set_clock(|| "2024-01-01T00:00:00.000Z".to_string());
+    filesystem
+        .restore(&OverlaySnapshot {
+            version: SCHEMA_VERSION,
+            entries,
+            removed: vec![],
+        })
+        .unwrap();
+}
+
+/// A context for running commands, with the site's config and no browser
+pub fn context(executor: &CommandExecutor) -> TerminalContext<'_> {
+    TerminalContext {
+        clear_screen: Rc::new(|| {}),
+        command_executor: executor,
+        execute: Rc::new(|_| CommandResult::Error("not available in tests".to_string())),
+        app_config: AppConfigService {
+            config: serde_json::from_str(include_str!("../app.json")).unwrap(),
+            current_theme: "default".to_string(),
+            code_theme: None,
+        },
+        set_theme: None,
+        set_code_theme: None,
+        stdin: None,
+    }
+}
+
+/// Run a command line to completion
+pub fn run(context: &TerminalContext, line: &str) -> CommandResult {
+    match context.command_executor.execute(line, context) {
+        CommandResult::Async(future) => block_on(future),
+        result => result,
+    }
+}
+
+/// Drive a future whose dependencies all resolve immediately
+pub fn block_on<T>(future: impl Future<Output = T>) -> T {
+    let mut future = Box::pin(future);
+    let mut context = Context::from_waker(Waker::noop());
+    match future.as_mut().poll(&mut context) {
+        Poll::Ready(value) => value,
+        Poll::Pending => panic!("future did not resolve"),
+    }
+}
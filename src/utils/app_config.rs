@@ -1,4 +1,7 @@
+use crate::utils::syntax_highlighter::{SyntaxHighlighter, DEFAULT_CODE_THEME};
+use crate::utils::theme::{available_themes, find_theme, Theme};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use web_sys::window;
 
 // Include the app.json file content at compile time
@@ -14,6 +17,9 @@ pub struct Author {
 pub struct Prompt {
     pub symbol: String,
     pub color: String,
+    /// PS1-style template, e.g. "{user}@zzhack {cwd} {symbol}"
+    #[serde(default)]
+    pub format: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -27,23 +33,64 @@ pub struct Layout {
     pub align: String,
 }
 
+/// Markdown extensions beyond CommonMark, all enabled by default
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MarkdownConfig {
+    /// `> [!NOTE]` style callouts
+    pub admonitions: bool,
+    /// `:rocket:` style shortcodes
+    pub emoji: bool,
+    /// `# Heading {#id .class}`
+    pub heading_attributes: bool,
+    /// `Term` lines followed by `: Definition` lines
+    pub definition_lists: bool,
+    /// Insert raw HTML from markdown as written instead of sanitizing it
+    pub trust_raw_html: bool,
+}
+
+impl Default for MarkdownConfig {
+    fn default() -> Self {
+        Self {
+            admonitions: true,
+            emoji: true,
+            heading_attributes: true,
+            definition_lists: true,
+            trust_raw_html: false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AppConfig {
     pub author: Author,
     pub terminal: Terminal,
     pub layout: Layout,
+    /// Default theme name, or "system" to follow prefers-color-scheme
     pub theme: String,
+    /// Additional themes, keyed by name (override data/themes/*.json)
+    #[serde(default)]
+    pub themes: BTreeMap<String, Theme>,
+    /// Markdown extensions used by `view`
+    #[serde(default)]
+    pub markdown: MarkdownConfig,
 }
 
 #[derive(Debug, Clone)]
 pub struct AppConfigService {
     pub config: AppConfig,
+    /// Selected theme name, or "system" to follow the browser preference
     pub current_theme: String,
+    /// Code highlighting theme chosen with `theme code`, overriding the
+    /// terminal theme's own syntax theme
+    pub code_theme: Option<String>,
 }
 
 impl PartialEq for AppConfigService {
     fn eq(&self, other: &Self) -> bool {
-        self.config == other.config && self.current_theme == other.current_theme
+        self.config == other.config
+            && self.current_theme == other.current_theme
+            && self.code_theme == other.code_theme
     }
 }
 
@@ -54,11 +101,12 @@ impl AppConfigService {
 
         // Check localStorage first, then fall back to config
         let current_theme =
-            Self::get_saved_theme().unwrap_or_else(|| Self::resolve_theme(&config.theme));
+            Self::get_saved_theme(&config).unwrap_or_else(|| config.theme.clone());
 
         Self {
             config,
             current_theme,
+            code_theme: Self::get_saved_item("terminal-code-theme"),
         }
     }
 
@@ -67,84 +115,159 @@ impl AppConfigService {
         serde_json::from_str::<AppConfig>(CONFIG_JSON).unwrap()
     }
 
-    fn get_saved_theme() -> Option<String> {
-        if let Some(window) = window() {
-            if let Ok(Some(storage)) = window.local_storage() {
-                if let Ok(Some(theme)) = storage.get_item("terminal-theme") {
-                    if matches!(theme.as_str(), "light" | "dark") {
-                        return Some(theme);
-                    }
-                }
-            }
-        }
-        None
+    fn get_saved_theme(config: &AppConfig) -> Option<String> {
+        Self::get_saved_item("terminal-theme")
+            .filter(|theme| theme == "system" || find_theme(&config.themes, theme).is_some())
+    }
+
+    fn get_saved_item(key: &str) -> Option<String> {
+        window()?.local_storage().ok()??.get_item(key).ok()?
     }
 
-    fn save_theme(theme: &str) {
+    fn save_item(key: &str, value: Option<&str>) {
         if let Some(window) = window() {
             if let Ok(Some(storage)) = window.local_storage() {
-                let _ = storage.set_item("terminal-theme", theme);
+                let _ = match value {
+                    Some(value) => storage.set_item(key, value),
+                    None => storage.remove_item(key),
+                };
             }
         }
     }
 
-    fn resolve_theme(theme: &str) -> String {
+    fn resolve_theme(&self, theme: &str) -> String {
         match theme {
             "system" => {
-                // Check if browser supports dark mode preference
+                // Follow the browser's color scheme preference
                 if Self::prefers_dark_mode() {
                     "dark".to_string()
                 } else {
                     "light".to_string()
                 }
             }
-            "light" | "dark" => theme.to_string(),
+            name if self.get_theme(name).is_some() => name.to_string(),
             _ => "dark".to_string(), // Default to dark
         }
     }
 
     fn prefers_dark_mode() -> bool {
-        // For simplicity, always return true (dark mode preference)
-        // In a real implementation, you would use window.matchMedia
-        true
+        window()
+            .and_then(|window| window.match_media("(prefers-color-scheme: dark)").ok())
+            .flatten()
+            .map(|query| query.matches())
+            .unwrap_or(true)
     }
 
-    pub fn get_current_theme(&self) -> &str {
-        &self.current_theme
+    /// All themes that can be switched to, keyed by name
+    pub fn available_themes(&self) -> BTreeMap<String, Theme> {
+        available_themes(&self.config.themes)
+    }
+
+    /// Look up a theme palette by name
+    pub fn get_theme(&self, name: &str) -> Option<Theme> {
+        find_theme(&self.config.themes, name).cloned()
+    }
+
+    /// Name of the active theme, with "system" resolved to light or dark
+    pub fn get_current_theme(&self) -> String {
+        self.resolve_theme(&self.current_theme)
+    }
+
+    /// Palette of the active theme
+    pub fn get_current_palette(&self) -> Option<Theme> {
+        self.get_theme(&self.get_current_theme())
+    }
+
+    /// Whether the active theme follows prefers-color-scheme
+    pub fn is_following_system(&self) -> bool {
+        self.current_theme == "system"
     }
 
     pub fn set_theme(&mut self, theme: &str) -> bool {
-        if matches!(theme, "light" | "dark") {
+        if theme == "system" || self.get_theme(theme).is_some() {
             self.current_theme = theme.to_string();
-            Self::save_theme(theme);
-            Self::apply_theme_to_dom(theme);
+            Self::save_item("terminal-theme", Some(theme));
+            self.apply_theme_to_dom();
             true
         } else {
             false
         }
     }
 
-    fn apply_theme_to_dom(theme: &str) {
+    /// Name of the syntect theme used for code blocks
+    pub fn get_code_theme(&self) -> String {
+        self.code_theme
+            .clone()
+            .or_else(|| self.get_current_palette().map(|palette| palette.syntax_theme))
+            .unwrap_or_else(|| DEFAULT_CODE_THEME.to_string())
+    }
+
+    /// Override the code theme, or pass "auto" to follow the terminal theme
+    pub fn set_code_theme(&mut self, theme: &str) -> bool {
+        let code_theme = match theme {
+            "auto" => None,
+            name if SyntaxHighlighter::shared().has_theme(name) => Some(name.to_string()),
+            _ => return false,
+        };
+
+        Self::save_item("terminal-code-theme", code_theme.as_deref());
+        self.code_theme = code_theme;
+        self.apply_theme_to_dom();
+        true
+    }
+
+    /// Apply the active theme's class and CSS variables to the body element,
+    /// and the code theme's stylesheet to the document head
+    pub fn apply_theme_to_dom(&self) {
+        let theme_class = self.get_theme_class();
+        let css_variables = self
+            .get_current_palette()
+            .map(|palette| palette.css_variables())
+            .unwrap_or_default();
+
         if let Some(window) = window() {
             if let Some(document) = window.document() {
                 if let Some(body) = document.body() {
-                    // Remove existing theme classes
-                    let _ = body.set_class_name("");
-
-                    // Add new theme class
-                    let theme_class = match theme {
-                        "light" => "theme-light",
-                        _ => "theme-dark",
-                    };
-                    let _ = body.set_class_name(theme_class);
+                    body.set_class_name(theme_class);
+                    let _ = body.set_attribute("style", &css_variables);
                 }
             }
         }
+
+        self.apply_code_theme_to_dom();
+    }
+
+    /// Replace the `<style id="syntax-theme">` element with the code theme's CSS
+    fn apply_code_theme_to_dom(&self) {
+        let highlighter = SyntaxHighlighter::shared();
+        let css = highlighter
+            .theme_css(&self.get_code_theme())
+            .or_else(|| highlighter.theme_css(DEFAULT_CODE_THEME))
+            .unwrap_or_default();
+
+        let Some(document) = window().and_then(|window| window.document()) else {
+            return;
+        };
+
+        let style = match document.get_element_by_id("syntax-theme") {
+            Some(style) => style,
+            None => {
+                let Ok(style) = document.create_element("style") else {
+                    return;
+                };
+                style.set_id("syntax-theme");
+                if let Some(head) = document.head() {
+                    let _ = head.append_child(&style);
+                }
+                style
+            }
+        };
+        style.set_text_content(Some(&css));
     }
 
-    pub fn get_theme_class(&self) -> &str {
-        match self.current_theme.as_str() {
-            "light" => "theme-light",
+    pub fn get_theme_class(&self) -> &'static str {
+        match self.get_current_palette() {
+            Some(palette) if !palette.dark => "theme-light",
             _ => "theme-dark",
         }
     }
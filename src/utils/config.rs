@@ -72,3 +72,21 @@ pub fn build_url(path: &str) -> String {
 pub fn build_data_url(file_path: &str) -> String {
     build_url(&format!("/data/{}", file_path))
 }
+
+/// Build an origin-relative path for a file in the data directory
+///
+/// Honors TRUNK_PUBLIC_URL like `build_data_url`, but needs no `window`, so
+/// it can be used for `src`/`href` attributes while rendering markdown.
+///
+/// # Examples
+/// ```
+/// let path = build_data_path("posts/img/arch.png");
+/// // Returns "/data/posts/img/arch.png" (assuming public_url = "/")
+/// ```
+pub fn build_data_path(file_path: &str) -> String {
+    format!(
+        "{}/data/{}",
+        get_base_path().trim_end_matches('/'),
+        file_path.trim_start_matches('/')
+    )
+}
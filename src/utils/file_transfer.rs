@@ -0,0 +1,81 @@
+// File Transfer
+// Download generated files and read files chosen by the user
+
+use futures::channel::oneshot;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+/// Offer `bytes` to the user as a download named `name`
+pub fn download_file(name: &str, bytes: &[u8], mime: &str) -> Result<(), String> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("no document")?;
+
+    let parts = js_sys::Array::new();
+    parts.push(&js_sys::Uint8Array::from(bytes));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+        .map_err(|_| "could not create file")?;
+    let url =
+        web_sys::Url::create_object_url_with_blob(&blob).map_err(|_| "could not create file")?;
+
+    let anchor: web_sys::HtmlAnchorElement = document
+        .create_element("a")
+        .map_err(|_| "could not start download")?
+        .unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+    let _ = web_sys::Url::revoke_object_url(&url);
+    Ok(())
+}
+
+/// Let the user choose a file, returning its name and bytes
+///
+/// Resolves with an error if the picker is cancelled.
+pub async fn pick_file() -> Result<(String, Vec<u8>), String> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("no document")?;
+    let input: web_sys::HtmlInputElement = document
+        .create_element("input")
+        .map_err(|_| "could not open file picker")?
+        .unchecked_into();
+    input.set_type("file");
+
+    // Either event settles the picker; whichever fires first wins
+    let (sender, receiver) = oneshot::channel::<bool>();
+    let sender = Rc::new(RefCell::new(Some(sender)));
+    let settle = |chosen: bool| {
+        let sender = sender.clone();
+        Closure::<dyn FnMut()>::new(move || {
+            if let Some(sender) = sender.borrow_mut().take() {
+                let _ = sender.send(chosen);
+            }
+        })
+    };
+    let on_change = settle(true);
+    let on_cancel = settle(false);
+    input
+        .add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref())
+        .map_err(|_| "could not open file picker")?;
+    input
+        .add_event_listener_with_callback("cancel", on_cancel.as_ref().unchecked_ref())
+        .map_err(|_| "could not open file picker")?;
+    input.click();
+
+    let chosen = receiver.await.unwrap_or(false);
+    let file = input
+        .files()
+        .and_then(|files| files.get(0))
+        .filter(|_| chosen)
+        .ok_or("no file chosen")?;
+    let buffer = JsFuture::from(file.array_buffer())
+        .await
+        .map_err(|_| format!("could not read {}", file.name()))?;
+    Ok((file.name(), js_sys::Uint8Array::new(&buffer).to_vec()))
+}
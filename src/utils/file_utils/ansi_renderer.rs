@@ -0,0 +1,630 @@
+// ANSI Markdown Renderer
+// Renders markdown as plain text with ANSI escapes, for `cat --pretty` and other text output
+
+use super::code_fence::CodeFence;
+use super::extensions::{apply_extensions, parse_alert_marker};
+use super::frontmatter::strip_frontmatter;
+use super::markdown_processor::markdown_options;
+use super::toc::heading_level;
+use crate::utils::app_config::MarkdownConfig;
+use crate::utils::syntax_highlighter::SyntaxHighlighter;
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, HeadingLevel, Parser, Tag};
+
+const RESET: &str = "\x1b[0m";
+/// Style of quote bars, rules, link targets and other decoration
+const DIM: &str = "90";
+
+/// Render markdown as text styled with ANSI escapes
+///
+/// The output goes through the same `AnsiSegment` pipeline as other command
+/// output, so it can be piped and searched like plain text. Source line
+/// breaks are kept, tables are drawn with box characters and code blocks are
+/// highlighted with the 16 terminal colors.
+pub fn render_markdown_to_ansi(markdown_input: &str, config: &MarkdownConfig) -> String {
+    let content = strip_frontmatter(markdown_input);
+    // Admonitions and definition lists produce HTML, so they are handled here instead
+    let text_config = MarkdownConfig {
+        admonitions: false,
+        definition_lists: false,
+        ..config.clone()
+    };
+    let events = apply_extensions(
+        Parser::new_ext(&content, markdown_options(config)).collect(),
+        &text_config,
+    );
+
+    let mut renderer = AnsiRenderer {
+        admonitions: config.admonitions,
+        ..AnsiRenderer::default()
+    };
+    renderer.render(&events);
+    renderer.finish()
+}
+
+/// Terminal columns taken by a string, ignoring ANSI escapes
+///
+/// CJK and emoji characters count as two columns, combining marks and
+/// zero-width joiners as none.
+pub fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    for c in text.chars() {
+        match c {
+            '\x1b' => in_escape = true,
+            c if in_escape => in_escape = !c.is_ascii_alphabetic(),
+            c => width += char_width(c),
+        }
+    }
+    width
+}
+
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x0300..=0x036F | 0x200B..=0x200F | 0xFE00..=0xFE0F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1FAFF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// Something that indents the lines inside it
+enum Indent {
+    /// Blockquote bar
+    Quote,
+    /// List item; the marker is printed on its first line only
+    Item {
+        width: usize,
+        marker: Option<String>,
+    },
+}
+
+/// Table being collected; it is drawn once all cells are known
+#[derive(Default)]
+struct Table {
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<String>>,
+    /// Number of header rows at the top of `rows`
+    header_rows: usize,
+}
+
+#[derive(Default)]
+struct AnsiRenderer {
+    admonitions: bool,
+    output: String,
+    /// Current line, without its indentation
+    line: String,
+    line_has_text: bool,
+    /// Insert a blank line before the next block
+    needs_blank: bool,
+    indents: Vec<Indent>,
+    /// Active SGR codes, innermost last
+    styles: Vec<&'static str>,
+    /// Next number of each open list, None for bullet lists
+    lists: Vec<Option<u64>>,
+    /// Destination and text of each open link
+    links: Vec<(String, String)>,
+    table: Option<Table>,
+    code_block: Option<(CodeFence, String)>,
+    /// Alt text of the image being rendered
+    image: Option<String>,
+}
+
+impl AnsiRenderer {
+    fn render(&mut self, events: &[Event]) {
+        let mut index = 0;
+        while index < events.len() {
+            // `> [!NOTE]`: replace the marker with a colored title line
+            if let (true, Event::Start(Tag::BlockQuote)) = (self.admonitions, &events[index]) {
+                if let (Some(Event::Start(Tag::Paragraph)), Some(Event::Text(text))) =
+                    (events.get(index + 1), events.get(index + 2))
+                {
+                    if let Some(((title, class), rest)) = parse_alert_marker(text) {
+                        self.start_block();
+                        self.indents.push(Indent::Quote);
+                        self.push_styled(admonition_style(class), title);
+                        self.flush_line();
+                        let rest = rest.trim_start();
+                        index += 3;
+                        if !rest.is_empty() {
+                            self.push_text(rest);
+                        } else if matches!(
+                            events.get(index),
+                            Some(Event::SoftBreak | Event::HardBreak)
+                        ) {
+                            index += 1;
+                        }
+                        continue;
+                    }
+                }
+            }
+            self.event(&events[index]);
+            index += 1;
+        }
+    }
+
+    fn event(&mut self, event: &Event) {
+        if let Some((_, code)) = self.code_block.as_mut() {
+            match event {
+                Event::Text(text) => code.push_str(text),
+                Event::End(Tag::CodeBlock(_)) => self.end_code_block(),
+                _ => {}
+            }
+            return;
+        }
+        if let Some(alt) = self.image.as_mut() {
+            match event {
+                Event::Text(text) | Event::Code(text) => alt.push_str(text),
+                Event::End(Tag::Image(..)) => {
+                    let alt = self.image.take().unwrap_or_default();
+                    self.push_styled(DIM, &format!("[image: {}]", alt));
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.push_text(text),
+            Event::Code(code) => self.push_styled("33", code),
+            Event::SoftBreak | Event::HardBreak => self.flush_line(),
+            Event::Rule => {
+                self.start_block();
+                self.push_styled(DIM, &"─".repeat(40));
+                self.end_block();
+            }
+            Event::TaskListMarker(checked) => {
+                if *checked {
+                    self.push_styled("32", "☑");
+                } else {
+                    self.push_styled(DIM, "☐");
+                }
+                self.push_text(" ");
+            }
+            Event::FootnoteReference(name) => self.push_styled(DIM, &format!("[^{}]", name)),
+            // Raw HTML has no text form
+            Event::Html(_) => {}
+        }
+    }
+
+    fn start(&mut self, tag: &Tag) {
+        match tag {
+            Tag::Paragraph => self.start_block(),
+            Tag::Heading(level, ..) => {
+                self.start_block();
+                self.styles.push(heading_style(*level));
+                self.restyle();
+                self.push_text(&format!("{} ", "#".repeat(heading_level(*level))));
+            }
+            Tag::BlockQuote => {
+                self.start_block();
+                self.indents.push(Indent::Quote);
+            }
+            Tag::CodeBlock(kind) => {
+                self.start_block();
+                let fence = match kind {
+                    CodeBlockKind::Fenced(info) => CodeFence::parse(info),
+                    CodeBlockKind::Indented => CodeFence::default(),
+                };
+                self.code_block = Some((fence, String::new()));
+            }
+            Tag::List(start) => {
+                if self.lists.is_empty() {
+                    self.start_block();
+                } else {
+                    // A nested list starts on its own line
+                    self.flush_line();
+                }
+                self.lists.push(*start);
+            }
+            Tag::Item => {
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}.", *number - 1)
+                    }
+                    _ => "•".to_string(),
+                };
+                let width = display_width(&marker) + 1;
+                self.indents.push(Indent::Item {
+                    width,
+                    marker: Some(format!("\x1b[36m{}\x1b[0m ", marker)),
+                });
+            }
+            Tag::FootnoteDefinition(name) => {
+                self.start_block();
+                self.push_styled(DIM, &format!("[^{}]: ", name));
+            }
+            Tag::Table(alignments) => {
+                self.start_block();
+                self.table = Some(Table {
+                    alignments: alignments.clone(),
+                    ..Table::default()
+                });
+            }
+            Tag::TableHead => {
+                self.start_row();
+                self.styles.push("1");
+            }
+            Tag::TableRow => self.start_row(),
+            Tag::TableCell => self.restyle(),
+            Tag::Emphasis => self.push_style("3"),
+            Tag::Strong => self.push_style("1"),
+            Tag::Strikethrough => self.push_style("9"),
+            Tag::Link(_, dest, _) => {
+                self.links.push((dest.to_string(), String::new()));
+                self.push_style("4;34");
+            }
+            Tag::Image(..) => self.image = Some(String::new()),
+        }
+    }
+
+    fn end(&mut self, tag: &Tag) {
+        match tag {
+            Tag::Paragraph | Tag::FootnoteDefinition(_) => self.end_block(),
+            Tag::Heading(..) => {
+                self.styles.pop();
+                self.end_block();
+            }
+            Tag::BlockQuote => {
+                self.flush_line();
+                self.indents.pop();
+                self.needs_blank = true;
+            }
+            Tag::CodeBlock(_) => self.end_code_block(),
+            Tag::List(_) => {
+                self.flush_line();
+                self.lists.pop();
+                self.needs_blank = self.lists.is_empty();
+            }
+            Tag::Item => {
+                self.flush_line();
+                self.indents.pop();
+                self.needs_blank = false;
+            }
+            Tag::Table(_) => self.end_table(),
+            Tag::TableHead => {
+                self.styles.pop();
+                if let Some(table) = self.table.as_mut() {
+                    table.header_rows = table.rows.len();
+                }
+            }
+            Tag::TableRow => {}
+            Tag::TableCell => self.end_cell(),
+            Tag::Emphasis | Tag::Strong | Tag::Strikethrough => self.pop_style(),
+            Tag::Link(..) => {
+                self.pop_style();
+                if let Some((dest, text)) = self.links.pop() {
+                    if !dest.is_empty() && !dest.starts_with('#') && dest != text {
+                        self.push_styled(DIM, &format!(" ({})", dest));
+                    }
+                }
+            }
+            Tag::Image(..) => {}
+        }
+    }
+
+    /// Append text in the current style to the current line or table cell
+    fn push_text(&mut self, text: &str) {
+        for (_, link_text) in self.links.iter_mut() {
+            link_text.push_str(text);
+        }
+        if let Some(row) = self.table.as_mut().and_then(|table| table.rows.last_mut()) {
+            if let Some(cell) = row.last_mut() {
+                cell.push_str(text);
+            }
+            return;
+        }
+        self.line.push_str(text);
+        self.line_has_text = true;
+    }
+
+    /// Append text in an extra style, then return to the current one
+    fn push_styled(&mut self, code: &'static str, text: &str) {
+        self.push_style(code);
+        self.push_text(text);
+        self.pop_style();
+    }
+
+    fn push_style(&mut self, code: &'static str) {
+        self.styles.push(code);
+        self.restyle();
+    }
+
+    fn pop_style(&mut self) {
+        self.styles.pop();
+        self.restyle();
+    }
+
+    /// Reset, then apply every active style
+    fn restyle(&mut self) {
+        let codes = format!("{}{}", RESET, self.style_codes());
+        if let Some(row) = self.table.as_mut().and_then(|table| table.rows.last_mut()) {
+            if let Some(cell) = row.last_mut() {
+                cell.push_str(&codes);
+            }
+        } else {
+            self.line.push_str(&codes);
+        }
+    }
+
+    fn style_codes(&self) -> String {
+        if self.styles.is_empty() {
+            String::new()
+        } else {
+            format!("\x1b[{}m", self.styles.join(";"))
+        }
+    }
+
+    /// Indentation for the next line: quote bars and list markers or padding
+    fn take_prefix(&mut self) -> String {
+        let mut prefix = String::new();
+        for indent in self.indents.iter_mut() {
+            match indent {
+                Indent::Quote => prefix.push_str("\x1b[90m│\x1b[0m "),
+                Indent::Item { width, marker } => match marker.take() {
+                    Some(marker) => prefix.push_str(&marker),
+                    None => prefix.push_str(&" ".repeat(*width)),
+                },
+            }
+        }
+        prefix
+    }
+
+    /// End the current line, if it has any text
+    fn flush_line(&mut self) {
+        if self.line_has_text {
+            let prefix = self.take_prefix();
+            self.output.push_str(&prefix);
+            self.output.push_str(&self.line);
+            self.output.push_str(RESET);
+            self.output.push('\n');
+        }
+        self.line = self.style_codes();
+        self.line_has_text = false;
+    }
+
+    /// Write a line that is already complete, such as a code or table line
+    fn push_line(&mut self, line: &str) {
+        let prefix = self.take_prefix();
+        self.output.push_str(&prefix);
+        self.output.push_str(line);
+        self.output.push('\n');
+    }
+
+    fn start_block(&mut self) {
+        self.flush_line();
+        if self.needs_blank && !self.output.is_empty() {
+            // Keep quote bars running through the gap
+            let bars: String = self
+                .indents
+                .iter()
+                .map(|indent| match indent {
+                    Indent::Quote => "\x1b[90m│\x1b[0m ",
+                    Indent::Item { .. } => "",
+                })
+                .collect();
+            self.output.push_str(bars.trim_end());
+            self.output.push('\n');
+        }
+        self.needs_blank = false;
+    }
+
+    fn end_block(&mut self) {
+        self.flush_line();
+        self.needs_blank = true;
+    }
+
+    fn end_code_block(&mut self) {
+        let Some((fence, code)) = self.code_block.take() else {
+            return;
+        };
+        let code = code.trim_end_matches('\n');
+
+        if let Some(title) = fence.title.as_ref() {
+            self.push_line(&format!("\x1b[90m┌ {}{}", title, RESET));
+        }
+
+        let lines = if fence.language == "run" {
+            code.lines()
+                .map(|command| format!("\x1b[32m$ {}{}", command.trim(), RESET))
+                .collect()
+        } else if fence.language.is_empty() {
+            code.lines().map(String::from).collect()
+        } else {
+            SyntaxHighlighter::shared().highlight_ansi_lines(code, &fence.language)
+        };
+
+        for (index, line) in lines.iter().enumerate() {
+            let number = index + 1;
+            let gutter = match fence.line_numbers {
+                Some(first) => format!("\x1b[90m{:>3} │{} ", first + index, RESET),
+                None => String::new(),
+            };
+            let marker = if fence.is_highlighted(number) {
+                "\x1b[33m▌\x1b[0m "
+            } else {
+                "  "
+            };
+            self.push_line(&format!("{}{}{}", marker, gutter, line));
+        }
+        self.needs_blank = true;
+    }
+
+    fn start_row(&mut self) {
+        if let Some(table) = self.table.as_mut() {
+            table.rows.push(vec![String::new()]);
+        }
+    }
+
+    fn end_cell(&mut self) {
+        if let Some(row) = self.table.as_mut().and_then(|table| table.rows.last_mut()) {
+            if let Some(cell) = row.last_mut() {
+                cell.push_str(RESET);
+            }
+            // Start the next cell
+            row.push(String::new());
+        }
+    }
+
+    fn end_table(&mut self) {
+        let Some(mut table) = self.table.take() else {
+            return;
+        };
+        // Every row ends with the empty cell started after its last one
+        for row in table.rows.iter_mut() {
+            row.pop();
+        }
+
+        let columns = table.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                table
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| display_width(cell))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let border = |left: &str, middle: &str, right: &str| {
+            let segments: Vec<String> = widths.iter().map(|width| "─".repeat(width + 2)).collect();
+            format!(
+                "\x1b[90m{}{}{}{}",
+                left,
+                segments.join(middle),
+                right,
+                RESET
+            )
+        };
+
+        self.push_line(&border("┌", "┬", "┐"));
+        for (index, row) in table.rows.iter().enumerate() {
+            if index == table.header_rows && index > 0 {
+                self.push_line(&border("├", "┼", "┤"));
+            }
+            let cells: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(column, width)| {
+                    let cell = row.get(column).map(String::as_str).unwrap_or_default();
+                    let alignment = table
+                        .alignments
+                        .get(column)
+                        .copied()
+                        .unwrap_or(Alignment::None);
+                    pad(cell, *width, alignment)
+                })
+                .collect();
+            let bar = "\x1b[90m│\x1b[0m";
+            self.push_line(&format!(
+                "{} {} {}",
+                bar,
+                cells.join(&format!(" {} ", bar)),
+                bar
+            ));
+        }
+        self.push_line(&border("└", "┴", "┘"));
+        self.needs_blank = true;
+    }
+
+    fn finish(mut self) -> String {
+        self.flush_line();
+        self.output.trim_end_matches('\n').to_string()
+    }
+}
+
+/// Pad a cell to `width` columns according to the column alignment
+fn pad(cell: &str, width: usize, alignment: Alignment) -> String {
+    let space = width.saturating_sub(display_width(cell));
+    let (left, right) = match alignment {
+        Alignment::Right => (space, 0),
+        Alignment::Center => (space / 2, space - space / 2),
+        Alignment::Left | Alignment::None => (0, space),
+    };
+    format!("{}{}{}", " ".repeat(left), cell, " ".repeat(right))
+}
+
+fn heading_style(level: HeadingLevel) -> &'static str {
+    match level {
+        HeadingLevel::H1 => "1;32",
+        HeadingLevel::H2 => "1;36",
+        HeadingLevel::H3 => "1;34",
+        _ => "1",
+    }
+}
+
+/// Title color for an admonition, by its CSS modifier
+fn admonition_style(class: &str) -> &'static str {
+    match class {
+        "tip" => "1;32",
+        "important" => "1;35",
+        "warning" => "1;33",
+        "caution" => "1;31",
+        _ => "1;34",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rendered text with escapes removed
+    fn plain(markdown: &str) -> String {
+        let mut text = String::new();
+        let mut in_escape = false;
+        for c in render_markdown_to_ansi(markdown, &MarkdownConfig::default()).chars() {
+            match c {
+                '\x1b' => in_escape = true,
+                c if in_escape => in_escape = !c.is_ascii_alphabetic(),
+                c => text.push(c),
+            }
+        }
+        text
+    }
+
+    #[test]
+    fn test_blocks_snapshot() {
+        let markdown = "---\ntitle: Demo\n---\n# Demo :rocket:\n\nSome **bold** and [a link](https://example.com).\n\n- one\n- [x] two\n  1. nested\n\n> [!TIP]\n> Quoted\n\n```js title=\"a.js\"\nlet a = 1;\n```\n";
+
+        assert_eq!(
+            plain(markdown),
+            "# Demo 🚀\n\nSome bold and a link (https://example.com).\n\n• one\n• ☑ two\n  1. nested\n\n│ Tip\n│ Quoted\n\n┌ a.js\n  let a = 1;"
+        );
+    }
+
+    #[test]
+    fn test_table_snapshot() {
+        let markdown = "| Name | 数量 |\n|:-----|-----:|\n| apple | 3 |\n| 香蕉 | 12 |\n";
+
+        assert_eq!(
+            plain(markdown),
+            "┌───────┬──────┐\n│ Name  │ 数量 │\n├───────┼──────┤\n│ apple │    3 │\n│ 香蕉  │   12 │\n└───────┴──────┘"
+        );
+    }
+
+    #[test]
+    fn test_styles_are_ansi_codes() {
+        let output = render_markdown_to_ansi("## Title\n\n*it* `code`", &MarkdownConfig::default());
+
+        assert!(output.starts_with("\x1b[0m\x1b[1;36m## Title"));
+        assert!(output.contains("\x1b[0m\x1b[3mit\x1b[0m"));
+        assert!(output.contains("\x1b[0m\x1b[33mcode\x1b[0m"));
+    }
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("\x1b[1m目录\x1b[0m"), 4);
+        assert_eq!(display_width("🚀"), 2);
+    }
+}
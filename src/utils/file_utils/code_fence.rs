@@ -0,0 +1,174 @@
+// Code Fence Metadata
+// Parses fence info strings such as `rust {3,5-7} title="main.rs" showLineNumbers`
+
+/// Language and attributes of a fenced code block
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CodeFence {
+    /// Language name, empty when the fence has none
+    pub language: String,
+    /// Inclusive 1-based line ranges from `{3,5-7}`
+    pub highlighted: Vec<(usize, usize)>,
+    /// Filename header from `title="..."` (or `filename="..."`)
+    pub title: Option<String>,
+    /// First line number when a gutter is requested via `showLineNumbers`
+    /// (or `showLineNumbers=10` to start counting at 10)
+    pub line_numbers: Option<usize>,
+    /// Color lines starting with `+`/`-`, set by the `diff` language or flag
+    pub diff: bool,
+    /// Any other bare words, e.g. `lazy` in ```` ```run lazy ````
+    pub flags: Vec<String>,
+}
+
+impl CodeFence {
+    /// Parse a fence info string
+    ///
+    /// The first word is the language unless it is an attribute. Unknown
+    /// `key=value` attributes are ignored so they never break highlighting.
+    pub fn parse(info: &str) -> Self {
+        let mut fence = CodeFence::default();
+
+        for (index, token) in tokenize(info).into_iter().enumerate() {
+            if let Some(ranges) = token.strip_prefix('{').and_then(|t| t.strip_suffix('}')) {
+                fence.highlighted.extend(parse_ranges(ranges));
+            } else if let Some((key, value)) = token.split_once('=') {
+                match key {
+                    "title" | "filename" => fence.title = Some(value.to_string()),
+                    "showLineNumbers" | "lineNumbers" => {
+                        fence.line_numbers = Some(value.parse().unwrap_or(1))
+                    }
+                    _ => {}
+                }
+            } else {
+                match token.as_str() {
+                    "showLineNumbers" | "lineNumbers" => fence.line_numbers = Some(1),
+                    "diff" => {
+                        fence.diff = true;
+                        if index == 0 {
+                            fence.language = token;
+                        }
+                    }
+                    _ if index == 0 => fence.language = token,
+                    _ => fence.flags.push(token),
+                }
+            }
+        }
+
+        fence
+    }
+
+    /// Whether the block needs per-line rendering rather than plain highlighting
+    pub fn has_line_features(&self) -> bool {
+        !self.highlighted.is_empty()
+            || self.title.is_some()
+            || self.line_numbers.is_some()
+            || self.diff
+    }
+
+    /// Whether a 1-based line number falls inside a highlighted range
+    pub fn is_highlighted(&self, line: usize) -> bool {
+        self.highlighted
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&line))
+    }
+
+    /// Check whether a bare flag such as `lazy` was given
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+}
+
+/// Split on whitespace, keeping quoted values and `{...}` groups together
+fn tokenize(info: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_braces = false;
+
+    for c in info.trim().chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '{') => {
+                in_braces = true;
+                current.push(c);
+            }
+            (None, '}') => {
+                in_braces = false;
+                current.push(c);
+            }
+            (None, c) if c.is_whitespace() && !in_braces => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            (None, c) if c.is_whitespace() => {}
+            (None, c) => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// Parse `3,5-7` into inclusive ranges, skipping malformed entries
+fn parse_ranges(ranges: &str) -> Vec<(usize, usize)> {
+    ranges
+        .split(',')
+        .filter_map(|part| {
+            let part = part.trim();
+            match part.split_once('-') {
+                Some((start, end)) => {
+                    let start = start.trim().parse().ok()?;
+                    let end = end.trim().parse().ok()?;
+                    (start <= end).then_some((start, end))
+                }
+                None => part.parse().ok().map(|line| (line, line)),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_info_string() {
+        let fence = CodeFence::parse(r#"rust {3,5-7} title="main.rs" showLineNumbers"#);
+
+        assert_eq!(fence.language, "rust");
+        assert_eq!(fence.highlighted, vec![(3, 3), (5, 7)]);
+        assert_eq!(fence.title.as_deref(), Some("main.rs"));
+        assert_eq!(fence.line_numbers, Some(1));
+        assert!(fence.is_highlighted(6));
+        assert!(!fence.is_highlighted(4));
+    }
+
+    #[test]
+    fn test_parse_attributes_without_language() {
+        let fence = CodeFence::parse(r#"title="my file.txt" { 2 - 3 } showLineNumbers=10"#);
+
+        assert_eq!(fence.language, "");
+        assert_eq!(fence.title.as_deref(), Some("my file.txt"));
+        assert_eq!(fence.highlighted, vec![(2, 3)]);
+        assert_eq!(fence.line_numbers, Some(10));
+    }
+
+    #[test]
+    fn test_parse_flags_and_diff() {
+        assert!(CodeFence::parse("diff").diff);
+        assert_eq!(CodeFence::parse("diff").language, "diff");
+
+        let fence = CodeFence::parse("rust diff");
+        assert_eq!(fence.language, "rust");
+        assert!(fence.diff);
+
+        let fence = CodeFence::parse("run lazy");
+        assert_eq!(fence.language, "run");
+        assert!(fence.has_flag("lazy"));
+        assert!(!fence.has_line_features());
+    }
+}
@@ -0,0 +1,351 @@
+// Markdown Extensions
+// Admonitions, emoji shortcodes and definition lists on top of pulldown-cmark
+
+use crate::utils::app_config::MarkdownConfig;
+use pulldown_cmark::{CowStr, Event, Tag};
+
+/// GitHub alert types: marker, title and CSS modifier
+const ADMONITIONS: [(&str, &str, &str); 5] = [
+    ("NOTE", "Note", "note"),
+    ("TIP", "Tip", "tip"),
+    ("IMPORTANT", "Important", "important"),
+    ("WARNING", "Warning", "warning"),
+    ("CAUTION", "Caution", "caution"),
+];
+
+/// Supported `:shortcode:` emoji, a subset of GitHub's list
+const EMOJI: &[(&str, &str)] = &[
+    ("+1", "👍"),
+    ("-1", "👎"),
+    ("100", "💯"),
+    ("bug", "🐛"),
+    ("bulb", "💡"),
+    ("books", "📚"),
+    ("book", "📖"),
+    ("bookmark", "🔖"),
+    ("boom", "💥"),
+    ("calendar", "📆"),
+    ("check", "✔️"),
+    ("clap", "👏"),
+    ("coffee", "☕"),
+    ("computer", "💻"),
+    ("construction", "🚧"),
+    ("crab", "🦀"),
+    ("memo", "📝"),
+    ("eyes", "👀"),
+    ("fire", "🔥"),
+    ("gear", "⚙️"),
+    ("globe_with_meridians", "🌐"),
+    ("hammer", "🔨"),
+    ("heart", "❤️"),
+    ("heavy_check_mark", "✔️"),
+    ("hourglass", "⌛"),
+    ("information_source", "ℹ️"),
+    ("key", "🔑"),
+    ("link", "🔗"),
+    ("lock", "🔒"),
+    ("mag", "🔍"),
+    ("package", "📦"),
+    ("pencil", "📝"),
+    ("pencil2", "✏️"),
+    ("point_right", "👉"),
+    ("pushpin", "📌"),
+    ("question", "❓"),
+    ("rainbow", "🌈"),
+    ("rocket", "🚀"),
+    ("sparkles", "✨"),
+    ("smile", "😄"),
+    ("smiley", "😃"),
+    ("star", "⭐"),
+    ("tada", "🎉"),
+    ("thinking", "🤔"),
+    ("thumbsdown", "👎"),
+    ("thumbsup", "👍"),
+    ("warning", "⚠️"),
+    ("wave", "👋"),
+    ("white_check_mark", "✅"),
+    ("wink", "😉"),
+    ("wrench", "🔧"),
+    ("x", "❌"),
+    ("zap", "⚡"),
+];
+
+/// Apply the enabled extensions to a parsed event stream
+///
+/// Runs before code highlighting and link rewriting, so the extensions
+/// only ever see plain markdown events.
+pub fn apply_extensions<'a>(events: Vec<Event<'a>>, config: &MarkdownConfig) -> Vec<Event<'a>> {
+    let mut events = merge_text(events);
+    if config.admonitions {
+        events = admonitions(events);
+    }
+    if config.definition_lists {
+        events = definition_lists(events);
+    }
+    if config.emoji {
+        events = emoji(events);
+    }
+    events
+}
+
+/// Look up the emoji for a shortcode name (without colons)
+pub fn emoji_for(name: &str) -> Option<&'static str> {
+    EMOJI
+        .iter()
+        .find(|(shortcode, _)| *shortcode == name)
+        .map(|(_, emoji)| *emoji)
+}
+
+/// Join adjacent text events, which pulldown-cmark splits at `[`, `_` and `:`
+fn merge_text(events: Vec<Event>) -> Vec<Event> {
+    let mut merged: Vec<Event> = Vec::with_capacity(events.len());
+    for event in events {
+        match (merged.last_mut(), event) {
+            (Some(Event::Text(previous)), Event::Text(text)) => {
+                *previous = CowStr::from(format!("{}{}", previous, text));
+            }
+            (_, event) => merged.push(event),
+        }
+    }
+    merged
+}
+
+/// Turn `> [!NOTE]` blockquotes into styled callouts
+fn admonitions(events: Vec<Event>) -> Vec<Event> {
+    let mut output = Vec::with_capacity(events.len());
+    // For each open blockquote, whether it became an admonition
+    let mut open: Vec<bool> = Vec::new();
+    let mut index = 0;
+
+    while index < events.len() {
+        match &events[index] {
+            Event::Start(Tag::BlockQuote) => {
+                let alert = match (events.get(index + 1), events.get(index + 2)) {
+                    (Some(Event::Start(Tag::Paragraph)), Some(Event::Text(text))) => {
+                        parse_alert_marker(text)
+                    }
+                    _ => None,
+                };
+                open.push(alert.is_some());
+
+                let Some(((title, class), rest)) = alert else {
+                    output.push(events[index].clone());
+                    index += 1;
+                    continue;
+                };
+
+                output.push(Event::Html(
+                    format!(
+                        r#"<div class="admonition admonition-{}"><div class="admonition-title">{}</div>"#,
+                        class, title
+                    )
+                    .into(),
+                ));
+
+                // Drop the marker (and the line break after it) from the paragraph
+                index += 3;
+                let rest = rest.trim_start().to_string();
+                let mut paragraph = vec![Event::Start(Tag::Paragraph)];
+                if !rest.is_empty() {
+                    paragraph.push(Event::Text(rest.into()));
+                } else if matches!(events.get(index), Some(Event::SoftBreak | Event::HardBreak)) {
+                    index += 1;
+                }
+                if matches!(events.get(index), Some(Event::End(Tag::Paragraph)))
+                    && paragraph.len() == 1
+                {
+                    // The marker was the whole paragraph
+                    index += 1;
+                } else {
+                    output.extend(paragraph);
+                }
+            }
+            Event::End(Tag::BlockQuote) => {
+                if open.pop().unwrap_or(false) {
+                    output.push(Event::Html("</div>".into()));
+                } else {
+                    output.push(events[index].clone());
+                }
+                index += 1;
+            }
+            event => {
+                output.push(event.clone());
+                index += 1;
+            }
+        }
+    }
+
+    output
+}
+
+/// Parse `[!NOTE] rest` into the alert's title, CSS class and remaining text
+pub(super) fn parse_alert_marker(text: &str) -> Option<((&'static str, &'static str), &str)> {
+    let inner = text.strip_prefix("[!")?;
+    let (kind, rest) = inner.split_once(']')?;
+    ADMONITIONS
+        .iter()
+        .find(|(marker, _, _)| marker.eq_ignore_ascii_case(kind))
+        .map(|(_, title, class)| ((*title, *class), rest))
+}
+
+/// Turn paragraphs of `Term` lines followed by `: Definition` lines into `<dl>`
+///
+/// ```text
+/// Term
+/// : Definition
+/// ```
+fn definition_lists(events: Vec<Event>) -> Vec<Event> {
+    let mut output = Vec::with_capacity(events.len());
+    let mut index = 0;
+
+    while index < events.len() {
+        if let Event::Start(Tag::Paragraph) = events[index] {
+            let end = events[index..]
+                .iter()
+                .position(|event| matches!(event, Event::End(Tag::Paragraph)))
+                .map(|offset| index + offset);
+            if let Some(end) = end {
+                if let Some(list) = definition_list(&events[index + 1..end]) {
+                    output.extend(list);
+                    index = end + 1;
+                    continue;
+                }
+            }
+        }
+        output.push(events[index].clone());
+        index += 1;
+    }
+
+    output
+}
+
+/// Render the inline events of one paragraph as a definition list, if it is one
+fn definition_list<'a>(inline: &[Event<'a>]) -> Option<Vec<Event<'a>>> {
+    let lines: Vec<&[Event]> = inline
+        .split(|event| matches!(event, Event::SoftBreak | Event::HardBreak))
+        .collect();
+    let is_definition =
+        |line: &[Event]| matches!(line.first(), Some(Event::Text(text)) if text.starts_with(": "));
+
+    // The first line must be a term, and some later line a definition
+    if lines.len() < 2 || is_definition(lines[0]) || !lines[1..].iter().any(|l| is_definition(l)) {
+        return None;
+    }
+
+    let mut output = vec![Event::Html("<dl>".into())];
+    for line in lines {
+        if is_definition(line) {
+            output.push(Event::Html("<dd>".into()));
+            if let Some(Event::Text(text)) = line.first() {
+                output.push(Event::Text(text[2..].to_string().into()));
+            }
+            output.extend(line[1..].iter().cloned());
+            output.push(Event::Html("</dd>".into()));
+        } else {
+            output.push(Event::Html("<dt>".into()));
+            output.extend(line.iter().cloned());
+            output.push(Event::Html("</dt>".into()));
+        }
+    }
+    output.push(Event::Html("</dl>".into()));
+
+    Some(output)
+}
+
+/// Replace `:shortcode:` in text outside code with emoji
+fn emoji(events: Vec<Event>) -> Vec<Event> {
+    let mut in_code_block = false;
+
+    events
+        .into_iter()
+        .map(|event| match event {
+            Event::Start(Tag::CodeBlock(_)) => {
+                in_code_block = true;
+                event
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                in_code_block = false;
+                event
+            }
+            Event::Text(text) if !in_code_block && text.contains(':') => {
+                Event::Text(replace_shortcodes(&text).into())
+            }
+            event => event,
+        })
+        .collect()
+}
+
+/// Replace known `:shortcode:`s in a string, leaving unknown ones as they are
+fn replace_shortcodes(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(':') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let name_len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '+' || c == '-'))
+            .unwrap_or(after.len());
+
+        match (
+            after[name_len..].starts_with(':'),
+            emoji_for(&after[..name_len]),
+        ) {
+            (true, Some(emoji)) if name_len > 0 => {
+                output.push_str(emoji);
+                rest = &after[name_len + 1..];
+            }
+            _ => {
+                output.push(':');
+                rest = after;
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::{html, Parser};
+
+    fn render(markdown: &str) -> String {
+        let events = apply_extensions(Parser::new(markdown).collect(), &MarkdownConfig::default());
+        let mut output = String::new();
+        html::push_html(&mut output, events.into_iter());
+        output
+    }
+
+    #[test]
+    fn test_admonitions() {
+        let html = render("> [!WARNING]\n> Mind the *gap*.\n\n> plain quote\n");
+
+        assert!(html.starts_with(
+            r#"<div class="admonition admonition-warning"><div class="admonition-title">Warning</div>
+<p>Mind the <em>gap</em>.</p>"#
+        ));
+        assert!(html.contains("<blockquote>\n<p>plain quote</p>\n</blockquote>"));
+    }
+
+    #[test]
+    fn test_emoji_shortcodes() {
+        assert_eq!(
+            replace_shortcodes("Ship it :rocket: at 10:30 :unknown: :white_check_mark:"),
+            "Ship it 🚀 at 10:30 :unknown: ✅"
+        );
+        assert!(render("`:rocket:`").contains("<code>:rocket:</code>"));
+        assert!(render(":tada: done").contains("🎉 done"));
+    }
+
+    #[test]
+    fn test_definition_lists() {
+        let html = render("Term\n: First *definition*\n: Second\n\nNot: a list\n");
+
+        assert!(html.starts_with(
+            "<dl><dt>Term</dt><dd>First <em>definition</em></dd><dd>Second</dd></dl>"
+        ));
+        assert!(html.contains("<p>Not: a list</p>"));
+    }
+}
@@ -2,10 +2,34 @@
 // Handles HTTP requests to fetch file content from the data directory
 
 use super::super::config::build_data_url;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{Request, RequestInit, RequestMode, Response};
 
+thread_local! {
+    /// Contents fetched by `fetch_file_content_cached`, keyed by data path
+    static CONTENT_CACHE: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+/// Fetch file content, reusing earlier fetches of the same file
+///
+/// Data files don't change while the app runs, so commands that read many
+/// files (like grep) only download each one once. Failures aren't cached.
+pub async fn fetch_file_content_cached(file_path: &str) -> Result<String, String> {
+    if let Some(content) = CONTENT_CACHE.with(|cache| cache.borrow().get(file_path).cloned()) {
+        return Ok(content);
+    }
+    let content = fetch_file_content(file_path).await?;
+    CONTENT_CACHE.with(|cache| {
+        cache
+            .borrow_mut()
+            .insert(file_path.to_string(), content.clone())
+    });
+    Ok(content)
+}
+
 /// Fetch file content from the data directory
 pub async fn fetch_file_content(file_path: &str) -> Result<String, String> {
     let url = build_data_url(file_path);
@@ -0,0 +1,109 @@
+// Markdown Frontmatter
+// Parses the `--`/`---` delimited metadata block at the top of markdown files
+
+use std::collections::HashMap;
+
+/// Key/value metadata from a markdown file's frontmatter
+///
+/// Uses the same `key: value` format as generate_metadata.js.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frontmatter {
+    fields: HashMap<String, String>,
+}
+
+impl Frontmatter {
+    /// Parse the frontmatter of a markdown file; empty if there is none
+    pub fn parse(markdown_input: &str) -> Self {
+        let mut fields = HashMap::new();
+        let Some(delimiter) = opening_delimiter(markdown_input) else {
+            return Self { fields };
+        };
+
+        for line in markdown_input.lines().skip(1) {
+            if line == delimiter {
+                break;
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                let key = key.trim();
+                let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+                if !key.is_empty() && !value.is_empty() {
+                    fields.insert(key.to_string(), value.to_string());
+                }
+            }
+        }
+
+        Self { fields }
+    }
+
+    /// Get a field's value
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.get(key).map(|value| value.as_str())
+    }
+}
+
+fn opening_delimiter(markdown_input: &str) -> Option<&'static str> {
+    if markdown_input.starts_with("---\n") {
+        Some("---")
+    } else if markdown_input.starts_with("--\n") {
+        Some("--")
+    } else {
+        None
+    }
+}
+
+/// Strip frontmatter metadata from markdown content
+pub fn strip_frontmatter(markdown_input: &str) -> String {
+    // Check if content starts with frontmatter delimiter
+    if !markdown_input.starts_with("--\n") && !markdown_input.starts_with("---\n") {
+        return markdown_input.to_string();
+    }
+
+    // Find the closing delimiter
+    let lines: Vec<&str> = markdown_input.lines().collect();
+    if lines.is_empty() {
+        return markdown_input.to_string();
+    }
+
+    // Determine the closing delimiter pattern
+    let delimiter = if lines[0] == "---" { "---" } else { "--" };
+
+    // Find the end of the frontmatter
+    for (i, line) in lines.iter().enumerate().skip(1) {
+        if *line == delimiter {
+            // Found closing delimiter, return content after it
+            if i + 1 < lines.len() {
+                return lines[i + 1..].join("\n");
+            } else {
+                return String::new(); // No content after frontmatter
+            }
+        }
+    }
+
+    // No closing delimiter found, return original content
+    markdown_input.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_frontmatter() {
+        let markdown = "---\ntitle: \"Hello: world\"\nimage_width: 600\ntoc: true\n---\n# Body\n";
+        let frontmatter = Frontmatter::parse(markdown);
+
+        assert_eq!(frontmatter.get("title"), Some("Hello: world"));
+        assert_eq!(frontmatter.get("image_width"), Some("600"));
+        assert_eq!(frontmatter.get("toc"), Some("true"));
+        assert_eq!(frontmatter.get("missing"), None);
+        assert_eq!(strip_frontmatter(markdown), "# Body");
+    }
+
+    #[test]
+    fn test_no_frontmatter() {
+        assert_eq!(
+            Frontmatter::parse("# Title\nkey: value"),
+            Frontmatter::default()
+        );
+    }
+}
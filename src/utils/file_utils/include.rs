@@ -0,0 +1,484 @@
+// Include Directives
+// Expands {{#include path}} and {{#exec command}} in markdown before rendering
+
+use super::frontmatter::strip_frontmatter;
+use super::link_rewriter::parent_dir;
+use super::markdown_processor::html_escape;
+use super::run_block::run_to_result;
+use crate::commands::{CommandExecutor, CommandResult};
+use crate::filesystem::navigation::resolve_path;
+use pulldown_cmark::{Event, Parser, Tag};
+use std::cell::Cell;
+use std::future::Future;
+use std::ops::Range;
+use std::pin::Pin;
+
+/// Deepest chain of nested includes before giving up
+const MAX_INCLUDE_DEPTH: usize = 8;
+/// Deepest chain of `{{#exec}}` commands or run blocks rendering files with
+/// more of them
+pub(super) const MAX_EXEC_DEPTH: usize = 3;
+
+thread_local! {
+    /// `{{#exec}}` commands and run blocks currently running, to stop `view`
+    /// recursing into itself
+    static EXEC_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+type Pending<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+/// Loads a file by its data path, e.g. `posts/demo.md`
+type Loader<'a> = dyn Fn(String) -> Pending<'a, Result<String, String>> + 'a;
+/// Runs a command to completion
+type Runner<'a> = dyn Fn(String) -> Pending<'a, CommandResult> + 'a;
+/// Inclusive 1-based line range; a missing bound means the file's edge
+type LineRange = (Option<usize>, Option<usize>);
+
+/// Markdown with directives expanded
+#[derive(Debug, Default)]
+pub struct Expanded {
+    pub markdown: String,
+    /// HTML output of `{{#exec}}` commands, inserted after rendering
+    outputs: Vec<String>,
+    /// Whether a file written in the overlay was included, so its raw HTML
+    /// must not be trusted
+    pub includes_overlay: bool,
+}
+
+impl Expanded {
+    /// Replace `{{#exec}}` placeholders in rendered HTML with command output
+    pub fn fill_outputs(&self, html: String) -> String {
+        self.outputs
+            .iter()
+            .enumerate()
+            .fold(html, |html, (index, output)| {
+                html.replacen(&exec_placeholder(index), output, 1)
+            })
+    }
+}
+
+/// Expand include directives in a file fetched from `file_path`
+///
+/// - `{{#include path}}` inserts a file, resolved against the including
+///   file's directory; markdown files are expanded recursively with their
+///   frontmatter removed, other files become a code block.
+/// - `{{#include path:3:10}}` inserts lines 3 to 10, `path:3` only line 3,
+///   `path:3:` from line 3 and `path::10` up to line 10.
+/// - `{{#exec command}}` runs a command in the reader's session and inserts
+///   its output, text as a code block.
+///
+/// Directives in code blocks and inline code are left as written. Failures,
+/// including include cycles, render as inline error placeholders rather
+/// than failing the whole page.
+pub async fn expand_includes(
+    content: &str,
+    file_path: &str,
+    executor: &CommandExecutor,
+) -> Expanded {
+    let includes_overlay = Cell::new(false);
+    let load = |path: String| -> Pending<'_, Result<String, String>> {
+        let source = executor
+            .filesystem
+            .borrow()
+            .get_file_info(&format!("/{}", path))
+            .map(|node| {
+                if node.overlay {
+                    includes_overlay.set(true);
+                }
+                node.content_source()
+            });
+        Box::pin(async move { source?.read().await })
+    };
+    let run = |command: String| -> Pending<'_, CommandResult> {
+        Box::pin(async move {
+            let Some(_depth) = ExecDepth::enter() else {
+                return CommandResult::Error("nested too deeply".to_string());
+            };
+            run_to_result(&command, executor).await
+        })
+    };
+
+    let expanded = Includes::new(&load, &run)
+        .expand_root(content, file_path)
+        .await;
+    Expanded {
+        includes_overlay: includes_overlay.get(),
+        ..expanded
+    }
+}
+
+/// A running `{{#exec}}` or run block counted in `EXEC_DEPTH` until dropped,
+/// so a render that is abandoned midway doesn't leave the count raised
+pub(super) struct ExecDepth;
+
+impl ExecDepth {
+    pub(super) fn enter() -> Option<Self> {
+        EXEC_DEPTH.with(|depth| {
+            if depth.get() >= MAX_EXEC_DEPTH {
+                return None;
+            }
+            depth.set(depth.get() + 1);
+            Some(Self)
+        })
+    }
+}
+
+impl Drop for ExecDepth {
+    fn drop(&mut self) {
+        EXEC_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// Poll `future` at the exec depth of the code spawning it
+///
+/// A spawned future runs after the render that started it has returned, so
+/// it takes that render's depth along instead of starting again from zero.
+pub(super) fn at_current_depth<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let mut depth = EXEC_DEPTH.with(Cell::get);
+    let mut future = Box::pin(future);
+    std::future::poll_fn(move |context| {
+        let outer = EXEC_DEPTH.with(|current| current.replace(depth));
+        let poll = future.as_mut().poll(context);
+        depth = EXEC_DEPTH.with(|current| current.replace(outer));
+        poll
+    })
+}
+
+/// Directive expansion with pluggable file loading and command execution
+struct Includes<'a> {
+    load: &'a Loader<'a>,
+    run: &'a Runner<'a>,
+    outputs: Vec<String>,
+}
+
+impl<'a> Includes<'a> {
+    fn new(load: &'a Loader<'a>, run: &'a Runner<'a>) -> Self {
+        Self {
+            load,
+            run,
+            outputs: Vec::new(),
+        }
+    }
+
+    async fn expand_root(mut self, content: &str, file_path: &str) -> Expanded {
+        let mut stack = vec![file_path.trim_start_matches('/').to_string()];
+        let markdown = self.expand(content.to_string(), &mut stack).await;
+        Expanded {
+            markdown,
+            outputs: self.outputs,
+            includes_overlay: false,
+        }
+    }
+
+    /// Expand directives in `content`, which belongs to the file on top of `stack`
+    fn expand<'s>(
+        &'s mut self,
+        content: String,
+        stack: &'s mut Vec<String>,
+    ) -> Pending<'s, String> {
+        Box::pin(async move {
+            let code = code_ranges(&content);
+            let mut output = String::with_capacity(content.len());
+            let mut rest = content.as_str();
+
+            while let Some(start) = rest.find("{{#") {
+                let Some(length) = rest[start..].find("}}") else {
+                    break;
+                };
+                let offset = content.len() - rest.len() + start;
+                if code.iter().any(|range| range.contains(&offset)) {
+                    output.push_str(&rest[..start + 3]);
+                    rest = &rest[start + 3..];
+                    continue;
+                }
+                output.push_str(&rest[..start]);
+                let directive = &rest[start + 3..start + length];
+                rest = &rest[start + length + 2..];
+
+                match directive.split_once(char::is_whitespace) {
+                    Some(("include", spec)) => {
+                        let included = self.include(spec.trim(), stack).await;
+                        output.push_str(&included);
+                    }
+                    Some(("exec", command)) => {
+                        let result = (self.run)(command.trim().to_string()).await;
+                        output.push_str(&self.exec_output(command.trim(), result));
+                    }
+                    // Not a directive we know, leave it as written
+                    _ => output.push_str(&format!("{{{{#{}}}}}", directive)),
+                }
+            }
+
+            output.push_str(rest);
+            output
+        })
+    }
+
+    async fn include(&mut self, spec: &str, stack: &mut Vec<String>) -> String {
+        let (path, range) = parse_include_spec(spec);
+        let base_dir = stack
+            .last()
+            .map(|file| parent_dir(file))
+            .unwrap_or_default();
+        let resolved = resolve_path(&base_dir, path)
+            .into_iter()
+            .filter(|component| !component.is_empty())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if stack.contains(&resolved) {
+            let chain: Vec<&str> = stack
+                .iter()
+                .map(String::as_str)
+                .chain([resolved.as_str()])
+                .collect();
+            return error_placeholder(
+                "include",
+                &format!("cycle detected: {}", chain.join(" -> ")),
+            );
+        }
+        if stack.len() > MAX_INCLUDE_DEPTH {
+            return error_placeholder("include", &format!("nested too deeply: {}", path));
+        }
+
+        let content = match (self.load)(resolved.clone()).await {
+            Ok(content) => content,
+            Err(error) => return error_placeholder("include", &format!("{}: {}", path, error)),
+        };
+        let content = match range {
+            Some((start, end)) => select_lines(&content, start, end),
+            None => content,
+        };
+        let content = content.trim_end_matches('\n').to_string();
+
+        if resolved.ends_with(".md") || resolved.ends_with(".markdown") {
+            stack.push(resolved);
+            let expanded = self.expand(strip_frontmatter(&content), stack).await;
+            stack.pop();
+            expanded
+        } else {
+            let language = resolved
+                .rsplit_once('.')
+                .map_or("text", |(_, extension)| extension);
+            code_block(language, &content)
+        }
+    }
+
+    /// Text output becomes a code block; HTML is filled in after rendering
+    fn exec_output(&mut self, command: &str, result: CommandResult) -> String {
+        match result {
+            CommandResult::Success(text) if text.trim().is_empty() => String::new(),
+            CommandResult::Success(text) => code_block("text", text.trim_end_matches('\n')),
+            CommandResult::Html(html) => {
+                self.outputs.push(html);
+                exec_placeholder(self.outputs.len() - 1)
+            }
+            CommandResult::Error(error) => {
+                error_placeholder("exec", &format!("{}: {}", command, error))
+            }
+            CommandResult::Async(_) => {
+                error_placeholder("exec", "nested async operations not supported")
+            }
+        }
+    }
+}
+
+/// Split `path:start:end` into the path and an optional inclusive line range
+fn parse_include_spec(spec: &str) -> (&str, Option<LineRange>) {
+    let mut parts = spec.splitn(3, ':');
+    let path = parts.next().unwrap_or_default();
+    let Some(start) = parts.next() else {
+        return (path, None);
+    };
+    let start = start.trim().parse().ok();
+    let end = match parts.next() {
+        Some(end) => end.trim().parse().ok(),
+        // `path:3` is just line 3
+        None => start,
+    };
+    (path, Some((start, end)))
+}
+
+/// Keep 1-based lines `start..=end`, either bound defaulting to the file's edge
+fn select_lines(content: &str, start: Option<usize>, end: Option<usize>) -> String {
+    let start = start.unwrap_or(1).max(1);
+    let end = end.unwrap_or(usize::MAX);
+    content
+        .lines()
+        .enumerate()
+        .filter(|(index, _)| (start..=end).contains(&(index + 1)))
+        .map(|(_, line)| line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Byte ranges of the code blocks and inline code in markdown
+fn code_ranges(markdown: &str) -> Vec<Range<usize>> {
+    Parser::new(markdown)
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Start(Tag::CodeBlock(_)) | Event::Code(_) => Some(range),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Fenced code block on lines of its own, with a fence longer than any
+/// run of backticks in `code`
+fn code_block(language: &str, code: &str) -> String {
+    let longest = code.split(|ch| ch != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("\n{}{}\n{}\n{}\n", fence, language, code, fence)
+}
+
+/// Marks where HTML output goes; a class rather than a `data-` attribute, so
+/// it survives raw HTML sanitizing
+fn exec_placeholder(index: usize) -> String {
+    format!(
+        r#"<span class="include-exec include-exec-{}"></span>"#,
+        index
+    )
+}
+
+fn error_placeholder(directive: &str, message: &str) -> String {
+    format!(
+        r#"<span class="include-error">{}: {}</span>"#,
+        directive,
+        html_escape(message)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::block_on;
+    use std::collections::HashMap;
+    fn expand(files: &[(&str, &str)], root: &str) -> Expanded {
+        let files: HashMap<String, String> = files
+            .iter()
+            .map(|(path, content)| (path.to_string(), content.to_string()))
+            .collect();
+        let load = |path: String| -> Pending<'_, Result<String, String>> {
+            let content = files
+                .get(&path)
+                .cloned()
+                .ok_or_else(|| format!("no such file or directory: {}", path));
+            Box::pin(async move { content })
+        };
+        let run = |command: String| -> Pending<'_, CommandResult> {
+            Box::pin(async move {
+                match command.as_str() {
+                    "pwd" => CommandResult::Success("/posts\n".to_string()),
+                    "ls --link" => CommandResult::Html("<div>links</div>".to_string()),
+                    _ => CommandResult::Error("command not found".to_string()),
+                }
+            })
+        };
+
+        let content = files[root].clone();
+        block_on(Includes::new(&load, &run).expand_root(&content, root))
+    }
+
+    #[test]
+    fn test_include_markdown_and_line_ranges() {
+        let expanded = expand(
+            &[
+                (
+                    "posts/guide.md",
+                    "# Guide\n{{#include ../snippets/install.md}}\n",
+                ),
+                (
+                    "snippets/install.md",
+                    "---\ntitle: x\n---\nRun:\n{{#include ../a.js:2:3}}\n",
+                ),
+                ("a.js", "one\ntwo\nthree\nfour\n"),
+            ],
+            "posts/guide.md",
+        );
+
+        assert_eq!(
+            expanded.markdown,
+            "# Guide\nRun:\n\n```js\ntwo\nthree\n```\n\n"
+        );
+    }
+
+    #[test]
+    fn test_directives_in_code_are_kept() {
+        let markdown = "Use `{{#exec pwd}}`:\n\n```md\n{{#include a.js}}\n```\n";
+        let expanded = expand(&[("index.md", markdown), ("a.js", "x")], "index.md");
+
+        assert_eq!(expanded.markdown, markdown);
+    }
+
+    #[test]
+    fn test_include_cycles_and_missing_files() {
+        let expanded = expand(
+            &[
+                ("a.md", "A {{#include b.md}}"),
+                ("b.md", "B {{#include a.md}} {{#include missing.md}}"),
+            ],
+            "a.md",
+        );
+
+        assert!(expanded.markdown.starts_with(
+            r#"A B <span class="include-error">include: cycle detected: a.md -&gt; b.md -&gt; a.md</span>"#
+        ));
+        assert!(expanded
+            .markdown
+            .contains("include: missing.md: no such file or directory: missing.md"));
+    }
+
+    #[test]
+    fn test_exec_output() {
+        let expanded = expand(
+            &[(
+                "index.md",
+                "{{#exec pwd}} {{#exec ls --link}} {{#exec nope}} {{#other}}",
+            )],
+            "index.md",
+        );
+
+        assert!(expanded.markdown.starts_with(
+            "\n```text\n/posts\n```\n <span class=\"include-exec include-exec-0\"></span> <span class=\"include-error\">exec: nope: command not found</span> {{#other}}"
+        ));
+        assert_eq!(
+            expanded.fill_outputs(exec_placeholder(0)),
+            "<div>links</div>"
+        );
+    }
+
+    #[test]
+    fn test_exec_depth_is_released_on_drop() {
+        let running: Vec<ExecDepth> = (0..MAX_EXEC_DEPTH)
+            .filter_map(|_| ExecDepth::enter())
+            .collect();
+        assert_eq!(running.len(), MAX_EXEC_DEPTH);
+        assert!(ExecDepth::enter().is_none());
+        drop(running);
+        assert_eq!(EXEC_DEPTH.with(Cell::get), 0);
+
+        let nested = {
+            let _depth = ExecDepth::enter();
+            at_current_depth(async { EXEC_DEPTH.with(Cell::get) })
+        };
+        assert_eq!(crate::test_fixtures::block_on(nested), 1);
+        assert_eq!(EXEC_DEPTH.with(Cell::get), 0);
+        assert_eq!(code_block("text", "a ``` b"), "\n````text\na ``` b\n````\n");
+    }
+
+    #[test]
+    fn test_parse_include_spec() {
+        assert_eq!(parse_include_spec("a.js"), ("a.js", None));
+        assert_eq!(
+            parse_include_spec("a.js:3"),
+            ("a.js", Some((Some(3), Some(3))))
+        );
+        assert_eq!(
+            parse_include_spec("a.js:3:"),
+            ("a.js", Some((Some(3), None)))
+        );
+        assert_eq!(
+            parse_include_spec("a.js::10"),
+            ("a.js", Some((None, Some(10))))
+        );
+    }
+}
@@ -0,0 +1,234 @@
+// Markdown Link Rewriting
+// Turns links in rendered markdown into terminal commands
+
+use crate::commands::CommandExecutor;
+use crate::filesystem::navigation::{path_to_string, resolve_path};
+use crate::filesystem::FileSystem;
+use crate::utils::config::{build_data_path, get_base_path};
+
+/// How a markdown link should be rendered
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkTarget {
+    /// Another site: open in a new tab
+    External(String),
+    /// A file or directory in the virtual filesystem: run `command` on click,
+    /// with `href` as the fallback for opening in a new tab
+    Command { command: String, href: String },
+    /// Anything else (in-page anchors, mail links): keep as-is
+    Unchanged,
+}
+
+/// Classify a link destination found in a file under `base_dir`
+///
+/// Relative destinations are resolved against the viewed file's directory.
+/// Markdown files become `view`, directories `navigate` and other files
+/// `cat`. When a filesystem is given, it decides whether a path is a
+/// directory; otherwise a trailing slash or a missing extension does.
+pub fn classify_link(
+    dest: &str,
+    base_dir: &[String],
+    filesystem: Option<&FileSystem>,
+) -> LinkTarget {
+    if is_external(dest) {
+        return LinkTarget::External(dest.to_string());
+    }
+    if dest.is_empty() || dest.starts_with('#') || dest.starts_with('?') || dest.contains(':') {
+        return LinkTarget::Unchanged;
+    }
+
+    // Drop any query or fragment from the filesystem path; `view` keeps the fragment
+    let path = dest.split(['#', '?']).next().unwrap_or(dest);
+    let fragment = dest
+        .split_once('#')
+        .map(|(_, fragment)| fragment)
+        .filter(|fragment| !fragment.is_empty());
+    let trailing_slash = path.ends_with('/');
+    let components: Vec<String> = resolve_path(base_dir, path.trim_end_matches('/'))
+        .into_iter()
+        .filter(|component| !component.is_empty())
+        .collect();
+    let absolute = path_to_string(&components);
+
+    let is_directory = match filesystem.and_then(|fs| fs.get_file_info(&absolute).ok()) {
+        Some(node) => node.node_type == "directory",
+        None => trailing_slash || components.last().is_none_or(|name| !name.contains('.')),
+    };
+
+    let (command, href) = if is_directory {
+        (
+            format!("navigate {}", CommandExecutor::quote_arg(&absolute)),
+            format!("{}{}", get_base_path().trim_end_matches('/'), absolute),
+        )
+    } else if absolute.ends_with(".md") || absolute.ends_with(".markdown") {
+        let command = match fragment {
+            Some(fragment) => format!(
+                "view {}",
+                CommandExecutor::quote_arg(&format!("{}#{}", absolute, fragment))
+            ),
+            None => format!("view {}", CommandExecutor::quote_arg(&absolute)),
+        };
+        (command, build_data_path(&absolute))
+    } else {
+        (
+            format!("cat {}", CommandExecutor::quote_arg(&absolute)),
+            build_data_path(&absolute),
+        )
+    };
+
+    LinkTarget::Command { command, href }
+}
+
+/// Resolve an image or other asset reference against the file's directory
+///
+/// Returns the data URL path for the asset, or None when the reference
+/// should be left alone (external URLs, `data:` URIs, anchors, or paths
+/// already under the data directory).
+pub fn resolve_asset_path(src: &str, base_dir: &[String]) -> Option<String> {
+    if src.is_empty() || is_external(src) || src.starts_with('#') || src.contains(':') {
+        return None;
+    }
+    if src.starts_with(&build_data_path("")) {
+        return None;
+    }
+
+    let (path, suffix) = match src.find(['?', '#']) {
+        Some(index) => src.split_at(index),
+        None => (src, ""),
+    };
+    let components = resolve_path(base_dir, path);
+    Some(format!(
+        "{}{}",
+        build_data_path(&components.join("/")),
+        suffix
+    ))
+}
+
+/// Rewrite relative `src`/`poster` attributes in raw HTML from a markdown file
+pub fn rewrite_html_assets(html: &str, base_dir: &[String]) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some((start, quote)) = find_asset_attribute(rest) {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(quote) else {
+            break;
+        };
+        let value = &rest[..end];
+        output.push_str(&resolve_asset_path(value, base_dir).unwrap_or_else(|| value.to_string()));
+        rest = &rest[end..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Find the start of the next asset attribute value and its quote character
+fn find_asset_attribute(html: &str) -> Option<(usize, char)> {
+    ["src=\"", "src='", "poster=\"", "poster='"]
+        .iter()
+        .filter_map(|attr| {
+            let quote = attr.chars().last().unwrap_or('"');
+            html.match_indices(attr)
+                // Only whole attribute names, e.g. not `data-src=`
+                .find(|(index, _)| {
+                    html[..*index]
+                        .chars()
+                        .last()
+                        .is_some_and(|c| c.is_whitespace())
+                })
+                .map(|(index, _)| (index + attr.len(), quote))
+        })
+        .min_by_key(|(index, _)| *index)
+}
+
+/// Directory components of a data file path, e.g. `posts/demo.md` -> `["posts"]`
+pub fn parent_dir(file_path: &str) -> Vec<String> {
+    let mut components: Vec<String> = file_path
+        .split('/')
+        .filter(|component| !component.is_empty())
+        .map(|component| component.to_string())
+        .collect();
+    components.pop();
+    components
+}
+
+fn is_external(dest: &str) -> bool {
+    dest.starts_with("//") || dest.starts_with("http://") || dest.starts_with("https://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(dest: &str, base: &str, filesystem: Option<&FileSystem>) -> String {
+        match classify_link(dest, &parent_dir(base), filesystem) {
+            LinkTarget::Command { command, .. } => command,
+            other => panic!("expected a command for {}, got {:?}", dest, other),
+        }
+    }
+
+    #[test]
+    fn test_relative_links_resolve_against_file_directory() {
+        assert_eq!(
+            command("../posts/demo.md", "about/index.md", None),
+            "view /posts/demo.md"
+        );
+        assert_eq!(
+            command("demo.md#intro", "about/index.md", None),
+            "view /about/demo.md#intro"
+        );
+        assert_eq!(command("/about/", "posts/a.md", None), "navigate /about");
+        assert_eq!(command("../a.js", "about/index.md", None), "cat /a.js");
+        assert_eq!(
+            command("my notes.md#a>b", "index.md", None),
+            "view '/my notes.md#a>b'"
+        );
+    }
+
+    #[test]
+    fn test_filesystem_decides_directories() {
+        let fs = FileSystem::new();
+        assert_eq!(command("about", "index.md", Some(&fs)), "navigate /about");
+        assert_eq!(command("./a.js", "index.md", Some(&fs)), "cat /a.js");
+    }
+
+    #[test]
+    fn test_asset_paths_resolve_against_file_directory() {
+        let base = parent_dir("posts/blog.md");
+        assert_eq!(
+            resolve_asset_path("./img/arch.png", &base).as_deref(),
+            Some(build_data_path("posts/img/arch.png").as_str())
+        );
+        assert_eq!(
+            resolve_asset_path("../shared/logo.svg?v=2", &base),
+            Some(format!("{}?v=2", build_data_path("shared/logo.svg")))
+        );
+        assert_eq!(resolve_asset_path("https://example.com/a.png", &base), None);
+        assert_eq!(
+            resolve_asset_path("data:image/png;base64,AAAA", &base),
+            None
+        );
+
+        let html = rewrite_html_assets(
+            r#"<video data-src="x" src="clip.mp4" poster='poster.png'></video>"#,
+            &base,
+        );
+        assert!(html.contains(&format!(r#"src="{}""#, build_data_path("posts/clip.mp4"))));
+        assert!(html.contains(&format!("poster='{}'", build_data_path("posts/poster.png"))));
+        assert!(html.contains(r#"data-src="x""#));
+    }
+
+    #[test]
+    fn test_external_and_anchor_links() {
+        assert_eq!(
+            classify_link("https://example.com", &[], None),
+            LinkTarget::External("https://example.com".to_string())
+        );
+        assert_eq!(classify_link("#section", &[], None), LinkTarget::Unchanged);
+        assert_eq!(
+            classify_link("mailto:a@b.c", &[], None),
+            LinkTarget::Unchanged
+        );
+    }
+}
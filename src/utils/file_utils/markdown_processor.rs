@@ -1,55 +1,127 @@
 // Markdown Processing
 // Renders markdown to HTML with syntax highlighting and command execution
 
-use crate::commands::{CommandExecutor, CommandResult, TerminalContext};
-use crate::utils::{syntax_highlighter::SyntaxHighlighter, AppConfigService};
+use super::code_fence::CodeFence;
+use super::extensions::apply_extensions;
+use super::frontmatter::{strip_frontmatter, Frontmatter};
+use super::include::expand_includes;
+use super::link_rewriter::{
+    classify_link, parent_dir, resolve_asset_path, rewrite_html_assets, LinkTarget,
+};
+use super::page_meta::{render_page_meta, PageMeta};
+use super::run_block::render_run_block;
+use super::toc::{extract_headings, heading_level, render_toc};
+use crate::commands::CommandExecutor;
+use crate::utils::app_config::MarkdownConfig;
+use crate::utils::html_sanitizer::sanitize_raw_html;
+use crate::utils::syntax_highlighter::SyntaxHighlighter;
 use pulldown_cmark::{html, CodeBlockKind, Event, Options, Parser, Tag};
 
+/// Fence languages whose code blocks get a "run in terminal" button
+const RUNNABLE_LANGUAGES: [&str; 4] = ["sh", "bash", "shell", "zzhack"];
+
+/// Options for rendering a markdown file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderOptions {
+    /// Prepend a table of contents (also enabled by `toc: true` frontmatter)
+    pub toc: bool,
+    /// Heading id to scroll to once the output is shown
+    pub anchor: Option<String>,
+    /// Extensions enabled in app.json
+    pub markdown: MarkdownConfig,
+    /// File metadata for the header above the content (hidden by `hide_meta: true`)
+    pub meta: Option<PageMeta>,
+}
+
 /// Fetch and render markdown file content to HTML with command execution support
+///
+/// The file is looked up in the executor's filesystem, so files written in
+/// the overlay render too. `{{#include}}` and `{{#exec}}` directives are
+/// expanded before rendering; raw HTML is never trusted once an overlay
+/// file is included.
 pub async fn fetch_and_render_markdown_with_executor(
     file_path: &str,
     executor: &CommandExecutor,
+    options: &RenderOptions,
 ) -> Result<String, String> {
-    let content = super::fetcher::fetch_file_content(file_path).await?;
-    Ok(render_markdown_to_html(&content, Some(executor)))
+    let source = executor
+        .filesystem
+        .borrow()
+        .get_file_info(&format!("/{}", file_path.trim_start_matches('/')))
+        .map(|node| node.content_source())?;
+    let content = source.read().await?;
+    let expanded = expand_includes(&content, file_path, executor).await;
+    let mut options = options.clone();
+    options.markdown.trust_raw_html &= !expanded.includes_overlay;
+    let html = render_markdown_to_html(&expanded.markdown, file_path, Some(executor), &options);
+    Ok(expanded.fill_outputs(html))
 }
 
-/// Render markdown content to HTML with syntax highlighting and optional command execution
-pub fn render_markdown_to_html(markdown_input: &str, executor: Option<&CommandExecutor>) -> String {
-    // Remove frontmatter metadata before parsing
-    let content_without_metadata = strip_frontmatter(markdown_input);
-
+/// Markdown extensions enabled for rendering
+pub(super) fn markdown_options(config: &MarkdownConfig) -> Options {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_STRIKETHROUGH);
     options.insert(Options::ENABLE_TABLES);
     options.insert(Options::ENABLE_FOOTNOTES);
     options.insert(Options::ENABLE_TASKLISTS);
+    if config.heading_attributes {
+        options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+    }
+    options
+}
+
+/// Render markdown content to HTML with syntax highlighting and optional command execution
+///
+/// `file_path` is the data path of the rendered file (e.g. `posts/demo.md`);
+/// relative links are resolved against its directory. Headings get slug ids
+/// (see `toc::extract_headings`) so they can be linked to.
+pub fn render_markdown_to_html(
+    markdown_input: &str,
+    file_path: &str,
+    executor: Option<&CommandExecutor>,
+    render_options: &RenderOptions,
+) -> String {
+    // Remove frontmatter metadata before parsing
+    let frontmatter = Frontmatter::parse(markdown_input);
+    let content_without_metadata = strip_frontmatter(markdown_input);
+    let options = markdown_options(&render_options.markdown);
 
     let parser = Parser::new_ext(&content_without_metadata, options);
-    let highlighter = SyntaxHighlighter::new();
+    let highlighter = SyntaxHighlighter::shared();
 
     // Process events to add syntax highlighting
-    let events: Vec<Event> = parser.collect();
+    let events = apply_extensions(parser.collect(), &render_options.markdown);
     let mut processed_events = Vec::new();
 
     let mut in_code_block = false;
-    let mut code_block_lang = String::new();
+    let mut code_block_fence = CodeFence::default();
     let mut code_block_content = String::new();
+    let base_dir = parent_dir(file_path);
+    // Whether each open link was replaced with custom HTML
+    let mut rewritten_links: Vec<bool> = Vec::new();
+    // Image being rendered: (src, title, alt text collected so far)
+    let mut image: Option<(String, String, String)> = None;
+    let headings = extract_headings(markdown_input, &render_options.markdown);
+    let mut heading_ids = headings.iter().map(|heading| heading.id.as_str());
 
     for event in events {
         match event {
-            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang))) => {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
                 in_code_block = true;
-                code_block_lang = lang.to_string();
+                code_block_fence = CodeFence::parse(&info);
                 code_block_content.clear();
                 // Don't push the original start tag, we'll create our own
             }
             Event::End(Tag::CodeBlock(CodeBlockKind::Fenced(_))) => {
                 if in_code_block {
-                    let highlighted_html = if code_block_lang == "run" {
+                    let highlighted_html = if code_block_fence.language == "run" {
                         // Handle executable code block
                         if let Some(exec) = executor {
-                            execute_run_block(&code_block_content, exec)
+                            render_run_block(
+                                &code_block_content,
+                                code_block_fence.has_flag("lazy"),
+                                exec,
+                            )
                         } else {
                             // Fallback to regular code block for run blocks without executor
                             format!(
@@ -57,31 +129,103 @@ pub fn render_markdown_to_html(markdown_input: &str, executor: Option<&CommandEx
                                 html_escape(&code_block_content)
                             )
                         }
-                    } else if code_block_lang.is_empty() {
-                        // No language specified, just escape HTML - use regular markdown rendering
-                        format!(
-                            "<pre><code>{}</code></pre>",
-                            html_escape(&code_block_content)
-                        )
                     } else {
-                        // Apply syntax highlighting - use regular markdown rendering
-                        highlighter.highlight_code(&code_block_content, &code_block_lang)
+                        let code_html = if code_block_fence.has_line_features() {
+                            render_code_lines(&code_block_fence, &code_block_content, highlighter)
+                        } else if code_block_fence.language.is_empty() {
+                            // No language specified, just escape HTML - use regular markdown rendering
+                            format!(
+                                "<pre><code>{}</code></pre>",
+                                html_escape(&code_block_content)
+                            )
+                        } else {
+                            // Apply syntax highlighting - use regular markdown rendering
+                            highlighter
+                                .highlight_code(&code_block_content, &code_block_fence.language)
+                        };
+                        wrap_code_block(&code_block_fence, &code_block_content, &code_html)
                     };
 
                     // Push as raw HTML
                     processed_events.push(Event::Html(highlighted_html.into()));
 
                     in_code_block = false;
-                    code_block_lang.clear();
                     code_block_content.clear();
                 }
                 // Don't push the original end tag
             }
+            Event::Start(Tag::Image(_, dest, title)) => {
+                let src = resolve_asset_path(&dest, &base_dir).unwrap_or_else(|| dest.to_string());
+                image = Some((src, title.to_string(), String::new()));
+            }
+            Event::End(Tag::Image(..)) => {
+                if let Some((src, title, alt)) = image.take() {
+                    let html = image_html(&src, &title, &alt, &frontmatter);
+                    processed_events.push(Event::Html(html.into()));
+                }
+            }
+            Event::Text(text) | Event::Code(text) if image.is_some() => {
+                if let Some((_, _, alt)) = image.as_mut() {
+                    alt.push_str(&text);
+                }
+            }
+            _ if image.is_some() => {
+                // Formatting inside alt text is dropped, only its text is kept
+            }
+            Event::Start(Tag::Heading(level, _, classes)) => {
+                let class_attr = if classes.is_empty() {
+                    String::new()
+                } else {
+                    format!(" class=\"{}\"", html_escape(&classes.join(" ")))
+                };
+                processed_events.push(Event::Html(
+                    format!(
+                        "<h{} id=\"{}\"{}>",
+                        heading_level(level),
+                        html_escape(heading_ids.next().unwrap_or_default()),
+                        class_attr
+                    )
+                    .into(),
+                ));
+            }
+            Event::End(Tag::Heading(level, ..)) => {
+                processed_events.push(Event::Html(format!("</h{}>", heading_level(level)).into()));
+            }
+            Event::Html(html) => {
+                let html = rewrite_html_assets(&html, &base_dir);
+                let html = if render_options.markdown.trust_raw_html {
+                    html
+                } else {
+                    sanitize_raw_html(&html)
+                };
+                processed_events.push(Event::Html(html.into()));
+            }
             Event::Text(text) if in_code_block => {
                 // Collect code block content
                 code_block_content.push_str(&text);
                 // Don't push the text event, we'll handle it when the block ends
             }
+            Event::Start(Tag::Link(link_type, dest, title)) => {
+                let filesystem = executor.map(|exec| exec.filesystem.borrow());
+                let target = classify_link(&dest, &base_dir, filesystem.as_deref());
+                match link_start_html(&target, &title) {
+                    Some(html) => {
+                        processed_events.push(Event::Html(html.into()));
+                        rewritten_links.push(true);
+                    }
+                    None => {
+                        processed_events.push(Event::Start(Tag::Link(link_type, dest, title)));
+                        rewritten_links.push(false);
+                    }
+                }
+            }
+            Event::End(Tag::Link(link_type, dest, title)) => {
+                if rewritten_links.pop().unwrap_or(false) {
+                    processed_events.push(Event::Html("</a>".into()));
+                } else {
+                    processed_events.push(Event::End(Tag::Link(link_type, dest, title)));
+                }
+            }
             _ => {
                 // For all other events, pass them through unchanged
                 processed_events.push(event);
@@ -93,95 +237,308 @@ pub fn render_markdown_to_html(markdown_input: &str, executor: Option<&CommandEx
     let mut html_output = String::new();
     html::push_html(&mut html_output, processed_events.into_iter());
 
-    // Wrap the rendered HTML in a div with markdown-content class
-    format!("<div class=\"markdown-content\">{}</div>", html_output)
-}
-
-/// Strip frontmatter metadata from markdown content
-fn strip_frontmatter(markdown_input: &str) -> String {
-    // Check if content starts with frontmatter delimiter
-    if !markdown_input.starts_with("--\n") && !markdown_input.starts_with("---\n") {
-        return markdown_input.to_string();
+    if render_options.toc || frontmatter.get("toc") == Some("true") {
+        html_output.insert_str(0, &render_toc(&headings));
     }
-
-    // Find the closing delimiter
-    let lines: Vec<&str> = markdown_input.lines().collect();
-    if lines.is_empty() {
-        return markdown_input.to_string();
+    if let Some(meta) = &render_options.meta {
+        html_output.insert_str(0, &render_page_meta(meta, markdown_input));
     }
 
-    // Determine the closing delimiter pattern
-    let delimiter = if lines[0] == "---" { "---" } else { "--" };
+    // Wrap the rendered HTML in a div with markdown-content class
+    let scroll_attr = render_options
+        .anchor
+        .as_ref()
+        .map(|anchor| format!(" data-scroll-target=\"{}\"", html_escape(anchor)))
+        .unwrap_or_default();
+    format!(
+        "<div class=\"markdown-content\"{}>{}</div>",
+        scroll_attr, html_output
+    )
+}
+
+/// Render a code block line by line for fence attributes
+///
+/// Produces a line-number gutter, highlighted lines and `+`/`-` diff
+/// coloring around the syntax-highlighted code.
+fn render_code_lines(fence: &CodeFence, code: &str, highlighter: &SyntaxHighlighter) -> String {
+    let lines = if fence.language.is_empty() {
+        code.lines().map(html_escape).collect()
+    } else {
+        highlighter.highlight_lines(code, &fence.language)
+    };
+    let raw_lines: Vec<&str> = code.lines().collect();
+    let first_number = fence.line_numbers.unwrap_or(1);
+    let gutter_width = (first_number + lines.len()).to_string().len();
 
-    // Find the end of the frontmatter
-    for (i, line) in lines.iter().enumerate().skip(1) {
-        if *line == delimiter {
-            // Found closing delimiter, return content after it
-            if i + 1 < lines.len() {
-                return lines[i + 1..].join("\n");
-            } else {
-                return String::new(); // No content after frontmatter
+    let mut body = String::new();
+    for (index, line_html) in lines.iter().enumerate() {
+        let mut classes = vec!["code-line"];
+        if fence.is_highlighted(index + 1) {
+            classes.push("highlighted");
+        }
+        if fence.diff {
+            match raw_lines.get(index).and_then(|line| line.chars().next()) {
+                Some('+') => classes.push("diff-add"),
+                Some('-') => classes.push("diff-remove"),
+                _ => {}
             }
         }
+
+        body.push_str(&format!("<span class=\"{}\">", classes.join(" ")));
+        if fence.line_numbers.is_some() {
+            body.push_str(&format!(
+                "<span class=\"line-number\">{:>width$}</span>",
+                first_number + index,
+                width = gutter_width
+            ));
+        }
+        // Keep empty lines one line tall, including highlighted ones that
+        // are left with only their scope spans
+        body.push_str(line_html);
+        if SyntaxHighlighter::is_only_tags(line_html) {
+            body.push(' ');
+        }
+        body.push_str("</span>");
     }
 
-    // No closing delimiter found, return original content
-    markdown_input.to_string()
+    let pre_class = if fence.language.is_empty() { "code-lines" } else { "syn-code code-lines" };
+    format!("<pre class=\"{}\"><code>{}</code></pre>", pre_class, body)
 }
 
-/// Execute a "run" code block with the given executor
-fn execute_run_block(code: &str, executor: &CommandExecutor) -> String {
-    let mut output = String::new();
-    let commands: Vec<&str> = code.lines().filter(|line| !line.trim().is_empty()).collect();
+/// Wrap a rendered code block with its filename header and action buttons
+///
+/// The raw code is kept in `data-code` for the copy button, and shell blocks
+/// get a run button; both are handled by the terminal's click handler.
+fn wrap_code_block(fence: &CodeFence, code: &str, code_html: &str) -> String {
+    let title = fence
+        .title
+        .as_ref()
+        .map(|title| format!("<div class=\"code-title\">{}</div>", html_escape(title)))
+        .unwrap_or_default();
 
-    for command_line in commands {
-        let command_line = command_line.trim();
-        if command_line.is_empty() {
-            continue;
-        }
+    let run_button = if RUNNABLE_LANGUAGES.contains(&fence.language.as_str()) {
+        r#"<button type="button" class="code-action" data-code-action="run" title="Run in terminal">run</button>"#
+    } else {
+        ""
+    };
 
-        // Create a minimal context for command execution
-        let context = TerminalContext {
-            clear_screen: std::rc::Rc::new(|| {}),
-            command_executor: executor,
-            execute: std::rc::Rc::new(|_| {
-                CommandResult::Error("Nested execute not supported in run blocks".to_string())
-            }),
-            app_config: AppConfigService::new(),
-            set_theme: None,
-        };
+    format!(
+        r#"<div class="code-block" data-code="{}">{}<div class="code-actions">{}<button type="button" class="code-action" data-code-action="copy" title="Copy to clipboard">copy</button></div>{}</div>"#,
+        html_escape(code),
+        title,
+        run_button,
+        code_html
+    )
+}
 
-        let result = executor.execute(command_line, &context);
-        
-        output.push_str(&format!("<div class=\"command-line\">$ {}</div>", html_escape(command_line)));
-        
-        match result {
-            CommandResult::Success(out) => {
-                if !out.is_empty() {
-                    output.push_str(&format!("<div class=\"command-output\">{}</div>", html_escape(&out)));
-                }
-            }
-            CommandResult::Error(err) => {
-                output.push_str(&format!("<div class=\"command-error\">Error: {}</div>", html_escape(&err)));
-            }
-            CommandResult::Html(html_content) => {
-                output.push_str(&format!("<div class=\"command-html\">{}</div>", html_content));
-            }
-            CommandResult::Async(_) => {
-                output.push_str("<div class=\"command-error\">Async commands not supported in run blocks</div>");
-            }
+/// Commands sent to the terminal by a code block's run button
+///
+/// Blank lines and `#` comments are skipped, and a leading `$ ` prompt is
+/// removed so copied shell transcripts run as-is.
+pub fn runnable_commands(code: &str) -> Vec<String> {
+    code.lines()
+        .map(|line| line.trim())
+        .map(|line| line.strip_prefix("$ ").unwrap_or(line).trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Opening `<a>` tag for a rewritten link, or None to keep the default rendering
+///
+/// Filesystem links carry a `data-command` that the terminal runs on click;
+/// external links open in a new tab.
+fn link_start_html(target: &LinkTarget, title: &str) -> Option<String> {
+    let title_attr = if title.is_empty() {
+        String::new()
+    } else {
+        format!(" title=\"{}\"", html_escape(title))
+    };
+
+    match target {
+        LinkTarget::External(href) => Some(format!(
+            r#"<a href="{}" target="_blank" rel="noopener"{}>"#,
+            html_escape(href),
+            title_attr
+        )),
+        LinkTarget::Command { command, href } => Some(format!(
+            r#"<a href="{}" data-command="{}"{}>"#,
+            html_escape(href),
+            html_escape(command),
+            title_attr
+        )),
+        LinkTarget::Unchanged => None,
+    }
+}
+
+/// `<img>` tag for a markdown image
+///
+/// Images load lazily; `image_width` and `image_height` frontmatter fields
+/// set their default size.
+fn image_html(src: &str, title: &str, alt: &str, frontmatter: &Frontmatter) -> String {
+    let mut attributes = format!(
+        r#"src="{}" alt="{}" loading="lazy""#,
+        html_escape(src),
+        html_escape(alt)
+    );
+    if !title.is_empty() {
+        attributes.push_str(&format!(r#" title="{}""#, html_escape(title)));
+    }
+    for (field, attribute) in [("image_width", "width"), ("image_height", "height")] {
+        if let Some(value) = frontmatter.get(field) {
+            attributes.push_str(&format!(r#" {}="{}""#, attribute, html_escape(value)));
         }
-        output.push('\n');
     }
 
-    format!("<div class=\"run-block\">{}</div>", output)
+    format!("<img {} />", attributes)
 }
 
 /// Escape HTML special characters
-fn html_escape(text: &str) -> String {
-    text.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&#x27;")
-}
\ No newline at end of file
+pub(super) use crate::utils::html_sanitizer::escape_html as html_escape;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{block_on, overlay_files};
+
+    #[test]
+    fn test_code_blocks_get_actions() {
+        let html = render_markdown_to_html(
+            "```bash\nls\n```\n\n```rust\nfn main() {}\n```\n",
+            "index.md",
+            None,
+            &RenderOptions::default(),
+        );
+
+        assert_eq!(html.matches(r#"data-code-action="copy""#).count(), 2);
+        assert_eq!(html.matches(r#"data-code-action="run""#).count(), 1);
+        assert!(html.contains(r#"data-code="fn main() {}"#));
+    }
+
+    #[test]
+    fn test_blank_code_lines_keep_their_height() {
+        let html = render_markdown_to_html(
+            "```rust {1}\nfn a() {}\n\nfn b() {}\n```\n",
+            "index.md",
+            None,
+            &RenderOptions::default(),
+        );
+
+        let lines: Vec<&str> = html.split(r#"<span class="code-line"#).skip(1).collect();
+        assert_eq!(lines.len(), 3);
+        assert!(!html.contains("line-number"));
+        assert!(lines[1].starts_with(r#""><span class="syn-"#));
+        assert!(lines[1].contains(" </span>"));
+    }
+
+    #[test]
+    fn test_links_become_commands() {
+        let html = render_markdown_to_html(
+            "[demo](../posts/demo.md) and [site](https://example.com)",
+            "about/index.md",
+            None,
+            &RenderOptions::default(),
+        );
+
+        assert!(html.contains(r#"data-command="view /posts/demo.md">demo</a>"#));
+        assert!(html.contains(r#"<a href="https://example.com" target="_blank" rel="noopener">"#));
+    }
+
+    #[test]
+    fn test_raw_html_cannot_add_commands() {
+        let html = render_markdown_to_html(
+            "<a href=\"x.md\" data-command=\"rm -r /\" id=\"x\">x</a> and [y](y.md)",
+            "index.md",
+            None,
+            &RenderOptions::default(),
+        );
+
+        assert!(html.contains(r#"<a href="x.md">x</a>"#));
+        assert_eq!(html.matches("data-command=").count(), 1);
+    }
+
+    #[test]
+    fn test_included_overlay_html_is_never_trusted() {
+        let executor = CommandExecutor::new();
+        overlay_files(
+            &executor,
+            &[
+                (
+                    "page.md",
+                    "<i data-command=\"ls\">a</i>\n\n{{#include raw.md}}",
+                ),
+                ("raw.md", "<b data-command=\"rm -r /\">b</b>"),
+                ("plain.md", "<i data-command=\"ls\">a</i>"),
+            ],
+        );
+        let mut options = RenderOptions::default();
+        options.markdown.trust_raw_html = true;
+        let render = |file| {
+            block_on(fetch_and_render_markdown_with_executor(
+                file, &executor, &options,
+            ))
+            .unwrap()
+        };
+
+        assert!(render("plain.md").contains(r#"data-command="ls""#));
+        let html = render("page.md");
+        assert!(html.contains("<b>b</b>"));
+        assert!(!html.contains("data-command"));
+    }
+
+    #[test]
+    fn test_images_resolve_relative_to_file() {
+        let html = render_markdown_to_html(
+            "---\nimage_width: 640\n---\n![The *architecture*](./img/arch.png \"Arch\")",
+            "posts/blog.md",
+            None,
+            &RenderOptions::default(),
+        );
+
+        assert!(html.contains(&format!(
+            r#"<img src="{}" alt="The architecture" loading="lazy" title="Arch" width="640" />"#,
+            crate::utils::config::build_data_path("posts/img/arch.png")
+        )));
+    }
+
+    #[test]
+    fn test_headings_get_ids_and_toc() {
+        let html = render_markdown_to_html(
+            "---\ntoc: true\n---\n# Guide\n## Install `view`\n",
+            "posts/guide.md",
+            None,
+            &RenderOptions {
+                toc: false,
+                anchor: Some("install-view".to_string()),
+                ..RenderOptions::default()
+            },
+        );
+
+        assert!(html.starts_with(r#"<div class="markdown-content" data-scroll-target="install-view"><nav class="toc">"#));
+        assert!(html.contains(r##"<a href="#install-view">Install view</a>"##));
+        assert!(html.contains(r#"<h2 id="install-view">Install <code>view</code></h2>"#));
+    }
+
+    #[test]
+    fn test_heading_attributes_are_configurable() {
+        let markdown = "## Setup {#install .wide}\n";
+        let html = render_markdown_to_html(markdown, "index.md", None, &RenderOptions::default());
+        assert!(html.contains(r#"<h2 id="install" class="wide">Setup</h2>"#));
+
+        let options = RenderOptions {
+            markdown: MarkdownConfig {
+                heading_attributes: false,
+                ..MarkdownConfig::default()
+            },
+            ..RenderOptions::default()
+        };
+        let html = render_markdown_to_html(markdown, "index.md", None, &options);
+        assert!(html.contains(r#"<h2 id="setup-install-wide">Setup {#install .wide}</h2>"#));
+    }
+
+    #[test]
+    fn test_runnable_commands_skip_comments_and_prompts() {
+        let commands = runnable_commands("# list posts\n$ ls /posts\n\ncat README.md\n");
+        assert_eq!(commands, vec!["ls /posts", "cat README.md"]);
+    }
+}
@@ -1,9 +1,18 @@
 // File Operations Module
 // Modular file handling with separate concerns
 
+pub mod ansi_renderer;
+pub mod code_fence;
+pub mod extensions;
 pub mod fetcher;
+pub mod frontmatter;
+pub mod include;
+pub mod link_rewriter;
 pub mod markdown_processor;
+pub mod page_meta;
+pub mod run_block;
+pub mod toc;
 
 // Re-export main functions for backward compatibility
-pub use fetcher::fetch_file_content;
+pub use fetcher::{fetch_file_content, fetch_file_content_cached};
 pub use markdown_processor::fetch_and_render_markdown_with_executor;
\ No newline at end of file
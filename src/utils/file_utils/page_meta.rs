@@ -0,0 +1,252 @@
+// Page Metadata Header
+// Title, date, reading time and tags shown above a viewed markdown file
+
+use super::frontmatter::{strip_frontmatter, Frontmatter};
+use super::markdown_processor::html_escape;
+use crate::commands::CommandExecutor;
+use crate::filesystem::types::FileSystemNode;
+use pulldown_cmark::{Event, Parser, Tag};
+
+/// Words read per minute for space-separated scripts
+const WORDS_PER_MINUTE: usize = 200;
+/// Characters read per minute for Chinese, Japanese and Korean
+const CJK_CHARS_PER_MINUTE: usize = 400;
+
+/// Metadata of a viewed file, from the filesystem metadata and frontmatter
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageMeta {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Date shown in the header, `YYYY-MM-DD` when it comes from the file's mtime
+    pub date: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl PageMeta {
+    /// Metadata recorded for a file by generate_metadata.js
+    pub fn from_node(node: &FileSystemNode) -> Self {
+        Self {
+            title: node.title.clone(),
+            description: node.description.clone(),
+            date: node
+                .modified
+                .as_deref()
+                .and_then(|modified| modified.split('T').next())
+                .map(String::from),
+            tags: node.tags.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Fill in from frontmatter; an explicit `date` wins over the file's mtime
+    fn with_frontmatter(mut self, frontmatter: &Frontmatter) -> Self {
+        if let Some(date) = frontmatter.get("date") {
+            self.date = Some(date.to_string());
+        }
+        if self.title.is_none() {
+            self.title = frontmatter.get("title").map(String::from);
+        }
+        if self.description.is_none() {
+            self.description = frontmatter.get("description").map(String::from);
+        }
+        if self.tags.is_empty() {
+            self.tags = frontmatter
+                .get("tags")
+                .or_else(|| frontmatter.get("tag"))
+                .map(|tags| {
+                    tags.split(',')
+                        .map(|tag| tag.trim().to_string())
+                        .filter(|tag| !tag.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default();
+        }
+        self
+    }
+}
+
+/// Word count of a document, split by how the text is read
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WordCount {
+    /// Words separated by spaces or punctuation
+    pub words: usize,
+    /// CJK characters, each counted as a word
+    pub cjk: usize,
+}
+
+impl WordCount {
+    pub fn total(&self) -> usize {
+        self.words + self.cjk
+    }
+
+    /// Estimated reading time in whole minutes, at least one
+    pub fn reading_minutes(&self) -> usize {
+        let minutes = self.words as f64 / WORDS_PER_MINUTE as f64
+            + self.cjk as f64 / CJK_CHARS_PER_MINUTE as f64;
+        (minutes.ceil() as usize).max(1)
+    }
+}
+
+/// Count the words in a piece of text
+///
+/// Runs of letters and digits are words; each CJK character is a word of
+/// its own, since those scripts don't separate words with spaces.
+pub fn count_words(text: &str) -> WordCount {
+    let mut count = WordCount::default();
+    let mut in_word = false;
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            count.cjk += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() || (in_word && (c == '\'' || c == '’' || c == '-')) {
+            if !in_word {
+                count.words += 1;
+                in_word = true;
+            }
+        } else {
+            in_word = false;
+        }
+    }
+
+    count
+}
+
+/// Count the words of a markdown document's text, leaving out code blocks
+pub fn count_markdown_words(markdown_input: &str) -> WordCount {
+    let content = strip_frontmatter(markdown_input);
+    let mut count = WordCount::default();
+    let mut in_code_block = false;
+
+    for event in Parser::new(&content) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(Tag::CodeBlock(_)) => in_code_block = false,
+            Event::Text(text) | Event::Code(text) if !in_code_block => {
+                let text_count = count_words(&text);
+                count.words += text_count.words;
+                count.cjk += text_count.cjk;
+            }
+            _ => {}
+        }
+    }
+
+    count
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF | 0x20000..=0x2FFFF
+    )
+}
+
+/// Header with the page's title, description, date, reading time and tags
+///
+/// Returns an empty string when the frontmatter sets `hide_meta: true`.
+pub fn render_page_meta(meta: &PageMeta, markdown_input: &str) -> String {
+    let frontmatter = Frontmatter::parse(markdown_input);
+    if frontmatter.get("hide_meta") == Some("true") {
+        return String::new();
+    }
+    let meta = meta.clone().with_frontmatter(&frontmatter);
+    let count = count_markdown_words(markdown_input);
+
+    let mut html = String::from(r#"<header class="page-meta">"#);
+    if let Some(title) = &meta.title {
+        html.push_str(&format!(
+            r#"<div class="page-meta-title">{}</div>"#,
+            html_escape(title)
+        ));
+    }
+    if let Some(description) = &meta.description {
+        html.push_str(&format!(
+            r#"<div class="page-meta-description">{}</div>"#,
+            html_escape(description)
+        ));
+    }
+
+    let mut info = Vec::new();
+    if let Some(date) = &meta.date {
+        info.push(format!(
+            r#"<time datetime="{}">{}</time>"#,
+            html_escape(date),
+            html_escape(date)
+        ));
+    }
+    info.push(format!("<span>{} min read</span>", count.reading_minutes()));
+    info.push(format!("<span>{} words</span>", count.total()));
+    html.push_str(&format!(
+        r#"<div class="page-meta-info">{}</div>"#,
+        info.join(r#"<span class="page-meta-separator">·</span>"#)
+    ));
+
+    if !meta.tags.is_empty() {
+        let tags: String = meta
+            .tags
+            .iter()
+            .map(|tag| {
+                format!(
+                    r#"<button type="button" class="page-meta-tag" data-command="{}" title="Find files tagged {}">#{}</button>"#,
+                    html_escape(&tag_command(tag)),
+                    html_escape(tag),
+                    html_escape(tag)
+                )
+            })
+            .collect();
+        html.push_str(&format!(r#"<div class="page-meta-tags">{}</div>"#, tags));
+    }
+
+    html.push_str("</header>");
+    html
+}
+
+/// `find` command listing the files with a tag
+fn tag_command(tag: &str) -> String {
+    format!("find / --tag {}", CommandExecutor::quote_arg(tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_words_cjk_aware() {
+        let count = count_words("Hello, world! It's 2024. 你好，世界。こんにちは");
+        assert_eq!(count, WordCount { words: 4, cjk: 9 });
+        assert_eq!(count.total(), 13);
+        assert_eq!(count.reading_minutes(), 1);
+
+        let long = WordCount { words: 450, cjk: 0 };
+        assert_eq!(long.reading_minutes(), 3);
+    }
+
+    #[test]
+    fn test_markdown_word_count_skips_code_blocks() {
+        let count = count_markdown_words(
+            "---\ntitle: x\n---\n# Title\n\nSome `code` 文字\n\n```\nnot counted\n```\n",
+        );
+        assert_eq!(count, WordCount { words: 3, cjk: 2 });
+    }
+
+    #[test]
+    fn test_header_and_hide_meta() {
+        let meta = PageMeta {
+            title: Some("A <b>post</b>".to_string()),
+            date: Some("2024-05-01".to_string()),
+            tags: vec!["rust".to_string(), "web dev".to_string()],
+            ..PageMeta::default()
+        };
+
+        let html = render_page_meta(&meta, "---\ndate: 2024-06-01\n---\nhello world");
+        assert!(html.contains(r#"<div class="page-meta-title">A &lt;b&gt;post&lt;/b&gt;</div>"#));
+        assert!(html.contains(r#"<time datetime="2024-06-01">"#));
+        assert!(html.contains("<span>2 words</span>"));
+        assert!(html.contains(r#"data-command="find / --tag rust""#));
+        assert!(html.contains(r#"data-command="find / --tag &#x27;web dev&#x27;""#));
+
+        assert_eq!(
+            render_page_meta(&meta, "---\nhide_meta: true\n---\nhello"),
+            ""
+        );
+    }
+}
@@ -0,0 +1,344 @@
+// Run Blocks
+// Executes ```run code blocks in the reader's session, filling in async results as they resolve
+
+use super::include::{at_current_depth, ExecDepth};
+use super::markdown_processor::html_escape;
+use crate::commands::{CommandExecutor, CommandResult, TerminalContext};
+use crate::utils::AppConfigService;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+
+/// Future of an async command, as returned in `CommandResult::Async`
+type PendingResult = Pin<Box<dyn Future<Output = CommandResult>>>;
+
+thread_local! {
+    /// Counter for output element ids, unique across renders
+    static NEXT_OUTPUT_ID: Cell<usize> = const { Cell::new(0) };
+    /// Async outputs whose placeholder isn't in the document yet: None while
+    /// running, then the resolved HTML until the placeholder is rendered
+    static RESOLVED_OUTPUTS: RefCell<HashMap<String, Option<String>>> = RefCell::new(HashMap::new());
+}
+
+/// Render a ```run block
+///
+/// Commands run in order against the shared executor, so `cd` or `export`
+/// in a block affect the terminal session. The first async command (e.g.
+/// `cat` or `view`) and everything after it run in the background, and their
+/// output replaces a loading placeholder once resolved.
+///
+/// In lazy mode (```` ```run lazy ````) nothing runs at render time; each
+/// command is a button that runs it when clicked.
+///
+/// Blocks that `view` files with more run blocks count towards the same
+/// nesting limit as `{{#exec}}`, so a file viewing itself stops.
+pub fn render_run_block(code: &str, lazy: bool, executor: &CommandExecutor) -> String {
+    let commands: Vec<&str> = code
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    // Held while the commands start; spawned ones take the depth along
+    let depth = ExecDepth::enter();
+    if depth.is_none() && !lazy {
+        return format!(
+            "<div class=\"run-block\">{}</div>",
+            result_html(CommandResult::Error(
+                "run blocks nested too deeply".to_string()
+            ))
+        );
+    }
+
+    let mut output = String::new();
+    // The first async command's pending result, then the commands after it
+    let mut pending: Option<(PendingResult, String)> = None;
+    let mut deferred: Vec<(String, String)> = Vec::new();
+
+    for command_line in commands {
+        let id = next_output_id();
+
+        if lazy {
+            output.push_str(&format!(
+                r#"<button type="button" class="command-line run-lazy" data-code-action="run-inline" data-run-command="{}" data-run-target="{}" title="Click to run">$ {}</button>"#,
+                html_escape(command_line),
+                id,
+                html_escape(command_line)
+            ));
+            output.push_str(&format!(r#"<div class="run-output" id="{}"></div>"#, id));
+            output.push('\n');
+            continue;
+        }
+
+        output.push_str(&format!(
+            "<div class=\"command-line\">$ {}</div>",
+            html_escape(command_line)
+        ));
+
+        // Once a command has gone async, later ones wait for it
+        let result_html = if pending.is_none() {
+            match execute(command_line, executor) {
+                CommandResult::Async(future) => {
+                    pending = Some((future, id.clone()));
+                    track_output(&id);
+                    loading_html()
+                }
+                result => result_html(result),
+            }
+        } else {
+            deferred.push((command_line.to_string(), id.clone()));
+            track_output(&id);
+            loading_html()
+        };
+
+        output.push_str(&format!(
+            r#"<div class="run-output" id="{}">{}</div>"#,
+            id, result_html
+        ));
+        output.push('\n');
+    }
+
+    if let Some((future, first_id)) = pending {
+        let executor = executor.clone();
+        wasm_bindgen_futures::spawn_local(at_current_depth(async move {
+            resolve_output(&first_id, awaited_html(future.await));
+            for (command_line, id) in deferred {
+                let html = run_to_html(&command_line, &executor).await;
+                resolve_output(&id, html);
+            }
+        }));
+    }
+
+    let class = if lazy { "run-block lazy" } else { "run-block" };
+    format!("<div class=\"{}\">{}</div>", class, output)
+}
+
+/// Run a single command from a lazy block and show its output in `target_id`
+pub fn run_into(command_line: &str, target_id: &str, executor: &CommandExecutor) {
+    let command_line = command_line.to_string();
+    let target_id = target_id.to_string();
+    let executor = executor.clone();
+
+    resolve_output(&target_id, loading_html());
+    wasm_bindgen_futures::spawn_local(async move {
+        let html = run_to_html(&command_line, &executor).await;
+        resolve_output(&target_id, html);
+    });
+}
+
+/// Fill placeholders under `root` whose output resolved before they were in the document
+///
+/// Called after rendered markdown is inserted into the page.
+pub fn fill_resolved_outputs(root: &web_sys::Element) {
+    let Ok(placeholders) = root.query_selector_all(".run-output[id]") else {
+        return;
+    };
+
+    for index in 0..placeholders.length() {
+        let Some(element) = placeholders
+            .item(index)
+            .and_then(|node| node.dyn_into::<web_sys::Element>().ok())
+        else {
+            continue;
+        };
+        // Still running outputs now find their placeholder themselves
+        let html = RESOLVED_OUTPUTS.with(|outputs| outputs.borrow_mut().remove(&element.id()));
+        if let Some(Some(html)) = html {
+            element.set_inner_html(&html);
+            fill_resolved_outputs(&element);
+        }
+    }
+}
+
+/// Forget the outputs of run blocks in HTML that won't be shown, because
+/// it was piped or redirected
+///
+/// Their results are dropped when they resolve instead of being kept for
+/// a placeholder that never appears.
+pub fn discard_outputs(html: &str) {
+    RESOLVED_OUTPUTS.with(|outputs| {
+        let mut outputs = outputs.borrow_mut();
+        if outputs.is_empty() {
+            return;
+        }
+        for id in output_ids(html) {
+            outputs.remove(id);
+        }
+    });
+}
+
+/// Ids of the run output placeholders in rendered HTML
+fn output_ids(html: &str) -> impl Iterator<Item = &str> {
+    html.match_indices(r#"id="run-output-"#)
+        .filter_map(|(start, _)| {
+            let id = &html[start + 4..];
+            id.find('"').map(|end| &id[..end])
+        })
+}
+
+fn next_output_id() -> String {
+    NEXT_OUTPUT_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        format!("run-output-{}", id)
+    })
+}
+
+/// Remember a running output until its placeholder is rendered
+fn track_output(id: &str) {
+    RESOLVED_OUTPUTS.with(|outputs| {
+        outputs.borrow_mut().insert(id.to_string(), None);
+    });
+}
+
+/// Show resolved output, or keep it until the placeholder is rendered
+///
+/// Output for a placeholder that was discarded, or already rendered and
+/// since removed (e.g. by `clear`), is dropped along with the outputs of
+/// any run blocks in it.
+fn resolve_output(id: &str, html: String) {
+    let element = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id(id));
+
+    if let Some(element) = element {
+        RESOLVED_OUTPUTS.with(|outputs| outputs.borrow_mut().remove(id));
+        element.set_inner_html(&html);
+        // Run blocks inside it may have resolved first
+        fill_resolved_outputs(&element);
+        return;
+    }
+    let dropped = RESOLVED_OUTPUTS.with(|outputs| match outputs.borrow_mut().get_mut(id) {
+        Some(output) => {
+            *output = Some(html);
+            None
+        }
+        None => Some(html),
+    });
+    if let Some(html) = dropped {
+        discard_outputs(&html);
+    }
+}
+
+/// Execute a command to completion, awaiting async results
+async fn run_to_html(command_line: &str, executor: &CommandExecutor) -> String {
+    result_html(run_to_result(command_line, executor).await)
+}
+
+/// Execute a command in the reader's session and await its result
+pub(super) async fn run_to_result(command_line: &str, executor: &CommandExecutor) -> CommandResult {
+    match execute(command_line, executor) {
+        CommandResult::Async(future) => match future.await {
+            CommandResult::Async(_) => {
+                CommandResult::Error("Nested async operations not supported".to_string())
+            }
+            result => result,
+        },
+        result => result,
+    }
+}
+
+/// HTML for the result of an awaited async command
+fn awaited_html(result: CommandResult) -> String {
+    match result {
+        CommandResult::Async(_) => result_html(CommandResult::Error(
+            "Nested async operations not supported".to_string(),
+        )),
+        result => result_html(result),
+    }
+}
+
+/// Execute a command with a context bound to the reader's session
+fn execute(command_line: &str, executor: &CommandExecutor) -> CommandResult {
+    let nested_executor = executor.clone();
+    let context = TerminalContext {
+        clear_screen: Rc::new(|| {}),
+        command_executor: executor,
+        execute: Rc::new(move |command_str: &str| {
+            let minimal_context = TerminalContext {
+                clear_screen: Rc::new(|| {}),
+                command_executor: &nested_executor,
+                execute: Rc::new(|_| {
+                    CommandResult::Error("Nested execute not supported".to_string())
+                }),
+                app_config: AppConfigService::new(),
+                set_theme: None,
+                set_code_theme: None,
+                stdin: None,
+            };
+            nested_executor.execute(command_str, &minimal_context)
+        }),
+        app_config: AppConfigService::new(),
+        set_theme: None,
+        set_code_theme: None,
+        stdin: None,
+    };
+
+    executor.execute(command_line, &context)
+}
+
+fn loading_html() -> String {
+    "<div class=\"command-output\">Loading...</div>".to_string()
+}
+
+/// HTML for a finished command result
+fn result_html(result: CommandResult) -> String {
+    match result {
+        CommandResult::Success(out) if out.is_empty() => String::new(),
+        CommandResult::Success(out) => {
+            format!("<div class=\"command-output\">{}</div>", html_escape(&out))
+        }
+        CommandResult::Error(err) => {
+            format!(
+                "<div class=\"command-error\">Error: {}</div>",
+                html_escape(&err)
+            )
+        }
+        CommandResult::Html(html_content) => {
+            format!("<div class=\"command-html\">{}</div>", html_content)
+        }
+        CommandResult::Async(_) => {
+            "<div class=\"command-error\">Error: Async result not awaited</div>".to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::include::MAX_EXEC_DEPTH;
+    use super::*;
+
+    #[test]
+    fn test_discard_outputs() {
+        track_output("run-output-7");
+        RESOLVED_OUTPUTS.with(|outputs| {
+            outputs
+                .borrow_mut()
+                .insert("run-output-8".to_string(), Some("done".to_string()))
+        });
+        let html = r#"<div class="run-output" id="run-output-7"></div><div class="run-output" id="run-output-8"></div>"#;
+        assert_eq!(
+            output_ids(html).collect::<Vec<_>>(),
+            vec!["run-output-7", "run-output-8"]
+        );
+        discard_outputs(html);
+        RESOLVED_OUTPUTS.with(|outputs| assert!(outputs.borrow().is_empty()));
+    }
+
+    #[test]
+    fn test_nested_run_blocks_stop() {
+        let executor = CommandExecutor::new();
+        let running: Vec<ExecDepth> = (0..MAX_EXEC_DEPTH)
+            .filter_map(|_| ExecDepth::enter())
+            .collect();
+        assert_eq!(
+            render_run_block("view self.md", false, &executor),
+            r#"<div class="run-block"><div class="command-error">Error: run blocks nested too deeply</div></div>"#
+        );
+        assert!(render_run_block("view self.md", true, &executor).contains("run-lazy"));
+        drop(running);
+    }
+}
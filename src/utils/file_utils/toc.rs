@@ -0,0 +1,190 @@
+// Table of Contents
+// Heading slugs and outlines for rendered markdown
+
+use super::extensions::apply_extensions;
+use super::frontmatter::strip_frontmatter;
+use super::markdown_processor::{html_escape, markdown_options};
+use crate::utils::app_config::MarkdownConfig;
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag};
+use std::collections::HashMap;
+use wasm_bindgen::JsCast;
+
+/// A heading in a markdown document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heading {
+    /// 1 for `#`, 2 for `##`, ...
+    pub level: usize,
+    /// Plain text of the heading
+    pub text: String,
+    /// Anchor id used in the rendered HTML
+    pub id: String,
+}
+
+/// Generates unique heading ids, GitHub style
+///
+/// Repeated slugs get `-1`, `-2`, ... suffixes in document order, so ids
+/// stay stable as long as earlier headings don't change.
+#[derive(Debug, Default)]
+pub struct Slugger {
+    seen: HashMap<String, usize>,
+}
+
+impl Slugger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Unique id for a heading with the given text
+    pub fn slug(&mut self, text: &str) -> String {
+        self.unique(slugify(text))
+    }
+
+    /// Make an id unique among the ids handed out so far
+    pub fn unique(&mut self, base: String) -> String {
+        let count = self.seen.entry(base.clone()).or_insert(0);
+        let id = if *count == 0 {
+            base.clone()
+        } else {
+            format!("{}-{}", base, count)
+        };
+        *count += 1;
+        id
+    }
+}
+
+/// Slug for heading text: lowercase, spaces to `-`, punctuation removed
+///
+/// Letters and digits of any script are kept, so CJK headings get
+/// readable ids too.
+pub fn slugify(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            c if c.is_whitespace() => Some('-'),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Numeric level of a pulldown-cmark heading
+pub fn heading_level(level: HeadingLevel) -> usize {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+/// List the headings of a markdown file, with the ids the renderer assigns
+pub fn extract_headings(markdown_input: &str, config: &MarkdownConfig) -> Vec<Heading> {
+    let content = strip_frontmatter(markdown_input);
+    let mut slugger = Slugger::new();
+    let mut headings = Vec::new();
+    let mut current: Option<(usize, Option<String>, String)> = None;
+
+    let events = apply_extensions(
+        Parser::new_ext(&content, markdown_options(config)).collect(),
+        config,
+    );
+    for event in events {
+        match event {
+            Event::Start(Tag::Heading(level, id, _)) => {
+                current = Some((heading_level(level), id.map(String::from), String::new()));
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, _, heading_text)) = current.as_mut() {
+                    heading_text.push_str(&text);
+                }
+            }
+            Event::End(Tag::Heading(..)) => {
+                if let Some((level, id, text)) = current.take() {
+                    let id = match id {
+                        Some(id) => slugger.unique(id),
+                        None => slugger.slug(&text),
+                    };
+                    headings.push(Heading { level, text, id });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    headings
+}
+
+/// Render headings as a nested list of in-page links
+pub fn render_toc(headings: &[Heading]) -> String {
+    if headings.is_empty() {
+        return String::new();
+    }
+
+    let min_level = headings.iter().map(|h| h.level).min().unwrap_or(1);
+    let items: String = headings
+        .iter()
+        .map(|heading| {
+            format!(
+                r##"<li class="toc-level-{}"><a href="#{}">{}</a></li>"##,
+                heading.level - min_level + 1,
+                html_escape(&heading.id),
+                html_escape(&heading.text)
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<nav class="toc"><div class="toc-title">Contents</div><ul>{}</ul></nav>"#,
+        items
+    )
+}
+
+/// Scroll the heading with `id` inside `root` into view
+///
+/// Ids are compared directly rather than through a CSS selector, since
+/// slugs may contain characters that would need escaping.
+pub fn scroll_to_heading(root: &web_sys::Element, id: &str) -> bool {
+    let Ok(elements) = root.query_selector_all("[id]") else {
+        return false;
+    };
+
+    let heading = (0..elements.length())
+        .filter_map(|index| elements.item(index))
+        .filter_map(|node| node.dyn_into::<web_sys::Element>().ok())
+        .find(|element| element.id() == id);
+
+    match heading {
+        Some(heading) => {
+            heading.scroll_into_view();
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("  `view` --toc  "), "view---toc");
+        assert_eq!(slugify("目录 结构"), "目录-结构");
+    }
+
+    #[test]
+    fn test_duplicate_headings_get_suffixes() {
+        let headings = extract_headings(
+            "# Intro\n## Setup\n## Setup\n### Setup\n",
+            &MarkdownConfig::default(),
+        );
+        let ids: Vec<&str> = headings.iter().map(|h| h.id.as_str()).collect();
+
+        assert_eq!(ids, vec!["intro", "setup", "setup-1", "setup-2"]);
+        assert_eq!(headings[3].level, 3);
+    }
+}
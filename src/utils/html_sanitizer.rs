@@ -0,0 +1,643 @@
+// HTML Sanitizer
+// Allow-list filtering for HTML command output before it is inserted into the page
+
+/// Elements kept as they are (with filtered attributes)
+const ALLOWED_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "article",
+    "aside",
+    "audio",
+    "b",
+    "blockquote",
+    "br",
+    "button",
+    "caption",
+    "cite",
+    "code",
+    "col",
+    "colgroup",
+    "dd",
+    "del",
+    "details",
+    "dfn",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "i",
+    "img",
+    "input",
+    "ins",
+    "kbd",
+    "li",
+    "main",
+    "mark",
+    "nav",
+    "ol",
+    "p",
+    "picture",
+    "pre",
+    "q",
+    "s",
+    "samp",
+    "section",
+    "small",
+    "source",
+    "span",
+    "strong",
+    "sub",
+    "summary",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "time",
+    "tr",
+    "u",
+    "ul",
+    "var",
+    "video",
+    "wbr",
+    // Icons, e.g. in `ls --link`
+    "svg",
+    "g",
+    "path",
+    "circle",
+    "ellipse",
+    "line",
+    "polyline",
+    "polygon",
+    "rect",
+];
+
+/// Elements removed together with everything inside them
+const DROPPED_WITH_CONTENT: &[&str] = &[
+    "script",
+    "style",
+    "iframe",
+    "frame",
+    "frameset",
+    "object",
+    "embed",
+    "applet",
+    "noscript",
+    "noembed",
+    "noframes",
+    "template",
+    "textarea",
+    "title",
+    "xmp",
+    "plaintext",
+    "math",
+    "foreignobject",
+];
+
+/// Attributes allowed on any element
+const GLOBAL_ATTRIBUTES: &[&str] = &["class", "id", "title", "lang", "dir", "role", "hidden"];
+
+/// Attributes allowed on specific elements
+const ELEMENT_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("a", &["href", "target", "rel", "name", "download"]),
+    (
+        "img",
+        &[
+            "src", "alt", "width", "height", "loading", "srcset", "sizes",
+        ],
+    ),
+    (
+        "video",
+        &[
+            "src",
+            "poster",
+            "controls",
+            "autoplay",
+            "loop",
+            "muted",
+            "playsinline",
+            "width",
+            "height",
+            "preload",
+        ],
+    ),
+    (
+        "audio",
+        &["src", "controls", "autoplay", "loop", "muted", "preload"],
+    ),
+    ("source", &["src", "srcset", "type", "media", "sizes"]),
+    ("input", &["type", "checked", "disabled"]),
+    ("button", &["type", "disabled"]),
+    ("td", &["colspan", "rowspan", "align"]),
+    ("th", &["colspan", "rowspan", "align", "scope"]),
+    ("col", &["span"]),
+    ("colgroup", &["span"]),
+    ("ol", &["start", "reversed", "type"]),
+    ("li", &["value"]),
+    ("details", &["open"]),
+    ("time", &["datetime"]),
+    ("q", &["cite"]),
+    ("blockquote", &["cite"]),
+    ("del", &["cite", "datetime"]),
+    ("ins", &["cite", "datetime"]),
+];
+
+/// Presentation attributes allowed on SVG elements
+const SVG_ATTRIBUTES: &[&str] = &[
+    "xmlns",
+    "viewbox",
+    "width",
+    "height",
+    "fill",
+    "stroke",
+    "stroke-width",
+    "stroke-linecap",
+    "stroke-linejoin",
+    "stroke-dasharray",
+    "fill-rule",
+    "clip-rule",
+    "opacity",
+    "transform",
+    "d",
+    "points",
+    "x",
+    "y",
+    "x1",
+    "y1",
+    "x2",
+    "y2",
+    "cx",
+    "cy",
+    "r",
+    "rx",
+    "ry",
+];
+
+/// Attributes holding URLs, checked against the allowed schemes
+const URL_ATTRIBUTES: &[&str] = &["href", "src", "poster", "cite", "srcset"];
+
+/// CSS properties allowed in `style` attributes
+const ALLOWED_STYLES: &[&str] = &[
+    "background-color",
+    "border",
+    "border-color",
+    "border-radius",
+    "border-style",
+    "border-width",
+    "color",
+    "display",
+    "font-style",
+    "font-weight",
+    "height",
+    "margin",
+    "margin-bottom",
+    "margin-left",
+    "margin-right",
+    "margin-top",
+    "max-width",
+    "opacity",
+    "padding",
+    "padding-bottom",
+    "padding-left",
+    "padding-right",
+    "padding-top",
+    "text-align",
+    "text-decoration",
+    "vertical-align",
+    "width",
+];
+
+/// Escape text for use in HTML content or a quoted attribute value
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#x27;")
+}
+
+/// Filter HTML down to allowed elements and attributes
+///
+/// Unknown elements are unwrapped (their text is kept), while scripts,
+/// styles, frames and similar are removed with their content. Event handler
+/// attributes are always dropped, and URLs must be relative or use http(s),
+/// mailto or tel (or `data:image/...` for images). Links opening a new tab
+/// always get `rel="noopener noreferrer"`.
+pub fn sanitize_html(html: &str) -> String {
+    sanitize(html, false)
+}
+
+/// Filter raw HTML written in markdown, which may come from anywhere
+///
+/// Like `sanitize_html`, but `id` and `data-*` attributes are removed too:
+/// the terminal runs `data-command` and similar attributes when clicked, so
+/// only markup the renderer generates itself may carry them.
+pub fn sanitize_raw_html(html: &str) -> String {
+    sanitize(html, true)
+}
+
+fn sanitize(html: &str, raw: bool) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        // Comments, doctypes and CDATA are dropped
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map_or("", |end| &rest[end + 3..]);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+
+        let Some(tag) = parse_tag(rest) else {
+            // Not a tag, e.g. `a < b`
+            output.push_str("&lt;");
+            rest = &rest[1..];
+            continue;
+        };
+        rest = &rest[tag.length..];
+
+        if DROPPED_WITH_CONTENT.contains(&tag.name.as_str()) {
+            if !tag.closing && !tag.self_closing {
+                rest = skip_past_closing_tag(rest, &tag.name);
+            }
+            continue;
+        }
+        if !ALLOWED_TAGS.contains(&tag.name.as_str()) {
+            continue;
+        }
+
+        if tag.closing {
+            output.push_str(&format!("</{}>", tag.name));
+            continue;
+        }
+
+        let mut attributes: Vec<(String, Option<String>)> = tag
+            .attributes
+            .into_iter()
+            .filter(|(name, value)| {
+                attribute_allowed(&tag.name, &name.to_ascii_lowercase(), value.as_deref(), raw)
+            })
+            .collect();
+        if attributes
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("target"))
+        {
+            attributes.retain(|(name, _)| !name.eq_ignore_ascii_case("rel"));
+            attributes.push(("rel".to_string(), Some("noopener noreferrer".to_string())));
+        }
+
+        output.push('<');
+        output.push_str(&tag.name);
+        for (name, value) in attributes {
+            match value {
+                Some(value) => {
+                    output.push_str(&format!(" {}=\"{}\"", name, value.replace('"', "&quot;")))
+                }
+                None => output.push_str(&format!(" {}", name)),
+            }
+        }
+        output.push_str(if tag.self_closing { "/>" } else { ">" });
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// A start or end tag
+struct Tag {
+    /// Lowercase element name
+    name: String,
+    /// Attributes in source order, as written (entities not decoded)
+    attributes: Vec<(String, Option<String>)>,
+    closing: bool,
+    self_closing: bool,
+    /// Length of the tag in the source, including `<` and `>`
+    length: usize,
+}
+
+/// Parse the tag at the start of `html`, which begins with `<`
+fn parse_tag(html: &str) -> Option<Tag> {
+    let bytes = html.as_bytes();
+    let mut index = 1;
+    let closing = bytes.get(index) == Some(&b'/');
+    if closing {
+        index += 1;
+    }
+    if !bytes.get(index)?.is_ascii_alphabetic() {
+        return None;
+    }
+
+    let name_start = index;
+    while index < bytes.len() && !is_tag_delimiter(bytes[index]) {
+        index += 1;
+    }
+    let name = html[name_start..index].to_ascii_lowercase();
+
+    let mut attributes = Vec::new();
+    let mut self_closing = false;
+    loop {
+        while index < bytes.len() && bytes[index].is_ascii_whitespace() {
+            index += 1;
+        }
+        match bytes.get(index)? {
+            b'>' => {
+                index += 1;
+                break;
+            }
+            b'/' => {
+                self_closing = true;
+                index += 1;
+                continue;
+            }
+            _ => {}
+        }
+        self_closing = false;
+
+        let attribute_start = index;
+        while index < bytes.len() && !is_tag_delimiter(bytes[index]) && bytes[index] != b'=' {
+            index += 1;
+        }
+        let attribute = html[attribute_start..index].to_string();
+        if attribute.is_empty() {
+            // Stray `=`; skip it
+            index += 1;
+            continue;
+        }
+
+        while index < bytes.len() && bytes[index].is_ascii_whitespace() {
+            index += 1;
+        }
+        let value = if bytes.get(index) == Some(&b'=') {
+            index += 1;
+            while index < bytes.len() && bytes[index].is_ascii_whitespace() {
+                index += 1;
+            }
+            match bytes.get(index)? {
+                quote @ (b'"' | b'\'') => {
+                    let value_start = index + 1;
+                    let value_end = value_start + html[value_start..].find(*quote as char)?;
+                    index = value_end + 1;
+                    Some(html[value_start..value_end].to_string())
+                }
+                _ => {
+                    let value_start = index;
+                    while index < bytes.len()
+                        && !bytes[index].is_ascii_whitespace()
+                        && bytes[index] != b'>'
+                    {
+                        index += 1;
+                    }
+                    Some(html[value_start..index].to_string())
+                }
+            }
+        } else {
+            None
+        };
+        attributes.push((attribute, value));
+    }
+
+    Some(Tag {
+        name,
+        attributes,
+        closing,
+        self_closing,
+        length: index,
+    })
+}
+
+fn is_tag_delimiter(byte: u8) -> bool {
+    byte.is_ascii_whitespace() || byte == b'>' || byte == b'/'
+}
+
+/// Skip to just after `</name>`, or to the end when it is never closed
+fn skip_past_closing_tag<'a>(html: &'a str, name: &str) -> &'a str {
+    let closing = format!("</{}", name);
+    let lowercase = html.to_ascii_lowercase();
+    match lowercase.find(&closing) {
+        Some(start) => {
+            let after = &html[start..];
+            after.find('>').map_or("", |end| &after[end + 1..])
+        }
+        None => "",
+    }
+}
+
+fn attribute_allowed(tag: &str, name: &str, value: Option<&str>, raw: bool) -> bool {
+    // Event handlers are never allowed, whatever the element
+    if name.starts_with("on") {
+        return false;
+    }
+    if raw && (name == "id" || name.starts_with("data-")) {
+        return false;
+    }
+
+    let allowed = GLOBAL_ATTRIBUTES.contains(&name)
+        || name.starts_with("data-")
+        || name.starts_with("aria-")
+        || name == "style"
+        || ELEMENT_ATTRIBUTES
+            .iter()
+            .any(|(element, attributes)| *element == tag && attributes.contains(&name))
+        || (is_svg_element(tag) && SVG_ATTRIBUTES.contains(&name));
+    if !allowed {
+        return false;
+    }
+
+    let decoded = value.map(decode_entities).unwrap_or_default();
+    match name {
+        "style" => style_allowed(&decoded),
+        "srcset" => decoded.split(',').all(|candidate| {
+            url_allowed(
+                candidate.split_whitespace().next().unwrap_or_default(),
+                false,
+            )
+        }),
+        _ if URL_ATTRIBUTES.contains(&name) => {
+            url_allowed(&decoded, tag == "img" || tag == "source")
+        }
+        "type" if tag == "input" => decoded.eq_ignore_ascii_case("checkbox"),
+        _ => true,
+    }
+}
+
+fn is_svg_element(tag: &str) -> bool {
+    matches!(
+        tag,
+        "svg" | "g" | "path" | "circle" | "ellipse" | "line" | "polyline" | "polygon" | "rect"
+    )
+}
+
+/// Allow relative URLs and a few safe schemes
+fn url_allowed(url: &str, image: bool) -> bool {
+    // Browsers ignore whitespace and control characters inside schemes
+    let normalized: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    let scheme_end = normalized.find(':');
+    let path_start = normalized.find(['/', '?', '#']);
+    match (scheme_end, path_start) {
+        // No scheme, or the colon is part of the path: relative URL
+        (None, _) => true,
+        (Some(colon), Some(slash)) if slash < colon => true,
+        (Some(colon), _) => {
+            let scheme = &normalized[..colon];
+            matches!(scheme, "http" | "https" | "mailto" | "tel")
+                || (image
+                    && scheme == "data"
+                    && normalized.starts_with("data:image/")
+                    && !normalized.starts_with("data:image/svg"))
+        }
+    }
+}
+
+/// Allow declarations of known properties with plain values
+fn style_allowed(style: &str) -> bool {
+    let lowercase = style.to_ascii_lowercase();
+    if ["url(", "expression", "javascript:", "@import", "\\", "<"]
+        .iter()
+        .any(|pattern| lowercase.contains(pattern))
+    {
+        return false;
+    }
+    lowercase
+        .split(';')
+        .filter(|declaration| !declaration.trim().is_empty())
+        .all(|declaration| match declaration.split_once(':') {
+            Some((property, _)) => ALLOWED_STYLES.contains(&property.trim()),
+            None => false,
+        })
+}
+
+/// Decode character references, so `&#106;avascript:` is checked as `javascript:`
+fn decode_entities(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest[1..]
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '#')
+            .map_or(rest.len(), |end| end + 1);
+        let reference = &rest[1..end];
+        let decoded = match reference.strip_prefix('#') {
+            Some(number) => match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => number.parse().ok(),
+            }
+            .and_then(char::from_u32),
+            None => match reference.to_ascii_lowercase().as_str() {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "colon" => Some(':'),
+                "tab" => Some('\t'),
+                "newline" => Some('\n'),
+                _ => None,
+            },
+        };
+        match decoded {
+            Some(c) => {
+                output.push(c);
+                // A reference may end with `;`
+                rest = rest[end..].strip_prefix(';').unwrap_or(&rest[end..]);
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_removes_scripts_and_handlers() {
+        assert_eq!(
+            sanitize_html(
+                r#"<p onclick="x()">Hi<script>alert(1)</script></p><img src=x onerror=alert(1)>"#
+            ),
+            r#"<p>Hi</p><img src="x">"#
+        );
+        assert_eq!(
+            sanitize_html("<STYLE>body{}</style><iframe src=//evil></iframe>ok"),
+            "ok"
+        );
+        assert_eq!(sanitize_html("<blink>text</blink> a < b"), "text a &lt; b");
+    }
+
+    #[test]
+    fn test_rejects_unsafe_urls() {
+        assert_eq!(
+            sanitize_html(r#"<a href="javascript:alert(1)">x</a>"#),
+            "<a>x</a>"
+        );
+        assert_eq!(
+            sanitize_html(r#"<a href="&#106;ava&#x73;cript&colon;alert(1)">x</a>"#),
+            "<a>x</a>"
+        );
+        assert_eq!(
+            sanitize_html(r#"<a href="jav	ascript:x">x</a><img src="data:text/html,x">"#),
+            "<a>x</a><img>"
+        );
+        assert_eq!(
+            sanitize_raw_html(r#"<a href="/posts/a.md?x=1:2" data-command="rm -r /" id="x">x</a>"#),
+            r#"<a href="/posts/a.md?x=1:2">x</a>"#
+        );
+        assert_eq!(
+            sanitize_raw_html(r#"<a href="https://a.example" target="_blank" rel="opener">x</a>"#),
+            r#"<a href="https://a.example" target="_blank" rel="noopener noreferrer">x</a>"#
+        );
+        assert_eq!(
+            sanitize_html(r#"<img src="data:image/png;base64,AAAA">"#),
+            r#"<img src="data:image/png;base64,AAAA">"#
+        );
+    }
+
+    #[test]
+    fn test_keeps_generated_markup() {
+        let svg =
+            r##"<svg width="20" viewBox="0 0 24 24" stroke="#32cd32"><path d="M22 19z"/></svg>"##;
+        assert_eq!(sanitize_html(svg), svg);
+
+        let swatch = r#"<span style="display: inline-block; background-color: #fff;"></span>"#;
+        assert_eq!(sanitize_html(swatch), swatch);
+        assert_eq!(
+            sanitize_html(r#"<div style="background: url(x)">a</div>"#),
+            "<div>a</div>"
+        );
+
+        let code = r#"<div class="code-block" data-code="a &lt; b &quot;c&quot;"><input disabled="" type="checkbox"/></div>"#;
+        assert_eq!(sanitize_html(code), code);
+    }
+}
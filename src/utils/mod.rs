@@ -2,9 +2,18 @@
 
 pub mod app_config;
 pub mod config;
+pub mod file_transfer;
 pub mod file_utils;
+pub mod html_sanitizer;
+pub mod prompt;
+pub mod search;
 pub mod syntax_highlighter;
+pub mod tar;
+pub mod text_buffer;
+pub mod theme;
 
 pub use app_config::AppConfigService;
 pub use config::build_url;
-pub use file_utils::{fetch_file_content, fetch_and_render_markdown_with_executor};
\ No newline at end of file
+pub use file_utils::{
+    fetch_and_render_markdown_with_executor, fetch_file_content, fetch_file_content_cached,
+};
\ No newline at end of file
@@ -0,0 +1,230 @@
+// Prompt Rendering
+// Expands PS1-style prompt templates into styled segments
+
+use crate::commands::CommandExecutor;
+use crate::utils::app_config::AppConfig;
+use crate::utils::theme::ANSI_COLOR_NAMES;
+
+/// Template used when neither PS1 nor terminal.prompt.format is set
+pub const DEFAULT_PROMPT_FORMAT: &str = "{symbol}";
+
+/// A styled piece of a rendered prompt
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptSegment {
+    pub text: String,
+    /// CSS color value (hex or `var(--ansi-*)`)
+    pub color: String,
+    pub bold: bool,
+}
+
+/// Values substituted into the prompt template
+pub struct PromptContext<'a> {
+    pub user: &'a str,
+    pub cwd: &'a str,
+    pub home: &'a str,
+    pub time: &'a str,
+    pub status: i32,
+    pub symbol: &'a str,
+}
+
+/// Render the prompt for the current session state
+///
+/// The template is taken from the PS1 environment variable, falling back to
+/// `terminal.prompt.format` in app.json and finally to just the symbol.
+pub fn current_prompt(executor: &CommandExecutor, config: &AppConfig) -> Vec<PromptSegment> {
+    let env = executor.environment.borrow();
+    let cwd = executor.filesystem.borrow().get_current_directory();
+    let template = env
+        .get("PS1")
+        .or(config.terminal.prompt.format.as_deref())
+        .unwrap_or(DEFAULT_PROMPT_FORMAT);
+
+    let context = PromptContext {
+        user: &config.author.name,
+        cwd: &cwd,
+        home: env.get("HOME").unwrap_or("/"),
+        time: &current_time(),
+        status: env.last_status(),
+        symbol: &config.terminal.prompt.symbol,
+    };
+
+    render_prompt(template, &context, &config.terminal.prompt.color)
+}
+
+/// Expand a prompt template into styled segments
+///
+/// Unknown escapes and unmatched braces are kept as literal text.
+pub fn render_prompt(
+    template: &str,
+    context: &PromptContext,
+    default_color: &str,
+) -> Vec<PromptSegment> {
+    let mut segments = Vec::new();
+    let mut current = PromptSegment {
+        text: String::new(),
+        color: default_color.to_string(),
+        bold: true,
+    };
+
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        current.text.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let Some(end) = after.find('}') else {
+            current.text.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+
+        let escape = &after[..end];
+        rest = &after[end + 1..];
+
+        match escape {
+            "user" => current.text.push_str(context.user),
+            "cwd" => current
+                .text
+                .push_str(&abbreviate_home(context.cwd, context.home)),
+            "time" => current.text.push_str(context.time),
+            "symbol" => current.text.push_str(context.symbol),
+            "status" => {
+                if context.status != 0 {
+                    current.text.push_str(&context.status.to_string());
+                }
+            }
+            "bold" | "normal" => {
+                push_segment(&mut segments, &mut current);
+                current.bold = escape == "bold";
+            }
+            "reset" => {
+                push_segment(&mut segments, &mut current);
+                current.bold = true;
+                current.color = default_color.to_string();
+            }
+            color if ANSI_COLOR_NAMES.contains(&color) => {
+                push_segment(&mut segments, &mut current);
+                current.color = format!("var(--ansi-{})", color);
+            }
+            color if is_hex_color(color) => {
+                push_segment(&mut segments, &mut current);
+                current.color = color.to_string();
+            }
+            _ => {
+                current.text.push('{');
+                current.text.push_str(escape);
+                current.text.push('}');
+            }
+        }
+    }
+    current.text.push_str(rest);
+    push_segment(&mut segments, &mut current);
+
+    segments
+}
+
+/// Replace a leading HOME directory in `cwd` with `~`
+pub fn abbreviate_home(cwd: &str, home: &str) -> String {
+    let home = home.trim_end_matches('/');
+    if home.is_empty() {
+        // HOME is the root directory
+        return format!("~{}", cwd.trim_end_matches('/'));
+    }
+
+    if cwd == home {
+        "~".to_string()
+    } else if let Some(rest) = cwd.strip_prefix(home).filter(|rest| rest.starts_with('/')) {
+        format!("~{}", rest)
+    } else {
+        cwd.to_string()
+    }
+}
+
+fn push_segment(segments: &mut Vec<PromptSegment>, current: &mut PromptSegment) {
+    if !current.text.is_empty() {
+        segments.push(current.clone());
+        current.text.clear();
+    }
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value.starts_with('#')
+        && matches!(value.len(), 4 | 7 | 9)
+        && value[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Current local time formatted as HH:MM:SS
+fn current_time() -> String {
+    let now = js_sys::Date::new_0();
+    format!(
+        "{:02}:{:02}:{:02}",
+        now.get_hours(),
+        now.get_minutes(),
+        now.get_seconds()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(cwd: &str, status: i32) -> PromptContext<'_> {
+        PromptContext {
+            user: "Mist",
+            cwd,
+            home: "/",
+            time: "12:34:56",
+            status,
+            symbol: "$",
+        }
+    }
+
+    fn text(segments: &[PromptSegment]) -> String {
+        segments.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_expands_escapes() {
+        let segments = render_prompt(
+            "{user}@zzhack {cwd} {time} {symbol}",
+            &context("/posts", 0),
+            "#fff",
+        );
+        assert_eq!(text(&segments), "Mist@zzhack ~/posts 12:34:56 $");
+    }
+
+    #[test]
+    fn test_status_only_shown_on_failure() {
+        assert_eq!(
+            text(&render_prompt("{status}{symbol}", &context("/", 0), "#fff")),
+            "$"
+        );
+        assert_eq!(
+            text(&render_prompt("{status}{symbol}", &context("/", 1), "#fff")),
+            "1$"
+        );
+    }
+
+    #[test]
+    fn test_color_escapes_split_segments() {
+        let segments = render_prompt("{red}a{reset}b{#00ff00}c", &context("/", 0), "#fff");
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].color, "var(--ansi-red)");
+        assert_eq!(segments[1].color, "#fff");
+        assert_eq!(segments[2].color, "#00ff00");
+    }
+
+    #[test]
+    fn test_unknown_escape_kept_literally() {
+        let segments = render_prompt("{nope} {unclosed", &context("/", 0), "#fff");
+        assert_eq!(text(&segments), "{nope} {unclosed");
+    }
+
+    #[test]
+    fn test_abbreviate_home() {
+        assert_eq!(abbreviate_home("/", "/"), "~");
+        assert_eq!(abbreviate_home("/posts", "/"), "~/posts");
+        assert_eq!(abbreviate_home("/posts", "/posts"), "~");
+        assert_eq!(abbreviate_home("/posts/rust", "/posts"), "~/rust");
+        assert_eq!(abbreviate_home("/postscript", "/posts"), "/postscript");
+    }
+}
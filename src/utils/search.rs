@@ -0,0 +1,299 @@
+// Full-Text Search
+// BM25 ranking over the inverted index built by generate_metadata.js
+
+use crate::utils::file_utils::fetch_file_content;
+use crate::utils::html_sanitizer::escape_html;
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Index file in the data directory, written by generate_metadata.js
+pub const SEARCH_INDEX_FILE: &str = "search_index.json";
+/// Index format this build understands
+const SEARCH_INDEX_VERSION: u32 = 1;
+
+/// BM25 term frequency saturation
+const K1: f64 = 1.2;
+/// BM25 document length normalisation
+const B: f64 = 0.75;
+/// Characters of context shown on each side of the first match in a snippet
+const SNIPPET_CONTEXT: usize = 60;
+
+thread_local! {
+    /// Index loaded by the first search
+    static INDEX: RefCell<Option<Rc<SearchIndex>>> = const { RefCell::new(None) };
+}
+
+/// An indexed markdown file
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct SearchDoc {
+    /// Data path, e.g. `posts/blog.md`
+    pub path: String,
+    pub title: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Weighted number of terms, for length normalisation
+    pub length: u32,
+    /// Plain text of the body, for snippets
+    pub text: String,
+}
+
+/// Inverted index of titles, descriptions, tags and body text
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct SearchIndex {
+    pub version: u32,
+    pub docs: Vec<SearchDoc>,
+    /// Term to `(document index, weighted term frequency)` postings
+    pub terms: HashMap<String, Vec<(usize, f64)>>,
+}
+
+/// A ranked search result
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit<'a> {
+    pub doc: &'a SearchDoc,
+    pub score: f64,
+}
+
+impl SearchIndex {
+    /// Parse an index, rejecting ones written in another format version
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let index: SearchIndex = serde_json::from_str(json)
+            .map_err(|error| format!("invalid search index: {}", error))?;
+        if index.version != SEARCH_INDEX_VERSION {
+            return Err(format!(
+                "search index version {} is not supported (expected {}); run generate_metadata.js",
+                index.version, SEARCH_INDEX_VERSION
+            ));
+        }
+        Ok(index)
+    }
+
+    /// Documents matching any query term, best first, scored with BM25
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit<'_>> {
+        let doc_count = self.docs.len() as f64;
+        let average_length =
+            self.docs.iter().map(|doc| doc.length as f64).sum::<f64>() / doc_count.max(1.0);
+
+        let mut query_terms = tokenize(query);
+        query_terms.sort();
+        query_terms.dedup();
+
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        for term in &query_terms {
+            let Some(postings) = self.terms.get(term) else {
+                continue;
+            };
+            let doc_frequency = postings.len() as f64;
+            let idf = (1.0 + (doc_count - doc_frequency + 0.5) / (doc_frequency + 0.5)).ln();
+            for &(doc_index, frequency) in postings {
+                let Some(doc) = self.docs.get(doc_index) else {
+                    continue;
+                };
+                let length_ratio = doc.length as f64 / average_length.max(1.0);
+                let score =
+                    idf * frequency * (K1 + 1.0) / (frequency + K1 * (1.0 - B + B * length_ratio));
+                *scores.entry(doc_index).or_default() += score;
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .into_iter()
+            .map(|(doc_index, score)| SearchHit {
+                doc: &self.docs[doc_index],
+                score,
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.doc.path.cmp(&b.doc.path))
+        });
+        hits.truncate(limit);
+        hits
+    }
+}
+
+/// Fetch and parse the search index, once per session
+pub async fn load_search_index() -> Result<Rc<SearchIndex>, String> {
+    if let Some(index) = INDEX.with(|index| index.borrow().clone()) {
+        return Ok(index);
+    }
+    let json = fetch_file_content(SEARCH_INDEX_FILE).await?;
+    let index = Rc::new(SearchIndex::from_json(&json)?);
+    INDEX.with(|cached| *cached.borrow_mut() = Some(index.clone()));
+    Ok(index)
+}
+
+/// Chinese, Japanese and Korean characters; must match `isCjk` in generate_metadata.js
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x3040..=0x30FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xAC00..=0xD7AF
+            | 0xF900..=0xFAFF
+            | 0x20000..=0x2FFFF
+    )
+}
+
+/// Split text into lowercase search terms; must match `tokenize` in generate_metadata.js
+///
+/// Letter and digit runs are words and CJK runs become overlapping bigrams
+/// (`搜索引擎` → `搜索`, `索引`, `引擎`), since those scripts don't put
+/// spaces between words. A lone CJK character is its own term.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut cjk_run: Vec<char> = Vec::new();
+
+    fn flush_cjk(run: &mut Vec<char>, tokens: &mut Vec<String>) {
+        match run.len() {
+            0 => {}
+            1 => tokens.push(run[0].to_string()),
+            _ => tokens.extend(run.windows(2).map(|pair| pair.iter().collect())),
+        }
+        run.clear();
+    }
+
+    for c in text.to_lowercase().chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            cjk_run.push(c);
+        } else if c.is_alphanumeric() {
+            flush_cjk(&mut cjk_run, &mut tokens);
+            word.push(c);
+        } else {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            flush_cjk(&mut cjk_run, &mut tokens);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    flush_cjk(&mut cjk_run, &mut tokens);
+
+    tokens
+}
+
+/// HTML excerpt of `text` around the first query word, with matches in `<mark>`
+///
+/// Falls back to the start of the text when no word occurs in it.
+pub fn render_snippet(text: &str, query: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    // One lowercase char per original char, so positions line up
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let words: Vec<Vec<char>> = query
+        .split(|c: char| !(c.is_alphanumeric() || is_cjk(c)))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase().chars().collect())
+        .collect();
+
+    let matches_at = |position: usize| -> Option<usize> {
+        words
+            .iter()
+            .filter(|word| lower[position..].starts_with(word))
+            .map(|word| word.len())
+            .max()
+    };
+
+    let first = (0..chars.len()).find(|&position| matches_at(position).is_some());
+    let start = first.map_or(0, |first| first.saturating_sub(SNIPPET_CONTEXT));
+    let end = (first.unwrap_or(0) + SNIPPET_CONTEXT * 2).min(chars.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    let mut position = start;
+    while position < end {
+        match matches_at(position) {
+            Some(length) => {
+                let matched: String = chars[position..position + length].iter().collect();
+                snippet.push_str(&format!("<mark>{}</mark>", escape_html(&matched)));
+                position += length;
+            }
+            None => {
+                snippet.push_str(&escape_html(&chars[position].to_string()));
+                position += 1;
+            }
+        }
+    }
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(path: &str, length: u32) -> SearchDoc {
+        SearchDoc {
+            path: path.to_string(),
+            title: path.to_string(),
+            description: None,
+            tags: Vec::new(),
+            length,
+            text: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_tokenize_cjk_bigrams() {
+        assert_eq!(
+            tokenize("Hello, Rust 搜索引擎 a 主 x2"),
+            ["hello", "rust", "搜索", "索引", "引擎", "a", "主", "x2"]
+        );
+    }
+
+    #[test]
+    fn test_bm25_ranking() {
+        let index = SearchIndex {
+            version: SEARCH_INDEX_VERSION,
+            docs: vec![doc("a.md", 100), doc("b.md", 10), doc("c.md", 50)],
+            terms: HashMap::from([
+                ("rust".to_string(), vec![(0, 2.0), (1, 2.0)]),
+                ("yew".to_string(), vec![(0, 1.0)]),
+                ("common".to_string(), vec![(0, 1.0), (1, 1.0), (2, 1.0)]),
+            ]),
+        };
+
+        // Same frequency, so the shorter document ranks higher
+        let paths: Vec<&str> = index
+            .search("Rust", 10)
+            .iter()
+            .map(|hit| hit.doc.path.as_str())
+            .collect();
+        assert_eq!(paths, ["b.md", "a.md"]);
+
+        // Matching more terms outweighs length
+        assert_eq!(index.search("rust yew", 10)[0].doc.path, "a.md");
+        assert_eq!(index.search("rust yew", 1).len(), 1);
+        assert!(index.search("missing", 10).is_empty());
+    }
+
+    #[test]
+    fn test_snippet_highlights_matches() {
+        let text = format!("{}Rust & Yew make rust apps", "x ".repeat(40));
+        let snippet = render_snippet(&text, "rust");
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with("<mark>Rust</mark> &amp; Yew make <mark>rust</mark> apps"));
+
+        assert_eq!(
+            render_snippet("使用搜索功能", "搜索"),
+            "使用<mark>搜索</mark>功能"
+        );
+        assert!(SearchIndex::from_json(r#"{"version":0,"docs":[],"terms":{}}"#).is_err());
+    }
+}
@@ -1,9 +1,52 @@
 // Syntax highlighting utilities using syntect
 // Provides syntax highlighting for code blocks in markdown
 
+use std::sync::OnceLock;
+use syntect::dumps::{from_binary, from_uncompressed_data};
+use syntect::easy::ScopeRegionIterator;
 use syntect::highlighting::ThemeSet;
-use syntect::html::highlighted_html_for_string;
-use syntect::parsing::{SyntaxSet, SyntaxReference};
+use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+/// Class style for highlighted output; the prefix avoids clashes with Tailwind classes
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "syn-" };
+
+/// Code theme used when the terminal theme does not name a known one
+pub const DEFAULT_CODE_THEME: &str = "base16-ocean.dark";
+
+/// Syntaxes selected by the `syntax-*` cargo features, dumped by build.rs
+const SYNTAX_DUMP: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/syntaxes.packdump"));
+
+/// syntect default themes plus data/themes/*.tmTheme, dumped by build.rs
+const THEME_DUMP: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/themes.themedump"));
+
+/// ANSI SGR codes for scope prefixes in ANSI output, checked innermost scope first
+///
+/// Only the 16 terminal colors are used so the output follows the terminal
+/// theme's palette rather than a code theme.
+const ANSI_SCOPES: &[(&str, &str)] = &[
+    ("comment", "90"),
+    ("string", "32"),
+    ("constant.character.escape", "36"),
+    ("constant", "33"),
+    ("keyword", "35"),
+    ("storage", "35"),
+    ("entity.name.function", "34"),
+    ("support.function", "34"),
+    ("variable.function", "34"),
+    ("entity.name", "36"),
+    ("support.type", "36"),
+    ("support.class", "36"),
+    ("entity.other.attribute-name", "33"),
+    ("markup.heading", "1;34"),
+    ("markup.inserted", "32"),
+    ("markup.deleted", "31"),
+    ("markup.changed", "33"),
+    ("meta.diff.range", "36"),
+];
+
+static SHARED: OnceLock<SyntaxHighlighter> = OnceLock::new();
 
 /// Syntax highlighter that provides syntax highlighting for code blocks
 pub struct SyntaxHighlighter {
@@ -12,14 +55,21 @@ pub struct SyntaxHighlighter {
 }
 
 impl SyntaxHighlighter {
-    /// Create a new syntax highlighter with default syntax and theme sets
+    /// Create a new syntax highlighter from the precompiled syntax and theme dumps
+    ///
+    /// Prefer `shared()`, which only pays this cost once.
     pub fn new() -> Self {
         Self {
-            syntax_set: SyntaxSet::load_defaults_newlines(),
-            theme_set: ThemeSet::load_defaults(),
+            syntax_set: from_uncompressed_data(SYNTAX_DUMP).expect("invalid syntax dump"),
+            theme_set: from_binary(THEME_DUMP),
         }
     }
 
+    /// The process-wide highlighter, created on first use
+    pub fn shared() -> &'static SyntaxHighlighter {
+        SHARED.get_or_init(Self::new)
+    }
+
     /// Highlight code with the specified language
     /// 
     /// # Arguments
@@ -27,23 +77,177 @@ impl SyntaxHighlighter {
     /// * `language` - The programming language (e.g., "rust", "javascript", "python")
     /// 
     /// # Returns
-    /// HTML string with syntax highlighting applied
+    /// HTML string with class-based highlighting; colors come from the
+    /// stylesheet produced by `theme_css`, so output follows theme changes
     pub fn highlight_code(&self, code: &str, language: &str) -> String {
+        format!(
+            "<pre class=\"syn-code\"><code>{}</code></pre>",
+            self.highlight_inner(code, language)
+        )
+    }
+
+    /// Highlight code and split the result into one HTML fragment per line
+    ///
+    /// Spans that cross a line break are closed at the end of the line and
+    /// reopened on the next, so each fragment can be wrapped on its own
+    /// (e.g. for line numbers or highlighted lines).
+    pub fn highlight_lines(&self, code: &str, language: &str) -> Vec<String> {
+        let html = self.highlight_inner(code, language);
+        let mut lines = Vec::new();
+        let mut open_tags: Vec<&str> = Vec::new();
+        let mut current = String::new();
+        let mut rest = html.as_str();
+
+        while !rest.is_empty() {
+            if rest.starts_with("</span>") {
+                open_tags.pop();
+                current.push_str("</span>");
+                rest = &rest["</span>".len()..];
+            } else if rest.starts_with("<span") {
+                let end = rest.find('>').map_or(rest.len(), |i| i + 1);
+                open_tags.push(&rest[..end]);
+                current.push_str(&rest[..end]);
+                rest = &rest[end..];
+            } else if let Some(after) = rest.strip_prefix('\n') {
+                current.push_str(&"</span>".repeat(open_tags.len()));
+                lines.push(std::mem::take(&mut current));
+                current.extend(open_tags.iter().copied());
+                rest = after;
+            } else {
+                let end = rest.find(['<', '\n']).unwrap_or(rest.len());
+                current.push_str(&rest[..end]);
+                rest = &rest[end..];
+            }
+        }
+
+        // Drop the trailing fragment left after a final newline
+        if !current.is_empty() && !Self::is_only_tags(&current) {
+            lines.push(current);
+        }
+        lines
+    }
+
+    /// Highlight code as text with ANSI color escapes, one entry per line
+    ///
+    /// Colors never carry over from one line to the next, so lines can be
+    /// prefixed or framed freely.
+    pub fn highlight_ansi_lines(&self, code: &str, language: &str) -> Vec<String> {
+        let syntax = self
+            .find_syntax_by_name(language)
+            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
+        let mut state = ParseState::new(syntax);
+        let mut stack = ScopeStack::new();
+
+        LinesWithEndings::from(code)
+            .map(|line| {
+                let text = line.trim_end_matches(['\n', '\r']);
+                let Ok(ops) = state.parse_line(line, &self.syntax_set) else {
+                    return text.to_string();
+                };
+
+                let mut output = String::new();
+                let mut current_code = None;
+                for (region, op) in ScopeRegionIterator::new(&ops, line) {
+                    if stack.apply(op).is_err() {
+                        continue;
+                    }
+                    let region = region.trim_end_matches(['\n', '\r']);
+                    if region.is_empty() {
+                        continue;
+                    }
+                    let code = Self::ansi_code_for(&stack);
+                    if code != current_code {
+                        output.push_str("\x1b[0m");
+                        if let Some(code) = code {
+                            output.push_str(&format!("\x1b[{}m", code));
+                        }
+                        current_code = code;
+                    }
+                    output.push_str(region);
+                }
+                if current_code.is_some() {
+                    output.push_str("\x1b[0m");
+                }
+                output
+            })
+            .collect()
+    }
+
+    /// ANSI code for the innermost scope with a known color
+    fn ansi_code_for(stack: &ScopeStack) -> Option<&'static str> {
+        stack.as_slice().iter().rev().find_map(|scope| {
+            let name = scope.build_string();
+            ANSI_SCOPES
+                .iter()
+                .find(|(prefix, _)| {
+                    name == *prefix || name.starts_with(&format!("{}.", prefix))
+                })
+                .map(|(_, code)| *code)
+        })
+    }
+
+    /// Highlighted HTML without the surrounding `<pre>`
+    fn highlight_inner(&self, code: &str, language: &str) -> String {
         // Get the syntax for the specified language
         let syntax = self.find_syntax_by_name(language)
             .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
 
-        // Use a dark theme that works well with terminal aesthetics
-        let theme = &self.theme_set.themes["base16-ocean.dark"];
-        
-        // Use the simpler API for highlighting
-        match highlighted_html_for_string(code, &self.syntax_set, syntax, theme) {
-            Ok(html) => html,
-            Err(_) => {
-                // Fallback to plain text if highlighting fails
-                html_escape(code)
+        let mut generator =
+            ClassedHTMLGenerator::new_with_class_style(syntax, &self.syntax_set, CLASS_STYLE);
+        let highlighted = LinesWithEndings::from(code)
+            .try_for_each(|line| generator.parse_html_for_line_which_includes_newline(line));
+
+        match highlighted {
+            Ok(()) => generator.finalize(),
+            // Fallback to plain text if highlighting fails
+            Err(_) => html_escape(code),
+        }
+    }
+
+    /// Check whether a fragment contains markup but no text
+    pub fn is_only_tags(fragment: &str) -> bool {
+        let mut in_tag = false;
+        fragment.chars().all(|c| match c {
+            '<' => {
+                in_tag = true;
+                true
+            }
+            '>' => {
+                in_tag = false;
+                true
             }
+            _ => in_tag,
+        })
+    }
+
+    /// Names of all available code themes, sorted
+    pub fn theme_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.theme_set.themes.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Check whether a code theme with the given name exists
+    pub fn has_theme(&self, name: &str) -> bool {
+        self.theme_set.themes.contains_key(name)
+    }
+
+    /// Generate the stylesheet for the given code theme
+    ///
+    /// Returns None if the theme does not exist or cannot be converted.
+    pub fn theme_css(&self, name: &str) -> Option<String> {
+        let theme = self.theme_set.themes.get(name)?;
+        let mut css = css_for_theme_with_class_style(theme, CLASS_STYLE).ok()?;
+
+        // Code blocks take the theme background over the markdown `pre` style
+        if let Some(background) = theme.settings.background {
+            css.push_str(&format!(
+                "\n.markdown-content pre.syn-code {{ background-color: #{:02x}{:02x}{:02x}; }}\n",
+                background.r, background.g, background.b
+            ));
         }
+
+        Some(css)
     }
 
     /// Find syntax by language name with fallback logic
@@ -60,12 +264,13 @@ impl SyntaxHighlighter {
             "js" | "javascript" => self.syntax_set.find_syntax_by_name("JavaScript"),
             "ts" | "typescript" => self.syntax_set.find_syntax_by_name("TypeScript"),
             "py" | "python" => self.syntax_set.find_syntax_by_name("Python"),
-            "sh" | "bash" | "shell" => self.syntax_set.find_syntax_by_name("Bash"),
+            "sh" | "bash" | "shell" | "zzhack" => self.syntax_set.find_syntax_by_name("Bourne Again Shell (bash)"),
             "html" => self.syntax_set.find_syntax_by_name("HTML"),
             "css" => self.syntax_set.find_syntax_by_name("CSS"),
             "json" => self.syntax_set.find_syntax_by_name("JSON"),
             "yaml" | "yml" => self.syntax_set.find_syntax_by_name("YAML"),
             "toml" => self.syntax_set.find_syntax_by_name("TOML"),
+            "diff" | "patch" => self.syntax_set.find_syntax_by_name("Diff"),
             "md" | "markdown" => self.syntax_set.find_syntax_by_name("Markdown"),
             "xml" => self.syntax_set.find_syntax_by_name("XML"),
             "sql" => self.syntax_set.find_syntax_by_name("SQL"),
@@ -125,4 +330,96 @@ mod tests {
         // Should still return the code (plain text highlighting)
         assert!(highlighted.contains("some random text"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_highlighting_uses_classes() {
+        let highlighter = SyntaxHighlighter::new();
+        let highlighted = highlighter.highlight_code("let x = 1;", "rust");
+
+        assert!(highlighted.starts_with("<pre class=\"syn-code\">"));
+        assert!(highlighted.contains("class=\"syn-"));
+        assert!(!highlighted.contains("style="));
+    }
+
+    #[test]
+    fn test_theme_css_includes_bundled_themes() {
+        let highlighter = SyntaxHighlighter::new();
+
+        assert!(highlighter.theme_css(DEFAULT_CODE_THEME).is_some());
+        assert!(highlighter.theme_css("zzhack-green").is_some());
+        assert!(highlighter.theme_css("no-such-theme").is_none());
+    }
+
+    #[test]
+    fn test_highlight_lines_balances_spans() {
+        let highlighter = SyntaxHighlighter::shared();
+        let lines = highlighter.highlight_lines("/* a\nb */\nlet x = 1;\n", "rust");
+
+        assert_eq!(lines.len(), 3);
+        for line in &lines {
+            assert_eq!(line.matches("<span").count(), line.matches("</span>").count());
+        }
+        assert!(lines[1].contains("*/"));
+        assert!(lines[1].contains("syn-comment"));
+    }
+
+    #[test]
+    fn test_highlight_ansi_lines() {
+        let highlighter = SyntaxHighlighter::shared();
+        let lines = highlighter.highlight_ansi_lines("// hi\nlet s = \"x\";\n", "rust");
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("\x1b[0m\x1b[90m//"));
+        assert!(lines[1].contains("\x1b[35mlet"));
+        assert!(lines[1].contains("\x1b[32m\"x\""));
+        assert!(lines[0].ends_with("\x1b[0m"));
+    }
+
+    #[test]
+    fn test_aliases_resolve_in_precompiled_set() {
+        let highlighter = SyntaxHighlighter::shared();
+
+        for alias in ["rs", "js", "py", "sh", "bash", "html", "css", "json", "yml", "md"] {
+            assert!(
+                highlighter.find_syntax_by_name(alias).is_some(),
+                "alias '{}' did not resolve",
+                alias
+            );
+        }
+        assert!(std::ptr::eq(highlighter, SyntaxHighlighter::shared()));
+    }
+
+    /// Compare rendering with a fresh highlighter per block (what every
+    /// code block used to pay) against the shared one, once it is loaded.
+    /// Run with:
+    /// `cargo test --release -- --ignored --nocapture bench_shared_highlighter`
+    #[test]
+    #[ignore]
+    fn bench_shared_highlighter() {
+        use std::time::Instant;
+
+        const RUNS: u32 = 20;
+        let code = "fn main() {\n    println!(\"Hello, world!\");\n}\n";
+
+        let start = Instant::now();
+        for _ in 0..RUNS {
+            SyntaxHighlighter::new().highlight_code(code, "rust");
+        }
+        let fresh = start.elapsed();
+
+        let start = Instant::now();
+        let shared = SyntaxHighlighter::shared();
+        let load = start.elapsed();
+        let start = Instant::now();
+        for _ in 0..RUNS {
+            shared.highlight_code(code, "rust");
+        }
+        let rendered = start.elapsed();
+
+        println!(
+            "{} blocks: fresh highlighter {:?}, shared highlighter {:?} after loading it once in {:?}",
+            RUNS, fresh, rendered, load
+        );
+        assert!(load + rendered < fresh);
+    }
+}
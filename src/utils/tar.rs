@@ -0,0 +1,227 @@
+// Tar Archives
+// Minimal ustar reader and writer for exporting and importing files
+
+const BLOCK: usize = 512;
+
+/// A file or directory in an archive
+#[derive(Debug, Clone, PartialEq)]
+pub struct TarEntry {
+    /// Path relative to the archive root, without a trailing slash
+    pub path: String,
+    pub directory: bool,
+    pub content: Vec<u8>,
+}
+
+/// Write `entries` as a ustar archive
+pub fn write_tar(entries: &[TarEntry]) -> Vec<u8> {
+    let mut archive = Vec::new();
+    for entry in entries {
+        let mut header = [0u8; BLOCK];
+        let name = if entry.directory {
+            format!("{}/", entry.path)
+        } else {
+            entry.path.clone()
+        };
+        let (prefix, name) = split_name(&name);
+        write_field(&mut header[0..100], name.as_bytes());
+        let mode: &[u8] = if entry.directory {
+            b"0000755"
+        } else {
+            b"0000644"
+        };
+        write_field(&mut header[100..108], mode);
+        write_field(&mut header[108..116], b"0000000");
+        write_field(&mut header[116..124], b"0000000");
+        let size = if entry.directory {
+            0
+        } else {
+            entry.content.len()
+        };
+        write_field(&mut header[124..136], format!("{:011o}", size).as_bytes());
+        write_field(&mut header[136..148], b"00000000000");
+        header[156] = if entry.directory { b'5' } else { b'0' };
+        write_field(&mut header[257..263], b"ustar\0");
+        write_field(&mut header[263..265], b"00");
+        write_field(&mut header[345..500], prefix.as_bytes());
+
+        // The checksum is computed with its own field read as spaces
+        header[148..156].fill(b' ');
+        let checksum: u32 = header.iter().map(|&byte| byte as u32).sum();
+        write_field(
+            &mut header[148..156],
+            format!("{:06o}\0 ", checksum).as_bytes(),
+        );
+
+        archive.extend_from_slice(&header);
+        if !entry.directory {
+            archive.extend_from_slice(&entry.content);
+            archive.resize(archive.len().next_multiple_of(BLOCK), 0);
+        }
+    }
+    // Two zero blocks mark the end of the archive
+    archive.resize(archive.len() + 2 * BLOCK, 0);
+    archive
+}
+
+/// Read the files and directories of a ustar or plain tar archive
+///
+/// Links and other special entries are skipped.
+pub fn read_tar(archive: &[u8]) -> Result<Vec<TarEntry>, String> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    while offset + BLOCK <= archive.len() {
+        let header = &archive[offset..offset + BLOCK];
+        if header.iter().all(|&byte| byte == 0) {
+            break;
+        }
+        let stored = parse_octal(&header[148..156])?;
+        let checksum: u64 = header
+            .iter()
+            .enumerate()
+            .map(|(i, &byte)| {
+                if (148..156).contains(&i) {
+                    b' ' as u64
+                } else {
+                    byte as u64
+                }
+            })
+            .sum();
+        if stored != checksum {
+            return Err(format!("bad header checksum at byte {}", offset));
+        }
+
+        let mut path = read_field(&header[0..100]);
+        if &header[257..262] == b"ustar" {
+            let prefix = read_field(&header[345..500]);
+            if !prefix.is_empty() {
+                path = format!("{}/{}", prefix, path);
+            }
+        }
+        let truncated = || format!("truncated archive: {} ends early", path);
+        let size = usize::try_from(parse_octal(&header[124..136])?).map_err(|_| truncated())?;
+        let start = offset + BLOCK;
+        let end = start
+            .checked_add(size)
+            .filter(|&end| end <= archive.len())
+            .ok_or_else(truncated)?;
+        // The data is padded to a whole block; the last one may be cut short
+        let next = size
+            .checked_next_multiple_of(BLOCK)
+            .and_then(|padded| start.checked_add(padded))
+            .ok_or_else(truncated)?;
+
+        let path = path
+            .trim_start_matches("./")
+            .trim_end_matches('/')
+            .to_string();
+        match header[156] {
+            b'5' => entries.push(TarEntry {
+                path,
+                directory: true,
+                content: Vec::new(),
+            }),
+            b'0' | 0 => entries.push(TarEntry {
+                path,
+                directory: false,
+                content: archive[start..end].to_vec(),
+            }),
+            _ => {}
+        }
+        offset = next;
+    }
+    Ok(entries)
+}
+
+/// Split a long name into the ustar prefix and name fields at a slash
+fn split_name(name: &str) -> (&str, &str) {
+    if name.len() <= 100 {
+        return ("", name);
+    }
+    let search = name.trim_end_matches('/');
+    search
+        .char_indices()
+        .filter(|&(i, c)| c == '/' && i <= 155 && name.len() - i - 1 <= 100)
+        .map(|(i, _)| (&name[..i], &name[i + 1..]))
+        .next()
+        .unwrap_or(("", name))
+}
+
+fn write_field(field: &mut [u8], value: &[u8]) {
+    let len = value.len().min(field.len());
+    field[..len].copy_from_slice(&value[..len]);
+}
+
+fn read_field(field: &[u8]) -> String {
+    let end = field
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+fn parse_octal(field: &[u8]) -> Result<u64, String> {
+    let text = read_field(field);
+    let text = text.trim_matches(|c: char| c == ' ' || c == '\0');
+    if text.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(text, 8).map_err(|_| format!("invalid number in header: {}", text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let long_path = format!("{}/notes.md", "nested".repeat(20));
+        let entries = vec![
+            TarEntry {
+                path: "drafts".to_string(),
+                directory: true,
+                content: Vec::new(),
+            },
+            TarEntry {
+                path: "drafts/hello.md".to_string(),
+                directory: false,
+                content: b"# Hello\n".to_vec(),
+            },
+            TarEntry {
+                path: long_path,
+                directory: false,
+                content: vec![b'x'; 700],
+            },
+        ];
+        let archive = write_tar(&entries);
+        assert_eq!(archive.len() % BLOCK, 0);
+        assert_eq!(&archive[257..262], b"ustar");
+        assert_eq!(read_tar(&archive).unwrap(), entries);
+
+        let mut corrupt = archive.clone();
+        corrupt[0] = b'X';
+        assert!(read_tar(&corrupt).is_err());
+    }
+
+    #[test]
+    fn test_size_past_end() {
+        for size in ["77777777777", "10000"] {
+            let mut archive = write_tar(&[TarEntry {
+                path: "big.md".to_string(),
+                directory: false,
+                content: b"small".to_vec(),
+            }]);
+            let header = &mut archive[..BLOCK];
+            header[124..136].fill(0);
+            write_field(&mut header[124..136], size.as_bytes());
+            header[148..156].fill(b' ');
+            let checksum: u32 = header.iter().map(|&byte| byte as u32).sum();
+            write_field(
+                &mut header[148..156],
+                format!("{:06o}\0 ", checksum).as_bytes(),
+            );
+            assert!(read_tar(&archive)
+                .unwrap_err()
+                .starts_with("truncated archive"));
+        }
+    }
+}
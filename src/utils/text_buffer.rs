@@ -0,0 +1,251 @@
+// Text Buffer
+// Line-based text with a cursor, edited by the full-screen editor
+
+/// Columns a tab advances to a multiple of
+const TAB_WIDTH: usize = 4;
+
+/// Lines of text and a cursor in them
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextBuffer {
+    lines: Vec<String>,
+    /// Cursor line
+    row: usize,
+    /// Cursor position in the line, in characters
+    column: usize,
+    /// Column to return to when moving through shorter lines
+    preferred_column: usize,
+    modified: bool,
+    /// Counts edits, so views can tell when the text changed
+    revision: u64,
+}
+
+impl TextBuffer {
+    pub fn new(text: &str) -> Self {
+        Self {
+            lines: text.split('\n').map(str::to_string).collect(),
+            row: 0,
+            column: 0,
+            preferred_column: 0,
+            modified: false,
+            revision: 0,
+        }
+    }
+
+    /// The buffer's text, lines joined with newlines
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Cursor line and character column
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.column)
+    }
+
+    /// Whether the text changed since it was loaded or last saved
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.modified = false;
+    }
+
+    /// Screen column of a character column, with tabs expanded
+    pub fn display_column(&self, row: usize, column: usize) -> usize {
+        self.lines[row]
+            .chars()
+            .take(column)
+            .fold(0, |width, ch| match ch {
+                '\t' => (width / TAB_WIDTH + 1) * TAB_WIDTH,
+                _ => width + 1,
+            })
+    }
+
+    /// Insert text at the cursor, leaving the cursor after it
+    pub fn insert(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n");
+        let line = &mut self.lines[self.row];
+        let index = byte_index(line, self.column);
+        let tail = line.split_off(index);
+
+        let mut inserted = text.split('\n');
+        if let Some(first) = inserted.next() {
+            line.push_str(first);
+        }
+        for next in inserted {
+            self.row += 1;
+            self.lines.insert(self.row, next.to_string());
+        }
+        let line = &mut self.lines[self.row];
+        self.column = line.chars().count();
+        line.push_str(&tail);
+        self.preferred_column = self.column;
+        self.modified = true;
+        self.revision += 1;
+    }
+
+    /// Delete the character before the cursor, joining lines at a line start
+    pub fn backspace(&mut self) {
+        if self.column > 0 {
+            self.column -= 1;
+            let line = &mut self.lines[self.row];
+            line.remove(byte_index(line, self.column));
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.column = self.lines[self.row].chars().count();
+            self.lines[self.row].push_str(&line);
+        } else {
+            return;
+        }
+        self.preferred_column = self.column;
+        self.modified = true;
+        self.revision += 1;
+    }
+
+    /// Delete the character under the cursor, joining lines at a line end
+    pub fn delete(&mut self) {
+        let line = &mut self.lines[self.row];
+        if self.column < line.chars().count() {
+            line.remove(byte_index(line, self.column));
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        } else {
+            return;
+        }
+        self.modified = true;
+        self.revision += 1;
+    }
+
+    pub fn move_left(&mut self) {
+        if self.column > 0 {
+            self.column -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.column = self.line_length();
+        }
+        self.preferred_column = self.column;
+    }
+
+    pub fn move_right(&mut self) {
+        if self.column < self.line_length() {
+            self.column += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.column = 0;
+        }
+        self.preferred_column = self.column;
+    }
+
+    /// Move up (negative) or down by `count` lines, staying in the buffer
+    pub fn move_lines(&mut self, count: isize) {
+        self.row = self
+            .row
+            .saturating_add_signed(count)
+            .min(self.lines.len() - 1);
+        self.column = self.preferred_column.min(self.line_length());
+    }
+
+    pub fn move_home(&mut self) {
+        self.column = 0;
+        self.preferred_column = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.column = self.line_length();
+        self.preferred_column = self.column;
+    }
+
+    /// Move the cursor to the next match of `query` after it, wrapping
+    /// around the end of the buffer
+    ///
+    /// Returns whether there was a match.
+    pub fn find_next(&mut self, query: &str) -> bool {
+        if query.is_empty() {
+            return false;
+        }
+        let count = self.lines.len();
+        // The cursor's line is searched twice: after the cursor, then before
+        for step in 0..=count {
+            let row = (self.row + step) % count;
+            let line = &self.lines[row];
+            let start = if step == 0 {
+                byte_index(line, self.column + 1).min(line.len())
+            } else {
+                0
+            };
+            if let Some(found) = line[start..].find(query) {
+                self.row = row;
+                self.column = line[..start + found].chars().count();
+                self.preferred_column = self.column;
+                return true;
+            }
+        }
+        false
+    }
+
+    fn line_length(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+}
+
+/// Byte offset of a character column, or the line's end past it
+fn byte_index(line: &str, column: usize) -> usize {
+    line.char_indices()
+        .nth(column)
+        .map_or(line.len(), |(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editing() {
+        let mut buffer = TextBuffer::new("fn main() {\n}\n");
+        assert_eq!(buffer.lines().len(), 3);
+        buffer.move_end();
+        buffer.insert("\n    println!(\"héllo\");");
+        assert_eq!(buffer.cursor(), (1, 22));
+        buffer.backspace();
+        buffer.move_lines(1);
+        assert_eq!(buffer.cursor(), (2, 1));
+        buffer.move_home();
+        buffer.backspace();
+        assert_eq!(buffer.cursor(), (1, 21));
+        buffer.delete();
+        assert_eq!(buffer.text(), "fn main() {\n    println!(\"héllo\")\n");
+        assert!(buffer.is_modified());
+        assert_eq!(buffer.revision(), 4);
+        buffer.move_lines(-1);
+        assert_eq!(buffer.revision(), 4);
+
+        let mut tabs = TextBuffer::new("\tx\ty");
+        assert_eq!(tabs.display_column(0, 3), 8);
+        tabs.move_end();
+        tabs.move_lines(-1);
+        assert_eq!(tabs.cursor(), (0, 4));
+    }
+
+    #[test]
+    fn test_find_next_wraps() {
+        let mut buffer = TextBuffer::new("one two\ntwo one\n");
+        assert!(buffer.find_next("two"));
+        assert_eq!(buffer.cursor(), (0, 4));
+        assert!(buffer.find_next("two"));
+        assert_eq!(buffer.cursor(), (1, 0));
+        assert!(buffer.find_next("two"));
+        assert_eq!(buffer.cursor(), (0, 4));
+        assert!(!buffer.find_next("three"));
+        assert_eq!(buffer.cursor(), (0, 4));
+    }
+}
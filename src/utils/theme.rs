@@ -0,0 +1,176 @@
+// Theme Definitions
+// Named color palettes loaded from data/themes/*.json and app.json
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+// Generated by build.rs from data/themes/*.json
+include!(concat!(env!("OUT_DIR"), "/themes.rs"));
+
+/// CSS variable names for the 16 ANSI colors, in SGR order (30-37, 90-97)
+pub const ANSI_COLOR_NAMES: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "bright-black",
+    "bright-red",
+    "bright-green",
+    "bright-yellow",
+    "bright-blue",
+    "bright-magenta",
+    "bright-cyan",
+    "bright-white",
+];
+
+/// A named terminal color palette
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Theme {
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Whether this is a dark palette (used for `prose-invert` and fallbacks)
+    #[serde(default = "default_dark")]
+    pub dark: bool,
+    pub background: String,
+    pub foreground: String,
+    pub prompt: String,
+    pub error: String,
+    pub info: String,
+    /// Defaults to the prompt color
+    #[serde(default)]
+    pub success: Option<String>,
+    /// Defaults to bright black
+    #[serde(default)]
+    pub border: Option<String>,
+    /// Background for headers and code blocks, defaults to the background
+    #[serde(default)]
+    pub header: Option<String>,
+    /// The 16 ANSI colors: black, red, green, yellow, blue, magenta, cyan,
+    /// white, followed by their bright variants
+    pub ansi: Vec<String>,
+    /// Name of the syntect theme used for code blocks
+    pub syntax_theme: String,
+}
+
+fn default_dark() -> bool {
+    true
+}
+
+impl Theme {
+    /// Color of the given ANSI index, falling back to the foreground
+    pub fn ansi_color(&self, index: usize) -> &str {
+        self.ansi
+            .get(index)
+            .map(|color| color.as_str())
+            .unwrap_or(&self.foreground)
+    }
+
+    /// CSS custom property declarations for this palette
+    ///
+    /// The result is suitable for a `style` attribute and overrides the
+    /// `--terminal-*` and `--ansi-*` variables used throughout input.css.
+    pub fn css_variables(&self) -> String {
+        let mut vars = vec![
+            ("terminal-bg".to_string(), self.background.as_str()),
+            ("terminal-text".to_string(), self.foreground.as_str()),
+            ("terminal-prompt".to_string(), self.prompt.as_str()),
+            (
+                "terminal-success".to_string(),
+                self.success.as_deref().unwrap_or(&self.prompt),
+            ),
+            ("terminal-error".to_string(), self.error.as_str()),
+            ("terminal-info".to_string(), self.info.as_str()),
+            (
+                "terminal-border".to_string(),
+                self.border.as_deref().unwrap_or(self.ansi_color(8)),
+            ),
+            (
+                "terminal-header".to_string(),
+                self.header.as_deref().unwrap_or(&self.background),
+            ),
+        ];
+        for (index, name) in ANSI_COLOR_NAMES.iter().enumerate() {
+            vars.push((format!("ansi-{}", name), self.ansi_color(index)));
+        }
+
+        vars.iter()
+            .map(|(name, value)| format!("--{}: {};", name, value))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+static PARSED_THEMES: OnceLock<BTreeMap<String, Theme>> = OnceLock::new();
+
+/// The bundled themes, parsed on first use
+fn bundled_themes() -> &'static BTreeMap<String, Theme> {
+    PARSED_THEMES.get_or_init(|| {
+        BUNDLED_THEMES
+            .iter()
+            .filter_map(|(name, json)| {
+                serde_json::from_str::<Theme>(json)
+                    .ok()
+                    .map(|theme| (name.to_string(), theme))
+            })
+            .collect()
+    })
+}
+
+/// All available themes: bundled data/themes/*.json files, overridden by
+/// themes of the same name defined under "themes" in app.json
+pub fn available_themes(configured: &BTreeMap<String, Theme>) -> BTreeMap<String, Theme> {
+    let mut themes = bundled_themes().clone();
+    themes.extend(
+        configured
+            .iter()
+            .map(|(name, theme)| (name.clone(), theme.clone())),
+    );
+    themes
+}
+
+/// The theme called `name`, preferring one configured in app.json
+pub fn find_theme<'a>(configured: &'a BTreeMap<String, Theme>, name: &str) -> Option<&'a Theme> {
+    configured.get(name).or_else(|| bundled_themes().get(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_themes_parse() {
+        let themes = available_themes(&BTreeMap::new());
+        assert_eq!(themes.len(), BUNDLED_THEMES.len());
+        assert!(themes.contains_key("dark"));
+        assert!(themes.contains_key("light"));
+        assert!(themes.values().all(|theme| theme.ansi.len() == 16));
+
+        let mut configured = BTreeMap::new();
+        let mut custom = themes["light"].clone();
+        custom.background = "#123456".to_string();
+        configured.insert("dark".to_string(), custom);
+        assert_eq!(
+            find_theme(&configured, "dark").unwrap().background,
+            "#123456"
+        );
+        assert_eq!(find_theme(&configured, "light"), Some(&themes["light"]));
+        assert_eq!(find_theme(&configured, "missing"), None);
+    }
+
+    #[test]
+    fn test_css_variables_fall_back() {
+        let mut theme = available_themes(&BTreeMap::new())["dark"].clone();
+        theme.success = None;
+        theme.header = None;
+
+        let css = theme.css_variables();
+        assert!(css.contains(&format!("--terminal-success: {};", theme.prompt)));
+        assert!(css.contains(&format!("--terminal-header: {};", theme.background)));
+        assert!(css.contains("--ansi-bright-white:"));
+    }
+}